@@ -0,0 +1,322 @@
+// src/api/email.rs
+
+use crate::api::events::{self, Event};
+use crate::error::{AppError, Result};
+use crate::models::alert::AlertSeverity;
+use crate::utils::fs_utils;
+use lettre::message::{header::ContentType, Mailbox};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Message, SmtpTransport, Transport};
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Upper bound of events listed in one digest; the rest are only counted.
+const MAX_DIGEST_EVENTS: usize = 100;
+/// SMTP connection/command timeout.
+const SMTP_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Transport security of the SMTP connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Unencrypted connection (local relays and test sinks).
+    None,
+    /// Plain connection upgraded with STARTTLS (usually port 587).
+    StartTls,
+    /// TLS from the first byte (usually port 465).
+    Tls,
+}
+
+/// SMTP notification settings (`email.json` in the app data directory).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmailConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    /// SMTP AUTH credentials. Authentication is skipped if no username is set.
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// Sender address, e.g. "Minecraft Server <mc@example.com>".
+    pub from: String,
+    pub recipients: Vec<String>,
+    /// Event types (`Event` variant names) that are mailed.
+    #[serde(default = "default_event_types")]
+    pub event_types: Vec<String>,
+    /// Alerts below this severity are not mailed. Resolutions follow the same rule.
+    #[serde(default = "default_min_severity")]
+    pub min_alert_severity: AlertSeverity,
+    /// Events arriving within this many seconds of the first one are sent as one digest.
+    /// 0 sends every event immediately.
+    #[serde(default = "default_digest_window_secs")]
+    pub digest_window_secs: u64,
+    #[serde(default = "default_subject_prefix")]
+    pub subject_prefix: String,
+}
+
+fn default_event_types() -> Vec<String> {
+    vec!["Alert".to_string(), "ServerCrashed".to_string()]
+}
+
+fn default_min_severity() -> AlertSeverity {
+    AlertSeverity::Warning
+}
+
+fn default_digest_window_secs() -> u64 {
+    300
+}
+
+fn default_subject_prefix() -> String {
+    "[Minecraft Server]".to_string()
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: 587,
+            security: SmtpSecurity::StartTls,
+            username: None,
+            password: None,
+            from: String::new(),
+            recipients: Vec::new(),
+            event_types: default_event_types(),
+            min_alert_severity: default_min_severity(),
+            digest_window_secs: default_digest_window_secs(),
+            subject_prefix: default_subject_prefix(),
+        }
+    }
+}
+
+/// An event waiting in the digest buffer.
+struct QueuedEvent {
+    /// Local time the event was received, formatted for the mail body.
+    received_at: String,
+    summary: String,
+}
+
+/// Mails selected events (alerts, crashes) to a list of recipients.
+///
+/// Events are buffered for `digest_window_secs` after the first one arrives and then sent
+/// as a single message, so an alert storm produces one mail instead of dozens.
+pub struct EmailNotifier {
+    config: RwLock<EmailConfig>,
+    config_path: PathBuf,
+}
+
+impl EmailNotifier {
+    /// Loads the configuration and starts the event subscriber thread.
+    pub fn new(config_path: PathBuf) -> Arc<Self> {
+        let config = match fs_utils::read_file_to_string(&config_path) {
+            Ok(content) => serde_json::from_str::<EmailConfig>(&content).unwrap_or_else(|e| {
+                error!("Email config {} is corrupt, email notifications disabled: {}", config_path.display(), e);
+                EmailConfig::default()
+            }),
+            Err(_) => EmailConfig::default(),
+        };
+        info!("Email notifications {}.", if config.enabled { "enabled" } else { "disabled" });
+
+        let notifier = Arc::new(Self {
+            config: RwLock::new(config),
+            config_path,
+        });
+
+        let receiver = events::subscribe();
+        let worker = notifier.clone();
+        thread::spawn(move || worker.run_digest_loop(receiver));
+        notifier
+    }
+
+    /// Returns the current configuration.
+    pub fn get_config(&self) -> Result<EmailConfig> {
+        self.config
+            .read()
+            .map(|guard| guard.clone())
+            .map_err(|e| AppError::LockError(format!("Failed to lock email config: {}", e)))
+    }
+
+    /// Validates and saves a new configuration.
+    pub fn set_config(&self, config: EmailConfig) -> Result<()> {
+        if config.enabled {
+            build_transport(&config)?;
+            parse_mailboxes(&config)?;
+        }
+        let json = serde_json::to_string_pretty(&config)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize email config: {}", e)))?;
+        fs_utils::write_string_to_file_atomic(&self.config_path, &json)?;
+
+        let mut guard = self
+            .config
+            .write()
+            .map_err(|e| AppError::LockError(format!("Failed to lock email config for writing: {}", e)))?;
+        *guard = config;
+        info!("Saved email notification config.");
+        Ok(())
+    }
+
+    /// Sends a test message immediately using the given (possibly unsaved) settings.
+    pub fn send_test_email(&self, config: EmailConfig) -> Result<()> {
+        info!("Sending test email via {}:{}", config.host, config.port);
+        let body = format!(
+            "This is a test message from the Minecraft server manager.\n\nSMTP server: {}:{} ({:?})\n",
+            config.host, config.port, config.security
+        );
+        send_mail(&config, &format!("{} Test email", config.subject_prefix), body)
+    }
+
+    // --- Digest ---
+
+    /// Receives events, buffers matching ones and flushes the buffer when the window ends.
+    fn run_digest_loop(&self, receiver: events::EventReceiver) {
+        debug!("Email notifier event subscriber started.");
+        let mut queue: Vec<QueuedEvent> = Vec::new();
+        let mut flush_at: Option<Instant> = None;
+
+        loop {
+            let timeout = flush_at
+                .map(|at| at.saturating_duration_since(Instant::now()))
+                .unwrap_or(Duration::from_secs(60));
+
+            match receiver.recv_timeout(timeout) {
+                Ok(event) => match self.get_config() {
+                    // Don't `continue` here: frequent non-matching events must not delay the flush
+                    Ok(config) if config.enabled && accepts(&config, &event) => {
+                        queue.push(QueuedEvent {
+                            received_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                            summary: event.summary(),
+                        });
+                        if flush_at.is_none() {
+                            flush_at = Some(Instant::now() + Duration::from_secs(config.digest_window_secs));
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => error!("{}", e),
+                },
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            if flush_at.map_or(false, |at| at <= Instant::now()) {
+                flush_at = None;
+                self.flush(std::mem::take(&mut queue));
+            }
+        }
+        debug!("Email notifier event subscriber stopped.");
+    }
+
+    /// Sends the buffered events as one message. Failures are logged; the events are dropped
+    /// so a broken SMTP server doesn't make the buffer grow forever.
+    fn flush(&self, queue: Vec<QueuedEvent>) {
+        if queue.is_empty() {
+            return;
+        }
+        let config = match self.get_config() {
+            Ok(config) if config.enabled => config,
+            Ok(_) => return, // Disabled while the digest window was open
+            Err(e) => {
+                error!("{}", e);
+                return;
+            }
+        };
+
+        let subject = if queue.len() == 1 {
+            format!("{} {}", config.subject_prefix, queue[0].summary)
+        } else {
+            format!("{} {} notifications", config.subject_prefix, queue.len())
+        };
+        let mut body = String::new();
+        for event in queue.iter().take(MAX_DIGEST_EVENTS) {
+            body.push_str(&format!("{}  {}\n", event.received_at, event.summary));
+        }
+        if queue.len() > MAX_DIGEST_EVENTS {
+            body.push_str(&format!("... and {} more\n", queue.len() - MAX_DIGEST_EVENTS));
+        }
+
+        match send_mail(&config, &subject, body) {
+            Ok(()) => info!("Sent email notification with {} events.", queue.len()),
+            Err(e) => error!("Failed to send email notification ({} events dropped): {}", queue.len(), e),
+        }
+    }
+}
+
+/// Returns true if the event should be mailed under the given configuration.
+fn accepts(config: &EmailConfig, event: &Event) -> bool {
+    let event_type = event.type_name();
+    if !config.event_types.iter().any(|t| t == "*" || t.eq_ignore_ascii_case(event_type)) {
+        return false;
+    }
+    match event {
+        Event::Alert(alert) => alert.severity >= config.min_alert_severity,
+        _ => true,
+    }
+}
+
+/// Builds an SMTP transport for the configured security mode and credentials.
+fn build_transport(config: &EmailConfig) -> Result<SmtpTransport> {
+    if config.host.trim().is_empty() {
+        return Err(AppError::ConfigError("SMTP host cannot be empty".to_string()));
+    }
+    let builder = match config.security {
+        SmtpSecurity::None => SmtpTransport::builder_dangerous(config.host.trim()),
+        SmtpSecurity::StartTls => SmtpTransport::starttls_relay(config.host.trim())
+            .map_err(|e| AppError::ConfigError(format!("Invalid SMTP host '{}': {}", config.host, e)))?,
+        SmtpSecurity::Tls => SmtpTransport::relay(config.host.trim())
+            .map_err(|e| AppError::ConfigError(format!("Invalid SMTP host '{}': {}", config.host, e)))?,
+    };
+    let mut builder = builder.port(config.port).timeout(Some(SMTP_TIMEOUT));
+    if let Some(username) = config.username.as_deref().filter(|u| !u.is_empty()) {
+        builder = builder.credentials(Credentials::new(
+            username.to_string(),
+            config.password.clone().unwrap_or_default(),
+        ));
+    }
+    Ok(builder.build())
+}
+
+/// Parses the sender and recipient addresses.
+fn parse_mailboxes(config: &EmailConfig) -> Result<(Mailbox, Vec<Mailbox>)> {
+    let from = config
+        .from
+        .parse::<Mailbox>()
+        .map_err(|e| AppError::ConfigError(format!("Invalid sender address '{}': {}", config.from, e)))?;
+    if config.recipients.is_empty() {
+        return Err(AppError::ConfigError("At least one email recipient is required".to_string()));
+    }
+    let recipients = config
+        .recipients
+        .iter()
+        .map(|r| {
+            r.parse::<Mailbox>()
+                .map_err(|e| AppError::ConfigError(format!("Invalid recipient address '{}': {}", r, e)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok((from, recipients))
+}
+
+/// Sends one plain-text message to all recipients.
+fn send_mail(config: &EmailConfig, subject: &str, body: String) -> Result<()> {
+    let (from, recipients) = parse_mailboxes(config)?;
+    let mut builder = Message::builder().from(from).subject(subject);
+    for recipient in recipients {
+        builder = builder.to(recipient);
+    }
+    let message = builder
+        .header(ContentType::TEXT_PLAIN)
+        .body(body)
+        .map_err(|e| AppError::ConfigError(format!("Failed to build email: {}", e)))?;
+
+    let transport = build_transport(config)?;
+    transport.send(&message).map_err(|e| {
+        warn!("SMTP delivery via {}:{} failed: {}", config.host, config.port, e);
+        AppError::ServerError(format!("Failed to send email: {}", e))
+    })?;
+    Ok(())
+}
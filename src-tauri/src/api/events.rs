@@ -1,4 +1,5 @@
 use crate::error::AppError; // Use AppError directly
+use crate::models::alert::{Alert, AlertState};
 use crate::models::log_entry::LogEntry;
 use crate::models::metrics::MetricsData;
 use crate::models::server_status::ServerStatus;
@@ -21,6 +22,10 @@ pub type EventReceiver = std::sync::mpsc::Receiver<Event>;
 /// Global static storage for the event sender. Uses RwLock for safe access.
 static EVENT_SENDER: Lazy<RwLock<Option<EventSender>>> = Lazy::new(|| RwLock::new(None));
 
+/// Additional in-process consumers of the event stream (exporters, notifiers).
+/// Each subscriber gets its own channel; disconnected subscribers are dropped on the next emit.
+static SUBSCRIBERS: Lazy<RwLock<Vec<EventSender>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
 /// Defines the different types of events that can occur within the backend.
 /// These events are sent to the internal MPSC channel and then bridged to Tauri.
 /// `Serialize` is crucial for sending to the frontend via Tauri.
@@ -32,8 +37,8 @@ pub enum Event {
     StatusChanged(ServerStatus),
     /// A log message was generated.
     Log(LogEntry),
-    /// An alert condition was met (could be a specific LogEntry or custom struct).
-    Alert(String), // Simple string alert for now
+    /// An alert rule started firing or resolved.
+    Alert(Alert),
     /// Performance metrics were updated.
     MetricsUpdated(MetricsData),
     /// A player joined the Minecraft server. Contains player name.
@@ -48,6 +53,10 @@ pub enum Event {
     ServerStopping,
     /// Server process has stopped cleanly.
     ServerStopped,
+    /// Server process terminated while it was Starting or Running. Contains a short reason.
+    ServerCrashed(String),
+    /// A restart (stop + start) was requested.
+    ServerRestarting,
     /// A command was sent to the server process. Includes success status and output if available.
     CommandExecuted {
         command: String,
@@ -56,8 +65,12 @@ pub enum Event {
     },
     /// Backup process has started.
     BackupStarted,
-    /// Backup process completed. Contains Result to indicate success or failure message.
-    BackupCompleted(Result<(), String>),
+    /// Backup process completed. Contains Result to indicate success or failure message,
+    /// and the size of the created archive if known.
+    BackupCompleted {
+        result: Result<(), String>,
+        size_bytes: Option<u64>,
+    },
     /// General application error occurred that the frontend should be aware of.
     Error(String),
     /// Notifies the frontend about the current EULA acceptance status.
@@ -67,6 +80,69 @@ pub enum Event {
     // Add more specific event types as your application evolves
 }
 
+impl Event {
+    /// Returns the variant name, identical to the serialized `type` tag.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Event::StatusChanged(_) => "StatusChanged",
+            Event::Log(_) => "Log",
+            Event::Alert(_) => "Alert",
+            Event::MetricsUpdated(_) => "MetricsUpdated",
+            Event::PlayerJoined(_) => "PlayerJoined",
+            Event::PlayerLeft(_) => "PlayerLeft",
+            Event::ServerStarting => "ServerStarting",
+            Event::ServerStarted => "ServerStarted",
+            Event::ServerStopping => "ServerStopping",
+            Event::ServerStopped => "ServerStopped",
+            Event::ServerCrashed(_) => "ServerCrashed",
+            Event::ServerRestarting => "ServerRestarting",
+            Event::CommandExecuted { .. } => "CommandExecuted",
+            Event::BackupStarted => "BackupStarted",
+            Event::BackupCompleted { .. } => "BackupCompleted",
+            Event::Error(_) => "Error",
+            Event::EulaStatus(_) => "EulaStatus",
+            Event::ProgressUpdate { .. } => "ProgressUpdate",
+        }
+    }
+
+    /// Returns a one-line human-readable description, used by outbound notifications.
+    pub fn summary(&self) -> String {
+        match self {
+            Event::StatusChanged(status) => format!("Server status changed to {}", status),
+            Event::Log(entry) => format!("[{}] {}: {}", entry.level, entry.source, entry.message),
+            Event::Alert(alert) => match alert.state {
+                AlertState::Firing => format!("Alert [{}]: {}", alert.severity, alert.message),
+                AlertState::Resolved => format!("Alert resolved: {}", alert.message),
+            },
+            Event::MetricsUpdated(metrics) => format!(
+                "CPU {:.1}%, memory {} MB, {} players",
+                metrics.cpu_usage,
+                metrics.memory_usage / (1024 * 1024),
+                metrics.player_count
+            ),
+            Event::PlayerJoined(name) => format!("{} joined the server", name),
+            Event::PlayerLeft(name) => format!("{} left the server", name),
+            Event::ServerStarting => "Server is starting".to_string(),
+            Event::ServerStarted => "Server started".to_string(),
+            Event::ServerStopping => "Server is stopping".to_string(),
+            Event::ServerStopped => "Server stopped".to_string(),
+            Event::ServerCrashed(reason) => format!("Server crashed: {}", reason),
+            Event::ServerRestarting => "Server is restarting".to_string(),
+            Event::CommandExecuted { command, success, .. } => {
+                format!("Command '{}' {}", command, if *success { "executed" } else { "failed" })
+            }
+            Event::BackupStarted => "Backup started".to_string(),
+            Event::BackupCompleted { result: Ok(()), .. } => "Backup completed".to_string(),
+            Event::BackupCompleted { result: Err(e), .. } => format!("Backup failed: {}", e),
+            Event::Error(message) => format!("Error: {}", message),
+            Event::EulaStatus(accepted) => format!("EULA accepted: {}", accepted),
+            Event::ProgressUpdate { task, progress, message } => {
+                format!("{} ({:.0}%): {}", task, progress, message)
+            }
+        }
+    }
+}
+
 /// Sets the global event sender. Should only be called once during application setup.
 pub fn set_event_sender(sender: EventSender) {
     let mut writer = EVENT_SENDER
@@ -88,9 +164,33 @@ fn get_event_sender() -> Option<EventSender> {
         .clone()
 }
 
+/// Registers a new subscriber and returns the receiving end of its channel.
+/// Every event emitted after this call is delivered to the subscriber as well as to the Tauri bridge.
+pub fn subscribe() -> EventReceiver {
+    let (sender, receiver) = channel::<Event>();
+    SUBSCRIBERS
+        .write()
+        .expect("Failed to lock SUBSCRIBERS for writing")
+        .push(sender);
+    debug!("New internal event subscriber registered.");
+    receiver
+}
+
+/// Delivers an event to all subscribers, removing those whose receiver was dropped.
+fn notify_subscribers(event: &Event) {
+    let mut subscribers = SUBSCRIBERS
+        .write()
+        .expect("Failed to lock SUBSCRIBERS for writing");
+    if subscribers.is_empty() {
+        return;
+    }
+    subscribers.retain(|subscriber| subscriber.send(event.clone()).is_ok());
+}
+
 /// Emits an event onto the internal MPSC channel.
 /// Logs a warning if the sender hasn't been set or if sending fails (receiver disconnected).
 pub fn emit_event(event: Event) {
+    notify_subscribers(&event);
     if let Some(sender) = get_event_sender() {
         debug!("Emitting event: {:?}", event); // Log event emission (use trace for production)
         if let Err(SendError(failed_event)) = sender.send(event) {
@@ -161,6 +261,12 @@ pub fn emit_eula_status(accepted: bool) {
     emit_event(Event::EulaStatus(accepted));
 }
 
+/// Emits a crash event and an associated warning log.
+pub fn emit_server_crashed(reason: String, source: String) {
+    emit_event(Event::ServerCrashed(reason.clone()));
+    emit_warn(reason, source);
+}
+
 /// Emits a general application error event based on AppError.
 pub fn emit_app_error(error: &AppError) {
     log::error!("Application Error: {}", error); // Log the error regardless
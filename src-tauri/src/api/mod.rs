@@ -1,3 +1,7 @@
 ﻿mod rest;
 mod websocket;
-pub(crate) mod events;
\ No newline at end of file
+pub(crate) mod email;
+pub(crate) mod events;
+pub(crate) mod mqtt;
+pub(crate) mod prometheus;
+pub(crate) mod webhooks;
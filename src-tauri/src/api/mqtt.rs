@@ -0,0 +1,643 @@
+// src/api/mqtt.rs
+
+use crate::api::events::{self, Event};
+use crate::app_state::AppState;
+use crate::commands::command_executor::CommandExecutor;
+use crate::error::{AppError, Result};
+use crate::models::server_status::ServerStatus;
+use crate::utils::fs_utils;
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Capacity of the client request queue between publishers and the connection thread.
+const REQUEST_QUEUE_CAPACITY: usize = 64;
+/// Delay before reconnecting after a connection error.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// Commands waiting for the command worker; further commands are rejected.
+const COMMAND_QUEUE_CAPACITY: usize = 8;
+/// At most this many commands are accepted per `COMMAND_RATE_WINDOW`.
+const COMMAND_RATE_LIMIT: usize = 10;
+const COMMAND_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// MQTT protocol version spoken to the broker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MqttProtocol {
+    V311,
+    V5,
+}
+
+/// MQTT publisher settings (`mqtt.json` in the app data directory). Disabled by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MqttConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub protocol: MqttProtocol,
+    /// Connect with TLS (using the system's root certificates).
+    #[serde(default)]
+    pub tls: bool,
+    pub client_id: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    /// First topic level. Topics are `<topic_prefix>/<server_name>/<suffix>`.
+    pub topic_prefix: String,
+    pub server_name: String,
+    /// QoS for all publications (0, 1 or 2).
+    #[serde(default)]
+    pub qos: u8,
+    #[serde(default = "default_keep_alive_secs")]
+    pub keep_alive_secs: u64,
+    /// Publish every metrics sample to `<base>/metrics`.
+    #[serde(default = "default_true")]
+    pub publish_metrics: bool,
+    /// Event types (`Event` variant names) published to `<base>/events`. "*" publishes everything.
+    #[serde(default = "default_event_types")]
+    pub event_types: Vec<String>,
+    /// Subscribe to `<base>/command` and execute received commands.
+    #[serde(default)]
+    pub commands_enabled: bool,
+    /// Commands accepted on the command topic, matched against the first word
+    /// (e.g. "say" allows "say hello"). "start", "stop" and "restart" must be listed explicitly.
+    #[serde(default = "default_command_allow_list")]
+    pub command_allow_list: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_keep_alive_secs() -> u64 {
+    30
+}
+
+fn default_event_types() -> Vec<String> {
+    ["ServerStarted", "ServerStopped", "ServerCrashed", "PlayerJoined", "PlayerLeft", "Alert", "BackupCompleted"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+fn default_command_allow_list() -> Vec<String> {
+    vec!["list".to_string(), "say".to_string(), "save-all".to_string()]
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: 1883,
+            protocol: MqttProtocol::V311,
+            tls: false,
+            client_id: "minecraft-server-manager".to_string(),
+            username: None,
+            password: None,
+            topic_prefix: "mclh".to_string(),
+            server_name: "server".to_string(),
+            qos: 0,
+            keep_alive_secs: default_keep_alive_secs(),
+            publish_metrics: true,
+            event_types: default_event_types(),
+            commands_enabled: false,
+            command_allow_list: default_command_allow_list(),
+        }
+    }
+}
+
+impl MqttConfig {
+    /// Topic prefix shared by all topics of this server, e.g. `mclh/survival`.
+    fn base_topic(&self) -> String {
+        format!("{}/{}", self.topic_prefix.trim_matches('/'), self.server_name.trim_matches('/'))
+    }
+
+    fn topic(&self, suffix: &str) -> String {
+        format!("{}/{}", self.base_topic(), suffix)
+    }
+}
+
+/// Connection state reported to the frontend.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MqttStatus {
+    pub connected: bool,
+    pub last_error: Option<String>,
+}
+
+/// Version-independent view of the packets the connection loop cares about.
+enum Incoming {
+    Connected,
+    Publish { topic: String, payload: Vec<u8> },
+}
+
+/// Client handle for either protocol version.
+#[derive(Clone)]
+enum MqttClient {
+    V311(rumqttc::Client),
+    V5(rumqttc::v5::Client),
+}
+
+impl MqttClient {
+    fn publish(&self, topic: String, qos: u8, retain: bool, payload: Vec<u8>) -> std::result::Result<(), String> {
+        match self {
+            MqttClient::V311(client) => client
+                .try_publish(topic, qos_v311(qos), retain, payload)
+                .map_err(|e| e.to_string()),
+            MqttClient::V5(client) => client
+                .try_publish(topic, qos_v5(qos), retain, payload)
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    fn subscribe(&self, topic: String, qos: u8) -> std::result::Result<(), String> {
+        match self {
+            MqttClient::V311(client) => client.try_subscribe(topic, qos_v311(qos)).map_err(|e| e.to_string()),
+            MqttClient::V5(client) => client.try_subscribe(topic, qos_v5(qos)).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn disconnect(&self) {
+        let result = match self {
+            MqttClient::V311(client) => client.try_disconnect().map_err(|e| e.to_string()),
+            MqttClient::V5(client) => client.try_disconnect().map_err(|e| e.to_string()),
+        };
+        if let Err(e) = result {
+            debug!("MQTT disconnect request failed: {}", e);
+        }
+    }
+}
+
+fn qos_v311(qos: u8) -> rumqttc::QoS {
+    match qos {
+        0 => rumqttc::QoS::AtMostOnce,
+        1 => rumqttc::QoS::AtLeastOnce,
+        _ => rumqttc::QoS::ExactlyOnce,
+    }
+}
+
+fn qos_v5(qos: u8) -> rumqttc::v5::mqttbytes::QoS {
+    match qos {
+        0 => rumqttc::v5::mqttbytes::QoS::AtMostOnce,
+        1 => rumqttc::v5::mqttbytes::QoS::AtLeastOnce,
+        _ => rumqttc::v5::mqttbytes::QoS::ExactlyOnce,
+    }
+}
+
+/// Hands accepted commands to the session's command worker, which runs them one at a time.
+/// Owned by the connection thread; the worker stops when it is dropped.
+struct CommandQueue {
+    sender: SyncSender<String>,
+    /// When the commands of the current rate window were accepted.
+    accepted: VecDeque<Instant>,
+}
+
+impl CommandQueue {
+    /// Queues a command unless the rate limit is reached or the queue is full.
+    fn submit(&mut self, command: String) -> std::result::Result<(), &'static str> {
+        let now = Instant::now();
+        while self.accepted.front().is_some_and(|at| now.duration_since(*at) >= COMMAND_RATE_WINDOW) {
+            self.accepted.pop_front();
+        }
+        if self.accepted.len() >= COMMAND_RATE_LIMIT {
+            return Err("Rate limit exceeded, try again later");
+        }
+        match self.sender.try_send(command) {
+            Ok(()) => {
+                self.accepted.push_back(now);
+                Ok(())
+            }
+            Err(TrySendError::Full(_)) => Err("Too many commands queued"),
+            Err(TrySendError::Disconnected(_)) => Err("Command worker stopped"),
+        }
+    }
+}
+
+/// A live connection: the client handle plus the flag that stops its connection thread.
+struct MqttSession {
+    client: MqttClient,
+    config: MqttConfig,
+    shutdown: Arc<AtomicBool>,
+}
+
+/// Publishes metrics, server status, players and selected events to an MQTT broker and
+/// optionally executes allow-listed console commands received on `<base>/command`.
+///
+/// `<base>/status` is retained and doubles as the last-will topic, so subscribers see
+/// `{"online": false}` as soon as the manager disappears without disconnecting.
+pub struct MqttPublisher {
+    state: Arc<AppState>,
+    config: RwLock<MqttConfig>,
+    config_path: PathBuf,
+    session: Mutex<Option<MqttSession>>,
+    status: Arc<Mutex<MqttStatus>>,
+    /// Players currently online, maintained from join/leave events.
+    players: Mutex<BTreeSet<String>>,
+}
+
+impl MqttPublisher {
+    /// Loads the configuration and starts forwarding events. The connection is started by `apply_config`.
+    pub fn new(state: Arc<AppState>, config_path: PathBuf) -> Arc<Self> {
+        let config = match fs_utils::read_file_to_string(&config_path) {
+            Ok(content) => serde_json::from_str::<MqttConfig>(&content).unwrap_or_else(|e| {
+                error!("MQTT config {} is corrupt, MQTT disabled: {}", config_path.display(), e);
+                MqttConfig::default()
+            }),
+            Err(_) => MqttConfig::default(),
+        };
+
+        let publisher = Arc::new(Self {
+            state,
+            config: RwLock::new(config),
+            config_path,
+            session: Mutex::new(None),
+            status: Arc::new(Mutex::new(MqttStatus::default())),
+            players: Mutex::new(BTreeSet::new()),
+        });
+
+        let receiver = events::subscribe();
+        let forwarder = publisher.clone();
+        thread::spawn(move || {
+            debug!("MQTT event subscriber started.");
+            while let Ok(event) = receiver.recv() {
+                forwarder.handle_event(&event);
+            }
+            debug!("MQTT event subscriber stopped.");
+        });
+
+        publisher
+    }
+
+    /// Returns the current configuration.
+    pub fn get_config(&self) -> Result<MqttConfig> {
+        self.config
+            .read()
+            .map(|guard| guard.clone())
+            .map_err(|e| AppError::LockError(format!("Failed to lock MQTT config: {}", e)))
+    }
+
+    /// Returns the connection state.
+    pub fn get_status(&self) -> Result<MqttStatus> {
+        self.status
+            .lock()
+            .map(|guard| guard.clone())
+            .map_err(|e| AppError::LockError(format!("Failed to lock MQTT status: {}", e)))
+    }
+
+    /// Validates and saves a new configuration, then reconnects with it.
+    pub fn set_config(self: &Arc<Self>, config: MqttConfig) -> Result<()> {
+        validate_config(&config)?;
+        let json = serde_json::to_string_pretty(&config)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize MQTT config: {}", e)))?;
+        fs_utils::write_string_to_file_atomic(&self.config_path, &json)?;
+        {
+            let mut guard = self
+                .config
+                .write()
+                .map_err(|e| AppError::LockError(format!("Failed to lock MQTT config for writing: {}", e)))?;
+            *guard = config;
+        }
+        self.apply_config()
+    }
+
+    /// (Re)starts the connection according to the stored configuration.
+    pub fn apply_config(self: &Arc<Self>) -> Result<()> {
+        let config = self.get_config()?;
+        let mut session_guard = self
+            .session
+            .lock()
+            .map_err(|e| AppError::LockError(format!("Failed to lock MQTT session: {}", e)))?;
+
+        if let Some(old) = session_guard.take() {
+            info!("Disconnecting from MQTT broker {}:{}", old.config.host, old.config.port);
+            // A clean disconnect suppresses the last will, so publish offline ourselves
+            let _ = old.client.publish(old.config.topic("status"), old.config.qos, true, offline_payload());
+            old.shutdown.store(true, Ordering::SeqCst);
+            old.client.disconnect();
+        }
+        if let Ok(mut status) = self.status.lock() {
+            *status = MqttStatus::default();
+        }
+
+        if !config.enabled {
+            info!("MQTT publisher disabled.");
+            return Ok(());
+        }
+
+        validate_config(&config)?;
+        info!(
+            "Connecting to MQTT broker {}:{} ({:?}) as '{}'",
+            config.host, config.port, config.protocol, config.client_id
+        );
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let client = self.connect(&config, shutdown.clone());
+        *session_guard = Some(MqttSession { client, config, shutdown });
+        Ok(())
+    }
+
+    /// Creates the client and spawns the thread that drives its connection.
+    fn connect(self: &Arc<Self>, config: &MqttConfig, shutdown: Arc<AtomicBool>) -> MqttClient {
+        let keep_alive = Duration::from_secs(config.keep_alive_secs.max(5));
+        let status_topic = config.topic("status");
+        let credentials = config
+            .username
+            .clone()
+            .filter(|u| !u.is_empty())
+            .map(|u| (u, config.password.clone().unwrap_or_default()));
+        let publisher = self.clone();
+        let thread_config = config.clone();
+
+        match config.protocol {
+            MqttProtocol::V311 => {
+                let mut options = rumqttc::MqttOptions::new(&config.client_id, &config.host, config.port);
+                options.set_keep_alive(keep_alive);
+                options.set_last_will(rumqttc::LastWill::new(
+                    status_topic,
+                    offline_payload(),
+                    qos_v311(config.qos),
+                    true,
+                ));
+                if let Some((username, password)) = credentials {
+                    options.set_credentials(username, password);
+                }
+                if config.tls {
+                    options.set_transport(rumqttc::Transport::tls_with_default_config());
+                }
+                let (client, mut connection) = rumqttc::Client::new(options, REQUEST_QUEUE_CAPACITY);
+                let mqtt_client = MqttClient::V311(client);
+                let loop_client = mqtt_client.clone();
+                let mut commands = self.spawn_command_worker(&mqtt_client, config);
+                thread::spawn(move || {
+                    for notification in connection.iter() {
+                        let incoming = match notification {
+                            Ok(rumqttc::Event::Incoming(rumqttc::Packet::ConnAck(_))) => Ok(Some(Incoming::Connected)),
+                            Ok(rumqttc::Event::Incoming(rumqttc::Packet::Publish(publish))) => Ok(Some(Incoming::Publish {
+                                topic: publish.topic,
+                                payload: publish.payload.to_vec(),
+                            })),
+                            Ok(_) => Ok(None),
+                            Err(e) => Err(e.to_string()),
+                        };
+                        if !publisher.on_notification(&thread_config, &loop_client, &shutdown, &mut commands, incoming) {
+                            break;
+                        }
+                    }
+                    debug!("MQTT connection thread stopped.");
+                });
+                mqtt_client
+            }
+            MqttProtocol::V5 => {
+                let mut options = rumqttc::v5::MqttOptions::new(&config.client_id, &config.host, config.port);
+                options.set_keep_alive(keep_alive);
+                options.set_last_will(rumqttc::v5::mqttbytes::v5::LastWill::new(
+                    status_topic,
+                    offline_payload(),
+                    qos_v5(config.qos),
+                    true,
+                    None,
+                ));
+                if let Some((username, password)) = credentials {
+                    options.set_credentials(username, password);
+                }
+                if config.tls {
+                    options.set_transport(rumqttc::Transport::tls_with_default_config());
+                }
+                let (client, mut connection) = rumqttc::v5::Client::new(options, REQUEST_QUEUE_CAPACITY);
+                let mqtt_client = MqttClient::V5(client);
+                let loop_client = mqtt_client.clone();
+                let mut commands = self.spawn_command_worker(&mqtt_client, config);
+                thread::spawn(move || {
+                    use rumqttc::v5::mqttbytes::v5::Packet;
+                    for notification in connection.iter() {
+                        let incoming = match notification {
+                            Ok(rumqttc::v5::Event::Incoming(Packet::ConnAck(_))) => Ok(Some(Incoming::Connected)),
+                            Ok(rumqttc::v5::Event::Incoming(Packet::Publish(publish))) => Ok(Some(Incoming::Publish {
+                                topic: String::from_utf8_lossy(&publish.topic).into_owned(),
+                                payload: publish.payload.to_vec(),
+                            })),
+                            Ok(_) => Ok(None),
+                            Err(e) => Err(e.to_string()),
+                        };
+                        if !publisher.on_notification(&thread_config, &loop_client, &shutdown, &mut commands, incoming) {
+                            break;
+                        }
+                    }
+                    debug!("MQTT connection thread stopped.");
+                });
+                mqtt_client
+            }
+        }
+    }
+
+    /// Handles one notification of the connection loop. Returns false when the loop should stop.
+    fn on_notification(
+        self: &Arc<Self>,
+        config: &MqttConfig,
+        client: &MqttClient,
+        shutdown: &AtomicBool,
+        commands: &mut CommandQueue,
+        incoming: std::result::Result<Option<Incoming>, String>,
+    ) -> bool {
+        if shutdown.load(Ordering::SeqCst) {
+            return false;
+        }
+        match incoming {
+            Ok(Some(Incoming::Connected)) => {
+                info!("Connected to MQTT broker {}:{}", config.host, config.port);
+                self.set_status(true, None);
+                // Retained state is re-published on every (re)connect
+                self.publish_status(client, config);
+                self.publish_players(client, config);
+                if config.commands_enabled {
+                    if let Err(e) = client.subscribe(config.topic("command"), config.qos) {
+                        error!("Failed to subscribe to MQTT command topic: {}", e);
+                    }
+                }
+            }
+            Ok(Some(Incoming::Publish { topic, payload })) if topic == config.topic("command") => {
+                self.handle_command(client, config, commands, String::from_utf8_lossy(&payload).trim().to_string());
+            }
+            Ok(_) => {}
+            Err(e) => {
+                warn!("MQTT connection error ({}:{}): {}. Retrying in {:?}", config.host, config.port, e, RECONNECT_DELAY);
+                self.set_status(false, Some(e));
+                // The iterator reconnects on the next poll
+                thread::sleep(RECONNECT_DELAY);
+                return !shutdown.load(Ordering::SeqCst);
+            }
+        }
+        true
+    }
+
+    /// Checks a command received on the command topic against the allow-list and queues it
+    /// for the command worker. Rejections are published as results right away.
+    fn handle_command(&self, client: &MqttClient, config: &MqttConfig, commands: &mut CommandQueue, command: String) {
+        let reject = |command: String, error: &str| {
+            let payload = json!({ "command": command, "success": false, "error": error });
+            let _ = client.publish(config.topic("command/result"), config.qos, false, payload.to_string().into_bytes());
+        };
+        if command.chars().any(char::is_control) {
+            warn!("Rejected MQTT command {:?}: contains control characters", command);
+            reject(command, "Command contains control characters");
+            return;
+        }
+        let keyword = command.split_whitespace().next().unwrap_or_default().to_lowercase();
+        let allowed = config.command_allow_list.iter().any(|c| c.trim().eq_ignore_ascii_case(&keyword));
+        if !allowed {
+            warn!("Rejected MQTT command '{}': not in the allow-list", command);
+            reject(command, "Command not allowed");
+            return;
+        }
+
+        if let Err(error) = commands.submit(command.clone()) {
+            warn!("Rejected MQTT command '{}': {}", command, error);
+            reject(command, error);
+        }
+    }
+
+    /// Starts the thread that executes a session's queued commands in order and publishes
+    /// their results. start/stop can block for a while, so this keeps them off the
+    /// connection loop without running them concurrently.
+    fn spawn_command_worker(&self, client: &MqttClient, config: &MqttConfig) -> CommandQueue {
+        let (sender, receiver) = mpsc::sync_channel::<String>(COMMAND_QUEUE_CAPACITY);
+        let executor = CommandExecutor::new(self.state.clone());
+        let client = client.clone();
+        let result_topic = config.topic("command/result");
+        let qos = config.qos;
+        thread::spawn(move || {
+            for command in receiver {
+                info!("Executing MQTT command: {}", command);
+                let payload = match executor.execute(&command) {
+                    Ok(()) => json!({ "command": command, "success": true }),
+                    Err(e) => json!({ "command": command, "success": false, "error": e.to_string() }),
+                };
+                if let Err(e) = client.publish(result_topic.clone(), qos, false, payload.to_string().into_bytes()) {
+                    warn!("Failed to publish MQTT command result: {}", e);
+                }
+            }
+            debug!("MQTT command worker stopped.");
+        });
+        CommandQueue { sender, accepted: VecDeque::new() }
+    }
+
+    /// Publishes an event to the matching topics of the active session, if any.
+    fn handle_event(&self, event: &Event) {
+        // Track players even while disconnected so the retained list is right after connecting
+        let players_changed = match event {
+            Event::PlayerJoined(name) => self.players.lock().map(|mut p| p.insert(name.clone())).unwrap_or(false),
+            Event::PlayerLeft(name) => self.players.lock().map(|mut p| p.remove(name)).unwrap_or(false),
+            // Every stop path (clean, crash, failed start) ends in StatusChanged(Stopped)
+            Event::StatusChanged(ServerStatus::Stopped) | Event::ServerCrashed(_) => self
+                .players
+                .lock()
+                .map(|mut p| {
+                    let had_players = !p.is_empty();
+                    p.clear();
+                    had_players
+                })
+                .unwrap_or(false),
+            _ => false,
+        };
+
+        let session_guard = match self.session.lock() {
+            Ok(guard) => guard,
+            Err(e) => {
+                error!("Failed to lock MQTT session: {}", e);
+                return;
+            }
+        };
+        let Some(session) = session_guard.as_ref() else { return };
+        let (client, config) = (&session.client, &session.config);
+
+        match event {
+            Event::MetricsUpdated(metrics) if config.publish_metrics => match serde_json::to_vec(metrics) {
+                Ok(payload) => self.publish(client, config.topic("metrics"), config.qos, false, payload),
+                Err(e) => error!("Failed to serialize metrics for MQTT: {}", e),
+            },
+            Event::StatusChanged(_) => self.publish_status(client, config),
+            _ => {}
+        }
+        if players_changed {
+            self.publish_players(client, config);
+        }
+
+        let event_type = event.type_name();
+        if config.event_types.iter().any(|t| t == "*" || t.eq_ignore_ascii_case(event_type)) {
+            match serde_json::to_vec(event) {
+                Ok(payload) => self.publish(client, config.topic("events"), config.qos, false, payload),
+                Err(e) => error!("Failed to serialize {} event for MQTT: {}", event_type, e),
+            }
+        }
+    }
+
+    /// Publishes the retained `{"online": true, "server_status": ...}` status.
+    fn publish_status(&self, client: &MqttClient, config: &MqttConfig) {
+        let server_status = self.state.get_status().unwrap_or(ServerStatus::Stopped);
+        let payload = json!({ "online": true, "server_status": server_status.to_string() });
+        self.publish(client, config.topic("status"), config.qos, true, payload.to_string().into_bytes());
+    }
+
+    /// Publishes the retained list of online players.
+    fn publish_players(&self, client: &MqttClient, config: &MqttConfig) {
+        let players: Vec<String> = match self.players.lock() {
+            Ok(guard) => guard.iter().cloned().collect(),
+            Err(e) => {
+                error!("Failed to lock MQTT player list: {}", e);
+                return;
+            }
+        };
+        let payload = json!({ "count": players.len(), "players": players });
+        self.publish(client, config.topic("players"), config.qos, true, payload.to_string().into_bytes());
+    }
+
+    fn publish(&self, client: &MqttClient, topic: String, qos: u8, retain: bool, payload: Vec<u8>) {
+        // try_publish never blocks; a full queue while disconnected just drops the sample
+        if let Err(e) = client.publish(topic.clone(), qos, retain, payload) {
+            debug!("Failed to queue MQTT publish to {}: {}", topic, e);
+        }
+    }
+
+    fn set_status(&self, connected: bool, last_error: Option<String>) {
+        if let Ok(mut status) = self.status.lock() {
+            status.connected = connected;
+            if last_error.is_some() || connected {
+                status.last_error = last_error;
+            }
+        }
+    }
+}
+
+/// Payload of the retained last will and of the clean-shutdown status.
+fn offline_payload() -> Vec<u8> {
+    json!({ "online": false }).to_string().into_bytes()
+}
+
+fn validate_config(config: &MqttConfig) -> Result<()> {
+    if config.host.trim().is_empty() {
+        return Err(AppError::ConfigError("MQTT host cannot be empty".to_string()));
+    }
+    if config.client_id.trim().is_empty() {
+        return Err(AppError::ConfigError("MQTT client id cannot be empty".to_string()));
+    }
+    if config.qos > 2 {
+        return Err(AppError::ConfigError(format!("Invalid MQTT QoS {} (must be 0, 1 or 2)", config.qos)));
+    }
+    for (label, value) in [("topic prefix", &config.topic_prefix), ("server name", &config.server_name)] {
+        let value = value.trim_matches('/');
+        if value.is_empty() || value.contains(['+', '#']) {
+            return Err(AppError::ConfigError(format!(
+                "MQTT {} must be non-empty and must not contain wildcards",
+                label
+            )));
+        }
+    }
+    Ok(())
+}
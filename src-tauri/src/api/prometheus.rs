@@ -0,0 +1,374 @@
+// src/api/prometheus.rs
+
+use crate::api::events::{self, Event};
+use crate::app_state::AppState;
+use crate::error::{AppError, Result};
+use crate::models::alert::{Alert, AlertSeverity, AlertState};
+use crate::models::metrics::MetricsData;
+use crate::models::server_status::ServerStatus;
+use crate::monitoring::alert_manager::AlertManager;
+use log::{debug, error, info, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Content type of the OpenMetrics text exposition format.
+const OPENMETRICS_CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+/// How often the accept loop checks whether it should shut down.
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// Read timeout for a scrape request, so a stuck client can't block the listener.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Configuration of the embedded `/metrics` listener. Disabled by default.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExporterConfig {
+    /// Whether the HTTP listener is running.
+    pub enabled: bool,
+    /// Address to bind to. Use "0.0.0.0" to allow scrapes from other hosts.
+    pub bind_address: String,
+    /// TCP port to listen on.
+    pub port: u16,
+}
+
+impl Default for ExporterConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 9225,
+        }
+    }
+}
+
+/// Counters derived from the internal event stream.
+#[derive(Debug, Default, Clone)]
+struct ExporterCounters {
+    alerts_total: u64,
+    starts_total: u64,
+    restarts_total: u64,
+    crashes_total: u64,
+    backups_succeeded_total: u64,
+    backups_failed_total: u64,
+    last_backup_duration_secs: Option<f64>,
+    last_backup_size_bytes: Option<u64>,
+    /// Set when BackupStarted is seen, used to time the backup.
+    backup_started_at: Option<Instant>,
+}
+
+impl ExporterCounters {
+    fn observe(&mut self, event: &Event) {
+        match event {
+            Event::Alert(alert) if alert.state == AlertState::Firing => self.alerts_total += 1,
+            Event::StatusChanged(ServerStatus::Starting) => self.starts_total += 1,
+            Event::ServerRestarting => self.restarts_total += 1,
+            Event::ServerCrashed(_) => self.crashes_total += 1,
+            Event::BackupStarted => self.backup_started_at = Some(Instant::now()),
+            Event::BackupCompleted { result, size_bytes } => {
+                match result {
+                    Ok(()) => self.backups_succeeded_total += 1,
+                    Err(_) => self.backups_failed_total += 1,
+                }
+                if let Some(started_at) = self.backup_started_at.take() {
+                    self.last_backup_duration_secs = Some(started_at.elapsed().as_secs_f64());
+                }
+                if size_bytes.is_some() {
+                    self.last_backup_size_bytes = *size_bytes;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Handle of the running listener thread.
+struct ListenerHandle {
+    shutdown: Arc<AtomicBool>,
+    thread: thread::JoinHandle<()>,
+}
+
+/// Serves `/metrics` in OpenMetrics text format for Prometheus scrapes.
+///
+/// Gauges are read from `AppState` (and active alerts from the `AlertManager`) on each
+/// scrape. Counters (alerts, starts, restarts, crashes, backups) are accumulated from the
+/// internal event stream for the lifetime of the application.
+pub struct PrometheusExporter {
+    state: Arc<AppState>,
+    alert_manager: Arc<AlertManager>,
+    config: Mutex<ExporterConfig>,
+    counters: Arc<Mutex<ExporterCounters>>,
+    listener: Mutex<Option<ListenerHandle>>,
+}
+
+impl PrometheusExporter {
+    /// Creates the exporter and starts counting events. The listener is started by `apply_config`.
+    pub fn new(state: Arc<AppState>, alert_manager: Arc<AlertManager>) -> Arc<Self> {
+        let counters = Arc::new(Mutex::new(ExporterCounters::default()));
+
+        let receiver = events::subscribe();
+        let counters_clone = counters.clone();
+        thread::spawn(move || {
+            debug!("Prometheus exporter event counter started.");
+            while let Ok(event) = receiver.recv() {
+                match counters_clone.lock() {
+                    Ok(mut guard) => guard.observe(&event),
+                    Err(e) => error!("Failed to lock exporter counters: {}", e),
+                }
+            }
+            debug!("Prometheus exporter event counter stopped.");
+        });
+
+        Arc::new(Self {
+            state,
+            alert_manager,
+            config: Mutex::new(ExporterConfig::default()),
+            counters,
+            listener: Mutex::new(None),
+        })
+    }
+
+    /// Returns the current exporter configuration.
+    pub fn get_config(&self) -> Result<ExporterConfig> {
+        self.config
+            .lock()
+            .map(|guard| guard.clone())
+            .map_err(|e| AppError::LockError(format!("Failed to lock exporter config: {}", e)))
+    }
+
+    /// Applies a new configuration, stopping and (re)starting the listener as needed.
+    pub fn apply_config(self: &Arc<Self>, config: ExporterConfig) -> Result<()> {
+        let mut listener_guard = self
+            .listener
+            .lock()
+            .map_err(|e| AppError::LockError(format!("Failed to lock exporter listener: {}", e)))?;
+
+        if let Some(handle) = listener_guard.take() {
+            info!("Stopping Prometheus exporter listener...");
+            handle.shutdown.store(true, Ordering::SeqCst);
+            if handle.thread.join().is_err() {
+                warn!("Prometheus exporter listener thread panicked.");
+            }
+        }
+
+        if config.enabled {
+            let address = format!("{}:{}", config.bind_address, config.port);
+            let listener = TcpListener::bind(&address).map_err(|e| {
+                AppError::ConfigError(format!("Failed to bind metrics listener on {}: {}", address, e))
+            })?;
+            listener.set_nonblocking(true)?;
+            info!("Prometheus exporter listening on http://{}/metrics", address);
+
+            let shutdown = Arc::new(AtomicBool::new(false));
+            let shutdown_clone = shutdown.clone();
+            let exporter = self.clone();
+            let thread = thread::spawn(move || exporter.accept_loop(listener, shutdown_clone));
+            *listener_guard = Some(ListenerHandle { shutdown, thread });
+        } else {
+            info!("Prometheus exporter is disabled.");
+        }
+
+        let mut config_guard = self
+            .config
+            .lock()
+            .map_err(|e| AppError::LockError(format!("Failed to lock exporter config: {}", e)))?;
+        *config_guard = config;
+        Ok(())
+    }
+
+    /// Accepts connections until `shutdown` is set. Scrapes are handled sequentially;
+    /// rendering is cheap and Prometheus scrapes one target at a time.
+    fn accept_loop(&self, listener: TcpListener, shutdown: Arc<AtomicBool>) {
+        while !shutdown.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, peer)) => {
+                    trace!("Metrics scrape from {}", peer);
+                    if let Err(e) = self.handle_connection(stream) {
+                        debug!("Failed to serve metrics request from {}: {}", peer, e);
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => thread::sleep(ACCEPT_POLL_INTERVAL),
+                Err(e) => {
+                    warn!("Metrics listener accept error: {}", e);
+                    thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+            }
+        }
+        info!("Prometheus exporter listener stopped.");
+    }
+
+    /// Reads a single HTTP request and writes the response.
+    fn handle_connection(&self, stream: TcpStream) -> std::io::Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        let mut request_line = String::new();
+        reader.read_line(&mut request_line)?;
+        // Drain headers; we don't need any of them
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        let mut parts = request_line.split_whitespace();
+        let method = parts.next().unwrap_or("");
+        let path = parts.next().unwrap_or("");
+        let path = path.split('?').next().unwrap_or("");
+
+        let (status_line, content_type, body) = match (method, path) {
+            ("GET", "/metrics") | ("HEAD", "/metrics") => {
+                ("200 OK", OPENMETRICS_CONTENT_TYPE, self.render())
+            }
+            ("GET", _) | ("HEAD", _) => ("404 Not Found", "text/plain", "Not Found\n".to_string()),
+            _ => ("405 Method Not Allowed", "text/plain", "Method Not Allowed\n".to_string()),
+        };
+
+        let mut stream = stream;
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            status_line,
+            content_type,
+            body.len()
+        )?;
+        if method != "HEAD" {
+            stream.write_all(body.as_bytes())?;
+        }
+        stream.flush()
+    }
+
+    /// Renders all metrics in OpenMetrics text format.
+    pub fn render(&self) -> String {
+        let metrics = self.state.get_metrics().unwrap_or_default();
+        let status = self.state.get_status().unwrap_or_default();
+        let active_alerts = self.alert_manager.get_active_alerts().unwrap_or_default();
+        let counters = self
+            .counters
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_default();
+
+        let mut out = String::with_capacity(4096);
+        render_status(&mut out, &status);
+        render_metrics_data(&mut out, &metrics);
+        render_active_alerts(&mut out, &active_alerts);
+        render_counters(&mut out, &counters);
+        out.push_str("# EOF\n");
+        out
+    }
+}
+
+/// Writes the TYPE/HELP header for a metric family.
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+}
+
+/// Writes a single unlabeled gauge.
+fn gauge(out: &mut String, name: &str, help: &str, value: f64) {
+    family(out, name, "gauge", help);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+/// Writes a single unlabeled counter. `name` is the family name without `_total`.
+fn counter(out: &mut String, name: &str, help: &str, value: u64) {
+    family(out, name, "counter", help);
+    let _ = writeln!(out, "{}_total {}", name, value);
+}
+
+fn render_status(out: &mut String, status: &ServerStatus) {
+    family(out, "mclh_server_status", "gauge", "Current server status (1 for the active status).");
+    let current = match status {
+        ServerStatus::Starting => "starting",
+        ServerStatus::Running => "running",
+        ServerStatus::Stopping => "stopping",
+        ServerStatus::Stopped => "stopped",
+        ServerStatus::Error(_) => "error",
+    };
+    for label in ["starting", "running", "stopping", "stopped", "error"] {
+        let _ = writeln!(
+            out,
+            "mclh_server_status{{status=\"{}\"}} {}",
+            label,
+            if label == current { 1 } else { 0 }
+        );
+    }
+}
+
+fn render_metrics_data(out: &mut String, m: &MetricsData) {
+    gauge(out, "mclh_cpu_usage_percent", "CPU usage of the server process tree, normalized to host cores.", m.cpu_usage as f64);
+    gauge(out, "mclh_memory_usage_bytes", "Resident memory of the server process tree.", m.memory_usage as f64);
+    gauge(out, "mclh_system_memory_total_bytes", "Total physical memory of the host.", m.system_memory_total as f64);
+    gauge(out, "mclh_players", "Players currently online.", m.player_count as f64);
+    gauge(out, "mclh_max_players", "Maximum players allowed by server.properties.", m.max_players as f64);
+    if let Some(tps) = m.tps {
+        gauge(out, "mclh_tps", "Server ticks per second.", tps as f64);
+    }
+    gauge(out, "mclh_uptime_seconds", "Server process uptime.", m.uptime as f64);
+    gauge(out, "mclh_processes", "Processes in the server process tree.", m.process_count as f64);
+    if let Some(threads) = m.thread_count {
+        gauge(out, "mclh_threads", "Threads in the server process tree.", threads as f64);
+    }
+    if let Some(files) = m.open_files {
+        gauge(out, "mclh_open_files", "Open file descriptors of the server process tree.", files as f64);
+    }
+    gauge(out, "mclh_disk_read_bytes_per_second", "Disk read throughput of the server process tree.", m.disk_read_bytes_per_sec as f64);
+    gauge(out, "mclh_disk_write_bytes_per_second", "Disk write throughput of the server process tree.", m.disk_write_bytes_per_sec as f64);
+
+    family(out, "mclh_host_load_average", "gauge", "Host load average.");
+    for (window, value) in ["1m", "5m", "15m"].iter().zip(m.load_average.iter()) {
+        let _ = writeln!(out, "mclh_host_load_average{{window=\"{}\"}} {}", window, value);
+    }
+    if let Some(rx) = m.host_network_rx_bytes_per_sec {
+        gauge(out, "mclh_host_network_receive_bytes_per_second", "Receive throughput of all interfaces in the server's network namespace (the whole host unless containerised).", rx as f64);
+    }
+    if let Some(tx) = m.host_network_tx_bytes_per_sec {
+        gauge(out, "mclh_host_network_transmit_bytes_per_second", "Transmit throughput of all interfaces in the server's network namespace (the whole host unless containerised).", tx as f64);
+    }
+
+    if let Some(jvm) = &m.jvm {
+        gauge(out, "mclh_jvm_heap_used_bytes", "JVM heap in use.", jvm.heap_used_bytes as f64);
+        gauge(out, "mclh_jvm_heap_committed_bytes", "JVM heap committed.", jvm.heap_committed_bytes as f64);
+        if let Some(max) = jvm.heap_max_bytes {
+            gauge(out, "mclh_jvm_heap_max_bytes", "JVM maximum heap size.", max as f64);
+        }
+        if let Some(old) = jvm.old_gen_used_bytes {
+            gauge(out, "mclh_jvm_old_gen_used_bytes", "JVM old generation usage.", old as f64);
+        }
+        family(out, "mclh_jvm_gc_collections", "counter", "Garbage collections since JVM start.");
+        let _ = writeln!(out, "mclh_jvm_gc_collections_total{{kind=\"young\"}} {}", jvm.young_gc_count);
+        let _ = writeln!(out, "mclh_jvm_gc_collections_total{{kind=\"full\"}} {}", jvm.full_gc_count);
+        family(out, "mclh_jvm_gc_pause_seconds", "counter", "Total time spent in GC pauses since JVM start.");
+        let _ = writeln!(out, "mclh_jvm_gc_pause_seconds_total {}", jvm.gc_pause_total_ms / 1000.0);
+        gauge(out, "mclh_jvm_allocation_rate_bytes_per_second", "Estimated JVM allocation rate.", jvm.allocation_rate_bytes_per_sec as f64);
+    }
+}
+
+fn render_active_alerts(out: &mut String, alerts: &[Alert]) {
+    family(out, "mclh_alerts_active", "gauge", "Alerts currently firing, by severity.");
+    for severity in [AlertSeverity::Info, AlertSeverity::Warning, AlertSeverity::Critical] {
+        let count = alerts.iter().filter(|alert| alert.severity == severity).count();
+        let _ = writeln!(out, "mclh_alerts_active{{severity=\"{}\"}} {}", severity, count);
+    }
+}
+
+fn render_counters(out: &mut String, c: &ExporterCounters) {
+    counter(out, "mclh_alerts", "Alerts fired since the manager started.", c.alerts_total);
+    counter(out, "mclh_server_starts", "Server start attempts since the manager started.", c.starts_total);
+    counter(out, "mclh_server_restarts", "Server restarts since the manager started.", c.restarts_total);
+    counter(out, "mclh_server_crashes", "Unexpected server terminations since the manager started.", c.crashes_total);
+
+    family(out, "mclh_backups", "counter", "Backups completed since the manager started.");
+    let _ = writeln!(out, "mclh_backups_total{{result=\"success\"}} {}", c.backups_succeeded_total);
+    let _ = writeln!(out, "mclh_backups_total{{result=\"failure\"}} {}", c.backups_failed_total);
+    if let Some(duration) = c.last_backup_duration_secs {
+        gauge(out, "mclh_backup_last_duration_seconds", "Duration of the most recent backup.", duration);
+    }
+    if let Some(size) = c.last_backup_size_bytes {
+        gauge(out, "mclh_backup_last_size_bytes", "Size of the most recent backup archive.", size as f64);
+    }
+}
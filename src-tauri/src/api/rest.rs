@@ -1,14 +1,47 @@
 ﻿use crate::api::events::{emit_app_error, emit_eula_status, emit_event, Event}; // Use event emitters
 use crate::app_state::AppState;
-use crate::config::{eula_manager, modpack_installer, server_properties}; // Added modpack_installer
+use crate::api::email::{EmailConfig, EmailNotifier};
+use crate::api::mqtt::{MqttConfig, MqttPublisher, MqttStatus};
+use crate::api::prometheus::{ExporterConfig, PrometheusExporter};
+use crate::api::webhooks::{DeadLetter, WebhookConfig, WebhookDispatcher, WebhookEndpoint, WebhookTestResult};
+use crate::commands::gamerules::GameruleService;
+use crate::commands::player_access::PlayerAccessManager;
+use crate::commands::startup_queue::StartupCommandQueue;
+use crate::config::manager_config::{ManagerConfig, ManagerConfigStore, ManagerSettings};
+use crate::config::presets::PresetManager;
+use crate::config::server_software::{ServerSoftwareManager, SoftwareSources};
+use crate::config::software_upgrade::SoftwareUpgrader;
+use crate::config::curseforge::CurseForgeSettings;
+use crate::config::modpack_installer::{ModpackInstaller, ModpackSource};
+use crate::config::{
+    backup, config_files, config_templates, eula_manager, property_catalog, server_properties, software_detector,
+};
 use crate::error::{AppError, Result}; // Use our Result and AppError
-use crate::models::config::ServerConfig; // Assuming this struct exists and is Serialize/Deserialize
+use crate::models::alert::{Alert, AlertRule};
+use crate::models::config::{
+    ConflictResolution, ModpackConfig, ModpackConflict, ModpackInstallResult, ModpackLoader, ModpackMergeView,
+    ModpackUpdateResult, ServerConfig,
+};
+use crate::models::config_file::{ConfigEditResult, ConfigFileContent, ConfigFileInfo}; // Assuming this struct exists and is Serialize/Deserialize
+use crate::models::gamerule::{GameruleReport, GameruleValue};
 use crate::models::metrics::MetricsData;
+use crate::models::player_access::{AccessList, BanRequest, CsvImportResult, PlayerAccessLists, PlayerProfile};
+use crate::models::preset::{PresetApplyResult, ServerPreset};
+use crate::models::property::{PropertyError, ServerConfigView};
+use crate::models::server_software::{
+    DetectedSoftware, InstalledSoftware, ServerFlavor, SoftwareBuild, SoftwareVersion, UpgradeCheck, UpgradeRequest,
+    UpgradeResult,
+};
 use crate::models::server_status::ServerStatus;
+use crate::models::template::{RenderedFile, TemplateProfile};
+use crate::monitoring::alert_manager::AlertManager;
+use crate::monitoring::metrics_collector::MetricsCollector;
+use crate::monitoring::metrics_store::{MetricsRange, MetricsResolution};
 // Import process_manager for start/stop/command/restart
 use crate::commands::process_manager;
 use log::{error, info}; // Use log crate
 use serde::Serialize;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::{command, AppHandle, Manager, State}; // Manager might not be needed if using MPSC only
 
@@ -52,6 +85,15 @@ impl<T: Serialize> ApiResponse<T> {
         }
     }
 
+    /// Creates an error response that also carries details (e.g. per-field validation errors).
+    fn error_with_data(error_message: String, data: T) -> Self {
+        Self {
+            success: false,
+            data: Some(data),
+            error: Some(error_message),
+        }
+    }
+
     /// Creates an ApiResponse from a Result<T, AppError>.
     fn from_result(result: Result<T>) -> Self {
         match result {
@@ -94,6 +136,32 @@ pub async fn get_server_metrics(state: State<'_, Arc<AppState>>) -> ApiResponse<
     ApiResponse::from_result(state.get_metrics())
 }
 
+/// Gets persisted metrics between `from` and `to` (UNIX seconds) for charts.
+/// `resolution` defaults to `auto`, which picks raw, 1-minute or 1-hour points based on the span.
+#[command]
+pub async fn get_metrics_range(
+    from: u64,
+    to: u64,
+    resolution: Option<MetricsResolution>,
+    collector: State<'_, Arc<MetricsCollector>>,
+) -> ApiResponse<MetricsRange> {
+    info!("'get_metrics_range' command received: {}..{} ({:?})", from, to, resolution);
+    let collector_clone = collector.inner().clone();
+    // Reads day files from disk, use spawn_blocking
+    let result = tokio::task::spawn_blocking(move || {
+        collector_clone.get_metrics_range(from, to, resolution.unwrap_or(MetricsResolution::Auto))
+    })
+        .await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for get_metrics_range: {}", join_error);
+            ApiResponse::error(format!("Failed to execute metrics range task: {}", join_error))
+        }
+    }
+}
+
 /// Starts the Minecraft server process.
 #[command]
 pub async fn start_server(state: State<'_, Arc<AppState>>) -> ApiResponse<()> {
@@ -188,13 +256,18 @@ pub async fn execute_command(command: String, state: State<'_, Arc<AppState>>) -
 }
 
 /// Retrieves the complete server configuration (properties, Java args, etc.).
+/// Properties are merged with catalog metadata (type, default, description, restart flag);
+/// `minecraft_version` filters out keys the version doesn't read.
 #[command]
-pub async fn get_server_config(state: State<'_, Arc<AppState>>) -> ApiResponse<ServerConfig> {
+pub async fn get_server_config(
+    minecraft_version: Option<String>,
+    state: State<'_, Arc<AppState>>,
+) -> ApiResponse<ServerConfigView> {
     info!("'get_server_config' command received.");
     // Reading config might involve file I/O, consider spawn_blocking if it becomes slow
     // Assuming read_config_fully is relatively fast for now
     match server_properties::read_config_fully(state.inner().clone()) {
-        Ok(config) => ApiResponse::success(config),
+        Ok(config) => ApiResponse::success(property_catalog::build_config_view(config, minecraft_version)),
         Err(e) => {
             // Emit specific error event if desired
             emit_app_error(&e);
@@ -204,16 +277,31 @@ pub async fn get_server_config(state: State<'_, Arc<AppState>>) -> ApiResponse<S
 }
 
 /// Updates the server configuration.
+/// Invalid properties are rejected without saving anything; `data` then lists one error per key.
 #[command]
 pub async fn update_server_config(
     config: ServerConfig, // Receive the full config object
     state: State<'_, Arc<AppState>>,
-) -> ApiResponse<()> {
+    config_store: State<'_, Arc<ManagerConfigStore>>,
+) -> ApiResponse<Vec<PropertyError>> {
     info!("'update_server_config' command received.");
+    let errors = property_catalog::validate_properties(&config.server_properties);
+    if !errors.is_empty() {
+        info!("Rejected server config update with {} invalid properties.", errors.len());
+        return ApiResponse::error_with_data(format!("{} properties have invalid values", errors.len()), errors);
+    }
+
     let app_state_clone = state.inner().clone();
+    let config_store_clone = config_store.inner().clone();
     // Saving config involves file I/O, use spawn_blocking
     let result = tokio::task::spawn_blocking(move || {
-        server_properties::update_config_fully(config, app_state_clone)
+        server_properties::update_config_fully(config.clone(), app_state_clone)?;
+        // Persist so Java args etc. survive a restart of the manager
+        config_store_clone.update(|manager_config| {
+            manager_config.server = config;
+            Ok(())
+        })?;
+        Ok(())
     }).await;
 
     match result {
@@ -258,22 +346,28 @@ pub async fn is_eula_accepted(state: State<'_, Arc<AppState>>) -> ApiResponse<bo
     ApiResponse::from_result(eula_manager::is_eula_accepted(state.inner().clone()))
 }
 
-/// Installs or updates a modpack from a given URL or identifier.
+/// Installs or updates a modpack from a given URL or identifier. If `loader` is given, the
+/// Forge/NeoForge installer (or Fabric/Quilt launcher) is installed afterwards and becomes the
+/// server's launch target.
 #[command]
-pub async fn install_modpack(url: String, state: State<'_, Arc<AppState>>) -> ApiResponse<()> {
-    info!("'install_modpack' command received for URL: {}", url);
-    let app_state_clone = state.inner().clone();
+pub async fn install_modpack(
+    url: String,
+    loader: Option<ModpackLoader>,
+    installer: State<'_, Arc<ModpackInstaller>>,
+) -> ApiResponse<ModpackInstallResult> {
+    info!("'install_modpack' command received for URL: {} (loader: {:?})", url, loader);
+    let installer_clone = installer.inner().clone();
     let url_clone = url.clone();
 
     // Modpack installation involves network I/O and file I/O (heavy), use spawn_blocking
     let result = tokio::task::spawn_blocking(move || {
         // This function should emit ProgressUpdate events
-        modpack_installer::install(app_state_clone, &url_clone)
+        installer_clone.install(&ModpackSource::Url(url_clone), loader)
     })
         .await;
 
     match result {
-        Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
         Err(join_error) => {
             error!("Task execution error for install_modpack: {}", join_error);
             ApiResponse::error(format!("Failed to execute modpack install task: {}", join_error))
@@ -281,36 +375,1063 @@ pub async fn install_modpack(url: String, state: State<'_, Arc<AppState>>) -> Ap
     }
 }
 
-/// Creates a backup of the server world and potentially configuration.
+/// Installs a modpack from a local pack file (zip, `.mrpack` or CurseForge export, detected
+/// by content) or from a directory holding an unpacked one, e.g. on a network share.
+#[command]
+pub async fn install_modpack_from_path(
+    path: PathBuf,
+    loader: Option<ModpackLoader>,
+    installer: State<'_, Arc<ModpackInstaller>>,
+) -> ApiResponse<ModpackInstallResult> {
+    info!("'install_modpack_from_path' command received for {} (loader: {:?})", path.display(), loader);
+    let installer_clone = installer.inner().clone();
+    let result = tokio::task::spawn_blocking(move || installer_clone.install(&ModpackSource::Path(path), loader)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for install_modpack_from_path: {}", join_error);
+            ApiResponse::error(format!("Failed to execute modpack install task: {}", join_error))
+        }
+    }
+}
+
+/// Updates the installed modpack to the version at `url`, keeping the user's changes to its
+/// files. Overlapping changes are returned as conflicts for the merge view.
+#[command]
+pub async fn update_modpack(
+    url: String,
+    loader: Option<ModpackLoader>,
+    installer: State<'_, Arc<ModpackInstaller>>,
+) -> ApiResponse<ModpackUpdateResult> {
+    info!("'update_modpack' command received for URL: {} (loader: {:?})", url, loader);
+    let installer_clone = installer.inner().clone();
+    let result = tokio::task::spawn_blocking(move || installer_clone.update(&ModpackSource::Url(url), loader)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for update_modpack: {}", join_error);
+            ApiResponse::error(format!("Failed to execute modpack update task: {}", join_error))
+        }
+    }
+}
+
+/// Updates the installed modpack from a local pack file or directory, like
+/// `install_modpack_from_path`, keeping the user's changes to its files.
+#[command]
+pub async fn update_modpack_from_path(
+    path: PathBuf,
+    loader: Option<ModpackLoader>,
+    installer: State<'_, Arc<ModpackInstaller>>,
+) -> ApiResponse<ModpackUpdateResult> {
+    info!("'update_modpack_from_path' command received for {} (loader: {:?})", path.display(), loader);
+    let installer_clone = installer.inner().clone();
+    let result = tokio::task::spawn_blocking(move || installer_clone.update(&ModpackSource::Path(path), loader)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for update_modpack_from_path: {}", join_error);
+            ApiResponse::error(format!("Failed to execute modpack update task: {}", join_error))
+        }
+    }
+}
+
+/// Lists the unresolved conflicts of the last modpack update.
+#[command]
+pub async fn get_modpack_conflicts(
+    installer: State<'_, Arc<ModpackInstaller>>,
+) -> ApiResponse<Vec<ModpackConflict>> {
+    ApiResponse::from_result(installer.list_conflicts())
+}
+
+/// Gets the previous pack, current and new pack version of a conflicting file.
+#[command]
+pub async fn get_modpack_merge_view(
+    path: String,
+    installer: State<'_, Arc<ModpackInstaller>>,
+) -> ApiResponse<ModpackMergeView> {
+    ApiResponse::from_result(installer.merge_view(&path))
+}
+
+/// Resolves a modpack update conflict by keeping the current file, taking the new pack's
+/// version or writing merged content.
+#[command]
+pub async fn resolve_modpack_conflict(
+    path: String,
+    resolution: ConflictResolution,
+    installer: State<'_, Arc<ModpackInstaller>>,
+) -> ApiResponse<()> {
+    info!("'resolve_modpack_conflict' command received for {}", path);
+    ApiResponse::from_empty_result(installer.resolve_conflict(&path, resolution))
+}
+
+/// Restores the server files, jar and modpack record from before the last modpack install.
+/// Returns the modpack that is installed again, if any.
+#[command]
+pub async fn rollback_modpack_install(
+    installer: State<'_, Arc<ModpackInstaller>>,
+) -> ApiResponse<Option<ModpackConfig>> {
+    info!("'rollback_modpack_install' command received.");
+    let installer_clone = installer.inner().clone();
+    let result = tokio::task::spawn_blocking(move || installer_clone.rollback()).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for rollback_modpack_install: {}", join_error);
+            ApiResponse::error(format!("Failed to execute modpack rollback task: {}", join_error))
+        }
+    }
+}
+
+/// Gets the user-configured globs modpack installs leave untouched.
+#[command]
+pub async fn get_modpack_protected_paths(
+    installer: State<'_, Arc<ModpackInstaller>>,
+) -> ApiResponse<Vec<String>> {
+    ApiResponse::from_result(installer.get_protected_paths())
+}
+
+/// Validates and saves the globs modpack installs leave untouched.
+#[command]
+pub async fn set_modpack_protected_paths(
+    paths: Vec<String>,
+    installer: State<'_, Arc<ModpackInstaller>>,
+) -> ApiResponse<()> {
+    info!("'set_modpack_protected_paths' command received: {:?}", paths);
+    ApiResponse::from_empty_result(installer.set_protected_paths(paths))
+}
+
+/// Gets the CurseForge API URL and key used to resolve CurseForge pack files.
+#[command]
+pub async fn get_curseforge_settings(
+    installer: State<'_, Arc<ModpackInstaller>>,
+) -> ApiResponse<CurseForgeSettings> {
+    ApiResponse::from_result(installer.get_curseforge_settings())
+}
+
+/// Validates and saves the CurseForge API URL and key.
+#[command]
+pub async fn set_curseforge_settings(
+    settings: CurseForgeSettings,
+    installer: State<'_, Arc<ModpackInstaller>>,
+) -> ApiResponse<()> {
+    info!("'set_curseforge_settings' command received.");
+    ApiResponse::from_empty_result(installer.set_curseforge_settings(settings))
+}
+
+/// Creates a backup of the server world and configuration under `backups/`.
 #[command]
 pub async fn create_backup(state: State<'_, Arc<AppState>>) -> ApiResponse<()> {
     info!("'create_backup' command received.");
     let app_state_clone = state.inner().clone();
 
     // Backup involves file I/O (potentially heavy), use spawn_blocking
+    // backup::create_backup emits BackupStarted/Completed (with the size) itself
+    let result = tokio::task::spawn_blocking(move || backup::create_backup(&app_state_clone)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_empty_result(inner_result.map(|_| ())),
+        Err(join_error) => {
+            error!("Task execution error for create_backup: {}", join_error);
+            emit_event(Event::BackupCompleted { result: Err(join_error.to_string()), size_bytes: None });
+            ApiResponse::error(format!("Failed to execute backup task: {}", join_error))
+        }
+    }
+}
+
+/// Gets the Prometheus exporter configuration.
+#[command]
+pub async fn get_exporter_config(exporter: State<'_, Arc<PrometheusExporter>>) -> ApiResponse<ExporterConfig> {
+    ApiResponse::from_result(exporter.get_config())
+}
+
+/// Updates the Prometheus exporter configuration, starting or stopping the `/metrics` listener.
+#[command]
+pub async fn set_exporter_config(
+    config: ExporterConfig,
+    exporter: State<'_, Arc<PrometheusExporter>>,
+    config_store: State<'_, Arc<ManagerConfigStore>>,
+) -> ApiResponse<()> {
+    info!("'set_exporter_config' command received: {:?}", config);
+    let exporter_clone = exporter.inner().clone();
+    let config_store_clone = config_store.inner().clone();
+    // Stopping the listener joins its thread, use spawn_blocking
     let result = tokio::task::spawn_blocking(move || {
-        // Replace with actual backup logic, which should emit BackupStarted/Completed events
-        // crate::backup::create_backup(app_state_clone)
-        emit_event(Event::BackupStarted);
-        // Simulate work
-        std::thread::sleep(std::time::Duration::from_secs(2));
-        let backup_result: Result<(), String> = Err("Backup feature not fully implemented".to_string()); // Placeholder
-        emit_event(Event::BackupCompleted(backup_result.clone()));
-
-        if let Err(e) = backup_result {
-            Err(AppError::BackupError(e)) // Convert String error to AppError
-        } else {
+        exporter_clone.apply_config(config.clone())?;
+        config_store_clone.update(|manager_config| {
+            manager_config.exporter = config;
             Ok(())
+        })?;
+        Ok(())
+    })
+        .await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for set_exporter_config: {}", join_error);
+            ApiResponse::error(format!("Failed to execute exporter config task: {}", join_error))
+        }
+    }
+}
+
+// --- Alert Rule Commands ---
+
+/// Gets all alert rules.
+#[command]
+pub async fn get_alert_rules(alert_manager: State<'_, Arc<AlertManager>>) -> ApiResponse<Vec<AlertRule>> {
+    ApiResponse::from_result(alert_manager.get_rules())
+}
+
+/// Replaces all alert rules. Alerts of removed rules are resolved.
+#[command]
+pub async fn set_alert_rules(
+    rules: Vec<AlertRule>,
+    alert_manager: State<'_, Arc<AlertManager>>,
+) -> ApiResponse<()> {
+    info!("'set_alert_rules' command received ({} rules)", rules.len());
+    let manager_clone = alert_manager.inner().clone();
+    // Persisting the rules file is blocking I/O
+    let result = tokio::task::spawn_blocking(move || manager_clone.set_rules(rules)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for set_alert_rules: {}", join_error);
+            ApiResponse::error(format!("Failed to execute alert rules task: {}", join_error))
+        }
+    }
+}
+
+/// Creates or updates a single alert rule. Returns the saved rule (with its id).
+#[command]
+pub async fn upsert_alert_rule(
+    rule: AlertRule,
+    alert_manager: State<'_, Arc<AlertManager>>,
+) -> ApiResponse<AlertRule> {
+    info!("'upsert_alert_rule' command received: {:?}", rule);
+    let manager_clone = alert_manager.inner().clone();
+    let result = tokio::task::spawn_blocking(move || manager_clone.upsert_rule(rule)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for upsert_alert_rule: {}", join_error);
+            ApiResponse::error(format!("Failed to execute alert rule task: {}", join_error))
+        }
+    }
+}
+
+/// Deletes an alert rule by id.
+#[command]
+pub async fn delete_alert_rule(rule_id: String, alert_manager: State<'_, Arc<AlertManager>>) -> ApiResponse<()> {
+    info!("'delete_alert_rule' command received: {}", rule_id);
+    let manager_clone = alert_manager.inner().clone();
+    let result = tokio::task::spawn_blocking(move || manager_clone.delete_rule(&rule_id)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for delete_alert_rule: {}", join_error);
+            ApiResponse::error(format!("Failed to execute alert rule task: {}", join_error))
+        }
+    }
+}
+
+/// Gets all currently firing alerts.
+#[command]
+pub async fn get_active_alerts(alert_manager: State<'_, Arc<AlertManager>>) -> ApiResponse<Vec<Alert>> {
+    ApiResponse::from_result(alert_manager.get_active_alerts())
+}
+
+/// Gets recently resolved alerts (newest last).
+#[command]
+pub async fn get_alert_history(alert_manager: State<'_, Arc<AlertManager>>) -> ApiResponse<Vec<Alert>> {
+    ApiResponse::from_result(alert_manager.get_alert_history())
+}
+
+/// Manually resolves a firing alert.
+#[command]
+pub async fn dismiss_alert(alert_id: String, alert_manager: State<'_, Arc<AlertManager>>) -> ApiResponse<()> {
+    info!("'dismiss_alert' command received: {}", alert_id);
+    let manager_clone = alert_manager.inner().clone();
+    let result = tokio::task::spawn_blocking(move || manager_clone.dismiss_alert(&alert_id)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for dismiss_alert: {}", join_error);
+            ApiResponse::error(format!("Failed to execute dismiss alert task: {}", join_error))
+        }
+    }
+}
+
+// --- Webhook Commands ---
+
+/// Gets the webhook configuration.
+#[command]
+pub async fn get_webhook_config(dispatcher: State<'_, Arc<WebhookDispatcher>>) -> ApiResponse<WebhookConfig> {
+    ApiResponse::from_result(dispatcher.get_config())
+}
+
+/// Validates and saves the webhook configuration. Returns it with generated endpoint ids.
+#[command]
+pub async fn set_webhook_config(
+    config: WebhookConfig,
+    dispatcher: State<'_, Arc<WebhookDispatcher>>,
+) -> ApiResponse<WebhookConfig> {
+    info!("'set_webhook_config' command received ({} endpoints)", config.endpoints.len());
+    let dispatcher_clone = dispatcher.inner().clone();
+    let result = tokio::task::spawn_blocking(move || dispatcher_clone.set_config(config)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for set_webhook_config: {}", join_error);
+            ApiResponse::error(format!("Failed to execute webhook config task: {}", join_error))
+        }
+    }
+}
+
+/// Sends a test delivery to an endpoint (saved or not) and returns the response.
+#[command]
+pub async fn test_webhook(
+    endpoint: WebhookEndpoint,
+    dispatcher: State<'_, Arc<WebhookDispatcher>>,
+) -> ApiResponse<WebhookTestResult> {
+    info!("'test_webhook' command received: {}", endpoint.url);
+    let dispatcher_clone = dispatcher.inner().clone();
+    // Blocking HTTP request, use spawn_blocking
+    let result = tokio::task::spawn_blocking(move || dispatcher_clone.test_endpoint(endpoint)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for test_webhook: {}", join_error);
+            ApiResponse::error(format!("Failed to execute webhook test task: {}", join_error))
+        }
+    }
+}
+
+/// Gets the most recent failed webhook deliveries (default 100).
+#[command]
+pub async fn get_webhook_dead_letters(
+    limit: Option<usize>,
+    dispatcher: State<'_, Arc<WebhookDispatcher>>,
+) -> ApiResponse<Vec<DeadLetter>> {
+    let dispatcher_clone = dispatcher.inner().clone();
+    let result = tokio::task::spawn_blocking(move || dispatcher_clone.get_dead_letters(limit.unwrap_or(100))).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for get_webhook_dead_letters: {}", join_error);
+            ApiResponse::error(format!("Failed to execute dead letter task: {}", join_error))
+        }
+    }
+}
+
+/// Deletes the webhook dead-letter log.
+#[command]
+pub async fn clear_webhook_dead_letters(dispatcher: State<'_, Arc<WebhookDispatcher>>) -> ApiResponse<()> {
+    info!("'clear_webhook_dead_letters' command received");
+    ApiResponse::from_empty_result(dispatcher.clear_dead_letters())
+}
+
+// --- Email Notification Commands ---
+
+/// Gets the email notification configuration.
+#[command]
+pub async fn get_email_config(notifier: State<'_, Arc<EmailNotifier>>) -> ApiResponse<EmailConfig> {
+    ApiResponse::from_result(notifier.get_config())
+}
+
+/// Validates and saves the email notification configuration.
+#[command]
+pub async fn set_email_config(config: EmailConfig, notifier: State<'_, Arc<EmailNotifier>>) -> ApiResponse<()> {
+    info!("'set_email_config' command received (enabled: {})", config.enabled);
+    let notifier_clone = notifier.inner().clone();
+    let result = tokio::task::spawn_blocking(move || notifier_clone.set_config(config)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for set_email_config: {}", join_error);
+            ApiResponse::error(format!("Failed to execute email config task: {}", join_error))
+        }
+    }
+}
+
+/// Sends a test email with the given settings (saved or not).
+#[command]
+pub async fn send_test_email(config: EmailConfig, notifier: State<'_, Arc<EmailNotifier>>) -> ApiResponse<()> {
+    info!("'send_test_email' command received");
+    let notifier_clone = notifier.inner().clone();
+    // SMTP conversation is blocking, use spawn_blocking
+    let result = tokio::task::spawn_blocking(move || notifier_clone.send_test_email(config)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for send_test_email: {}", join_error);
+            ApiResponse::error(format!("Failed to execute test email task: {}", join_error))
+        }
+    }
+}
+
+// --- MQTT Commands ---
+
+/// Gets the MQTT publisher configuration.
+#[command]
+pub async fn get_mqtt_config(publisher: State<'_, Arc<MqttPublisher>>) -> ApiResponse<MqttConfig> {
+    ApiResponse::from_result(publisher.get_config())
+}
+
+/// Saves the MQTT publisher configuration and reconnects with it.
+#[command]
+pub async fn set_mqtt_config(config: MqttConfig, publisher: State<'_, Arc<MqttPublisher>>) -> ApiResponse<()> {
+    info!("'set_mqtt_config' command received (enabled: {})", config.enabled);
+    let publisher_clone = publisher.inner().clone();
+    let result = tokio::task::spawn_blocking(move || publisher_clone.set_config(config)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for set_mqtt_config: {}", join_error);
+            ApiResponse::error(format!("Failed to execute MQTT config task: {}", join_error))
+        }
+    }
+}
+
+/// Gets the MQTT connection state (connected, last error).
+#[command]
+pub async fn get_mqtt_status(publisher: State<'_, Arc<MqttPublisher>>) -> ApiResponse<MqttStatus> {
+    ApiResponse::from_result(publisher.get_status())
+}
+
+// --- Manager Config Commands ---
+
+/// Gets the persisted manager configuration.
+#[command]
+pub async fn get_manager_config(config_store: State<'_, Arc<ManagerConfigStore>>) -> ApiResponse<ManagerConfig> {
+    ApiResponse::from_result(config_store.get())
+}
+
+/// Validates and saves manager settings (jar name, Java override, stop timeout, scheduler).
+/// Jar name, Java path and stop timeout take effect the next time the manager starts.
+#[command]
+pub async fn update_manager_settings(
+    settings: ManagerSettings,
+    config_store: State<'_, Arc<ManagerConfigStore>>,
+) -> ApiResponse<ManagerConfig> {
+    info!("'update_manager_settings' command received: {:?}", settings);
+    let config_store_clone = config_store.inner().clone();
+    let result = tokio::task::spawn_blocking(move || {
+        config_store_clone.update(|manager_config| settings.apply_to(manager_config))
+    })
+        .await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for update_manager_settings: {}", join_error);
+            ApiResponse::error(format!("Failed to execute manager settings task: {}", join_error))
+        }
+    }
+}
+
+// --- Config File Commands ---
+
+/// Lists YAML/TOML/JSON config files found in the server directory.
+#[command]
+pub async fn list_config_files(state: State<'_, Arc<AppState>>) -> ApiResponse<Vec<ConfigFileInfo>> {
+    let app_state_clone = state.inner().clone();
+    // Walks the config and plugin directories, use spawn_blocking
+    let result = tokio::task::spawn_blocking(move || config_files::list_config_files(&app_state_clone)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for list_config_files: {}", join_error);
+            ApiResponse::error(format!("Failed to execute config file listing task: {}", join_error))
+        }
+    }
+}
+
+/// Reads a config file (path relative to the server directory) as text and parsed tree.
+#[command]
+pub async fn get_config_file(path: String, state: State<'_, Arc<AppState>>) -> ApiResponse<ConfigFileContent> {
+    ApiResponse::from_result(config_files::read_config_file(state.inner(), &path))
+}
+
+/// Gets one value from a config file by dotted key path.
+#[command]
+pub async fn get_config_value(
+    path: String,
+    key: String,
+    state: State<'_, Arc<AppState>>,
+) -> ApiResponse<serde_json::Value> {
+    ApiResponse::from_result(config_files::get_config_value(state.inner(), &path, &key))
+}
+
+/// Sets one value in a config file by dotted key path, keeping comments where possible.
+#[command]
+pub async fn set_config_value(
+    path: String,
+    key: String,
+    value: serde_json::Value,
+    state: State<'_, Arc<AppState>>,
+) -> ApiResponse<ConfigEditResult> {
+    info!("'set_config_value' command received: {} -> {}", path, key);
+    let app_state_clone = state.inner().clone();
+    let result = tokio::task::spawn_blocking(move || {
+        config_files::set_config_value(&app_state_clone, &path, &key, value)
+    }).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for set_config_value: {}", join_error);
+            ApiResponse::error(format!("Failed to execute config edit task: {}", join_error))
         }
+    }
+}
+
+/// Checks the syntax of unsaved editor text for a config file.
+#[command]
+pub async fn validate_config_file(path: String, content: String, state: State<'_, Arc<AppState>>) -> ApiResponse<()> {
+    ApiResponse::from_empty_result(config_files::validate_config_file(state.inner(), &path, &content))
+}
 
+/// Saves the full text of a config file after validating its syntax.
+#[command]
+pub async fn save_config_file(path: String, content: String, state: State<'_, Arc<AppState>>) -> ApiResponse<()> {
+    info!("'save_config_file' command received: {}", path);
+    let app_state_clone = state.inner().clone();
+    let result = tokio::task::spawn_blocking(move || {
+        config_files::save_config_file(&app_state_clone, &path, &content)
     }).await;
 
     match result {
         Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
         Err(join_error) => {
-            error!("Task execution error for create_backup: {}", join_error);
-            emit_event(Event::BackupCompleted(Err(join_error.to_string())));
-            ApiResponse::error(format!("Failed to execute backup task: {}", join_error))
+            error!("Task execution error for save_config_file: {}", join_error);
+            ApiResponse::error(format!("Failed to execute config save task: {}", join_error))
+        }
+    }
+}
+
+// --- Template Profile Commands ---
+
+/// Lists the template profiles (named sets of templates rendered together).
+#[command]
+pub async fn list_template_profiles(state: State<'_, Arc<AppState>>) -> ApiResponse<Vec<TemplateProfile>> {
+    let app_state_clone = state.inner().clone();
+    let result = tokio::task::spawn_blocking(move || {
+        config_templates::list_profiles(&app_state_clone)
+    }).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for list_template_profiles: {}", join_error);
+            ApiResponse::error(format!("Failed to execute template profile listing task: {}", join_error))
+        }
+    }
+}
+
+/// Creates or replaces a template profile.
+#[command]
+pub async fn save_template_profile(profile: TemplateProfile, state: State<'_, Arc<AppState>>) -> ApiResponse<()> {
+    info!("'save_template_profile' command received: {}", profile.name);
+    let app_state_clone = state.inner().clone();
+    let result = tokio::task::spawn_blocking(move || {
+        config_templates::save_profile(&profile, &app_state_clone)
+    }).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for save_template_profile: {}", join_error);
+            ApiResponse::error(format!("Failed to execute template profile save task: {}", join_error))
+        }
+    }
+}
+
+/// Deletes a template profile.
+#[command]
+pub async fn delete_template_profile(name: String, state: State<'_, Arc<AppState>>) -> ApiResponse<()> {
+    info!("'delete_template_profile' command received: {}", name);
+    let app_state_clone = state.inner().clone();
+    let result = tokio::task::spawn_blocking(move || {
+        config_templates::delete_profile(&name, &app_state_clone)
+    }).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for delete_template_profile: {}", join_error);
+            ApiResponse::error(format!("Failed to execute template profile delete task: {}", join_error))
+        }
+    }
+}
+
+/// Dry run: renders a profile and returns each file with a diff against the current one.
+#[command]
+pub async fn preview_template_profile(
+    name: String,
+    variables: Option<serde_json::Map<String, serde_json::Value>>,
+    state: State<'_, Arc<AppState>>,
+) -> ApiResponse<Vec<RenderedFile>> {
+    let app_state_clone = state.inner().clone();
+    let result = tokio::task::spawn_blocking(move || {
+        config_templates::preview_profile(&name, &variables.unwrap_or_default(), &app_state_clone)
+    }).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for preview_template_profile: {}", join_error);
+            ApiResponse::error(format!("Failed to execute template preview task: {}", join_error))
+        }
+    }
+}
+
+/// Renders a profile and writes the changed files (previous versions are backed up).
+#[command]
+pub async fn apply_template_profile(
+    name: String,
+    variables: Option<serde_json::Map<String, serde_json::Value>>,
+    state: State<'_, Arc<AppState>>,
+) -> ApiResponse<Vec<RenderedFile>> {
+    info!("'apply_template_profile' command received: {}", name);
+    let app_state_clone = state.inner().clone();
+    let result = tokio::task::spawn_blocking(move || {
+        config_templates::apply_profile(&name, &variables.unwrap_or_default(), &app_state_clone)
+    }).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for apply_template_profile: {}", join_error);
+            ApiResponse::error(format!("Failed to execute template apply task: {}", join_error))
+        }
+    }
+}
+
+// --- Preset Commands ---
+
+/// Lists the saved server presets.
+#[command]
+pub async fn list_presets(presets: State<'_, Arc<PresetManager>>) -> ApiResponse<Vec<ServerPreset>> {
+    let presets_clone = presets.inner().clone();
+    let result = tokio::task::spawn_blocking(move || presets_clone.list_presets()).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for list_presets: {}", join_error);
+            ApiResponse::error(format!("Failed to execute preset listing task: {}", join_error))
+        }
+    }
+}
+
+/// Creates or replaces a preset.
+#[command]
+pub async fn save_preset(preset: ServerPreset, presets: State<'_, Arc<PresetManager>>) -> ApiResponse<()> {
+    info!("'save_preset' command received: {}", preset.name);
+    let presets_clone = presets.inner().clone();
+    let result = tokio::task::spawn_blocking(move || presets_clone.save_preset(&preset)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for save_preset: {}", join_error);
+            ApiResponse::error(format!("Failed to execute preset save task: {}", join_error))
+        }
+    }
+}
+
+/// Deletes a preset.
+#[command]
+pub async fn delete_preset(name: String, presets: State<'_, Arc<PresetManager>>) -> ApiResponse<()> {
+    info!("'delete_preset' command received: {}", name);
+    let presets_clone = presets.inner().clone();
+    let result = tokio::task::spawn_blocking(move || presets_clone.delete_preset(&name)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for delete_preset: {}", join_error);
+            ApiResponse::error(format!("Failed to execute preset delete task: {}", join_error))
+        }
+    }
+}
+
+/// Applies a preset. Properties and JVM settings are saved now (effective on restart);
+/// gamerules and datapacks are queued for the next successful start.
+#[command]
+pub async fn apply_preset(name: String, presets: State<'_, Arc<PresetManager>>) -> ApiResponse<PresetApplyResult> {
+    info!("'apply_preset' command received: {}", name);
+    let presets_clone = presets.inner().clone();
+    let result = tokio::task::spawn_blocking(move || presets_clone.apply_preset(&name)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for apply_preset: {}", join_error);
+            ApiResponse::error(format!("Failed to execute preset apply task: {}", join_error))
+        }
+    }
+}
+
+/// Saves the current properties and JVM settings as a new preset.
+#[command]
+pub async fn save_current_as_preset(
+    name: String,
+    description: Option<String>,
+    presets: State<'_, Arc<PresetManager>>,
+) -> ApiResponse<ServerPreset> {
+    info!("'save_current_as_preset' command received: {}", name);
+    let presets_clone = presets.inner().clone();
+    let result = tokio::task::spawn_blocking(move || {
+        presets_clone.save_current_as_preset(&name, &description.unwrap_or_default())
+    }).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for save_current_as_preset: {}", join_error);
+            ApiResponse::error(format!("Failed to execute preset save task: {}", join_error))
+        }
+    }
+}
+
+/// Exports a preset to a JSON file.
+#[command]
+pub async fn export_preset(name: String, path: String, presets: State<'_, Arc<PresetManager>>) -> ApiResponse<()> {
+    info!("'export_preset' command received: {} -> {}", name, path);
+    let presets_clone = presets.inner().clone();
+    let result = tokio::task::spawn_blocking(move || presets_clone.export_preset(&name, std::path::Path::new(&path))).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_empty_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for export_preset: {}", join_error);
+            ApiResponse::error(format!("Failed to execute preset export task: {}", join_error))
+        }
+    }
+}
+
+/// Imports a preset from a JSON file.
+#[command]
+pub async fn import_preset(path: String, presets: State<'_, Arc<PresetManager>>) -> ApiResponse<ServerPreset> {
+    info!("'import_preset' command received: {}", path);
+    let presets_clone = presets.inner().clone();
+    let result = tokio::task::spawn_blocking(move || presets_clone.import_preset(std::path::Path::new(&path))).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for import_preset: {}", join_error);
+            ApiResponse::error(format!("Failed to execute preset import task: {}", join_error))
+        }
+    }
+}
+
+/// Lists console commands waiting for the next server start.
+#[command]
+pub async fn get_startup_commands(queue: State<'_, Arc<StartupCommandQueue>>) -> ApiResponse<Vec<String>> {
+    ApiResponse::from_result(queue.get_pending())
+}
+
+/// Drops all console commands waiting for the next server start.
+#[command]
+pub async fn clear_startup_commands(queue: State<'_, Arc<StartupCommandQueue>>) -> ApiResponse<()> {
+    ApiResponse::from_empty_result(queue.clear())
+}
+
+// --- Gamerules ---
+
+/// Queries all gamerules from the running server (takes up to a few seconds) and returns
+/// them together with the values enforced on every start.
+#[command]
+pub async fn get_gamerules(gamerules: State<'_, Arc<GameruleService>>) -> ApiResponse<GameruleReport> {
+    info!("'get_gamerules' command received.");
+    let service = gamerules.inner().clone();
+    let result = tokio::task::spawn_blocking(move || service.get_gamerules()).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for get_gamerules: {}", join_error);
+            ApiResponse::error(format!("Failed to execute gamerule query task: {}", join_error))
+        }
+    }
+}
+
+/// Validates and sets a gamerule. The value is remembered and re-applied on every start;
+/// if the server is stopped it is only applied then.
+#[command]
+pub async fn set_gamerule(
+    name: String,
+    value: String,
+    gamerules: State<'_, Arc<GameruleService>>,
+) -> ApiResponse<GameruleValue> {
+    info!("'set_gamerule' command received: {} = {}", name, value);
+    ApiResponse::from_result(gamerules.set_gamerule(&name, &value))
+}
+
+/// Stops re-applying a gamerule on start. The world keeps its current value.
+#[command]
+pub async fn clear_desired_gamerule(name: String, gamerules: State<'_, Arc<GameruleService>>) -> ApiResponse<()> {
+    info!("'clear_desired_gamerule' command received: {}", name);
+    ApiResponse::from_empty_result(gamerules.clear_desired(&name))
+}
+
+// --- Player Access (whitelist, ops, bans) ---
+
+/// Runs a player access operation on the blocking pool, since online UUID lookups block.
+async fn run_player_access<T, F>(name: &str, access: &Arc<PlayerAccessManager>, operation: F) -> ApiResponse<T>
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce(&PlayerAccessManager) -> Result<T> + Send + 'static,
+{
+    let access = access.clone();
+    let result = tokio::task::spawn_blocking(move || operation(&access)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for {}: {}", name, join_error);
+            ApiResponse::error(format!("Failed to execute {} task: {}", name, join_error))
+        }
+    }
+}
+
+/// Reads the whitelist, ops and ban lists from the server directory.
+#[command]
+pub async fn get_player_access_lists(access: State<'_, Arc<PlayerAccessManager>>) -> ApiResponse<PlayerAccessLists> {
+    ApiResponse::from_result(access.get_lists())
+}
+
+/// Resolves a player name to the UUID the server uses (offline or online mode).
+#[command]
+pub async fn resolve_player(name: String, access: State<'_, Arc<PlayerAccessManager>>) -> ApiResponse<PlayerProfile> {
+    info!("'resolve_player' command received: {}", name);
+    run_player_access("resolve_player", access.inner(), move |access| access.resolve_player(&name)).await
+}
+
+/// Adds a player to the whitelist (through the console if the server is running).
+#[command]
+pub async fn whitelist_add(name: String, access: State<'_, Arc<PlayerAccessManager>>) -> ApiResponse<()> {
+    info!("'whitelist_add' command received: {}", name);
+    run_player_access("whitelist_add", access.inner(), move |access| access.whitelist_add(&name)).await
+}
+
+#[command]
+pub async fn whitelist_remove(name: String, access: State<'_, Arc<PlayerAccessManager>>) -> ApiResponse<()> {
+    info!("'whitelist_remove' command received: {}", name);
+    run_player_access("whitelist_remove", access.inner(), move |access| access.whitelist_remove(&name)).await
+}
+
+/// Makes a player an operator. Levels other than `op-permission-level` need the server stopped.
+#[command]
+pub async fn op_add(
+    name: String,
+    level: u8,
+    bypasses_player_limit: Option<bool>,
+    access: State<'_, Arc<PlayerAccessManager>>,
+) -> ApiResponse<()> {
+    info!("'op_add' command received: {} (level {})", name, level);
+    run_player_access("op_add", access.inner(), move |access| {
+        access.op_add(&name, level, bypasses_player_limit.unwrap_or(false))
+    })
+    .await
+}
+
+#[command]
+pub async fn op_remove(name: String, access: State<'_, Arc<PlayerAccessManager>>) -> ApiResponse<()> {
+    info!("'op_remove' command received: {}", name);
+    run_player_access("op_remove", access.inner(), move |access| access.op_remove(&name)).await
+}
+
+/// Bans a player, optionally with a reason and expiry (temporary bans need the server stopped).
+#[command]
+pub async fn ban_player(
+    name: String,
+    ban: BanRequest,
+    access: State<'_, Arc<PlayerAccessManager>>,
+) -> ApiResponse<()> {
+    info!("'ban_player' command received: {}", name);
+    run_player_access("ban_player", access.inner(), move |access| access.ban_player(&name, &ban)).await
+}
+
+#[command]
+pub async fn pardon_player(name: String, access: State<'_, Arc<PlayerAccessManager>>) -> ApiResponse<()> {
+    info!("'pardon_player' command received: {}", name);
+    run_player_access("pardon_player", access.inner(), move |access| access.pardon_player(&name)).await
+}
+
+#[command]
+pub async fn ban_ip(ip: String, ban: BanRequest, access: State<'_, Arc<PlayerAccessManager>>) -> ApiResponse<()> {
+    info!("'ban_ip' command received: {}", ip);
+    run_player_access("ban_ip", access.inner(), move |access| access.ban_ip(&ip, &ban)).await
+}
+
+#[command]
+pub async fn pardon_ip(ip: String, access: State<'_, Arc<PlayerAccessManager>>) -> ApiResponse<()> {
+    info!("'pardon_ip' command received: {}", ip);
+    run_player_access("pardon_ip", access.inner(), move |access| access.pardon_ip(&ip)).await
+}
+
+/// Bulk-adds entries to one list from CSV text. Rejected lines are listed in the result.
+#[command]
+pub async fn import_player_access_csv(
+    list: AccessList,
+    csv: String,
+    access: State<'_, Arc<PlayerAccessManager>>,
+) -> ApiResponse<CsvImportResult> {
+    info!("'import_player_access_csv' command received for {}", list.file_name());
+    run_player_access("import_player_access_csv", access.inner(), move |access| access.import_csv(list, &csv)).await
+}
+
+// --- Server Software ---
+
+/// Runs a blocking server software operation (HTTP requests, downloads) off the async runtime.
+async fn run_software_task<T, F>(name: &str, software: &Arc<ServerSoftwareManager>, operation: F) -> ApiResponse<T>
+where
+    T: Serialize + Send + 'static,
+    F: FnOnce(&ServerSoftwareManager) -> Result<T> + Send + 'static,
+{
+    let software = software.clone();
+    let result = tokio::task::spawn_blocking(move || operation(&software)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for {}: {}", name, join_error);
+            ApiResponse::error(format!("Failed to execute {} task: {}", name, join_error))
+        }
+    }
+}
+
+#[command]
+pub async fn get_software_sources(software: State<'_, Arc<ServerSoftwareManager>>) -> ApiResponse<SoftwareSources> {
+    ApiResponse::from_result(software.get_sources())
+}
+
+/// Changes the provider base URLs, e.g. to use a local mirror.
+#[command]
+pub async fn set_software_sources(
+    sources: SoftwareSources,
+    software: State<'_, Arc<ServerSoftwareManager>>,
+) -> ApiResponse<()> {
+    info!("'set_software_sources' command received.");
+    ApiResponse::from_empty_result(software.set_sources(sources))
+}
+
+/// Lists Minecraft versions available for a flavor, newest first.
+#[command]
+pub async fn list_server_versions(
+    flavor: ServerFlavor,
+    include_snapshots: Option<bool>,
+    software: State<'_, Arc<ServerSoftwareManager>>,
+) -> ApiResponse<Vec<SoftwareVersion>> {
+    info!("'list_server_versions' command received: {}", flavor);
+    run_software_task("list_server_versions", software.inner(), move |software| {
+        software.list_versions(flavor, include_snapshots.unwrap_or(false))
+    })
+    .await
+}
+
+/// Lists builds of a flavor for one Minecraft version, newest first.
+#[command]
+pub async fn list_server_builds(
+    flavor: ServerFlavor,
+    minecraft_version: String,
+    include_experimental: Option<bool>,
+    software: State<'_, Arc<ServerSoftwareManager>>,
+) -> ApiResponse<Vec<SoftwareBuild>> {
+    info!("'list_server_builds' command received: {} {}", flavor, minecraft_version);
+    run_software_task("list_server_builds", software.inner(), move |software| {
+        software.list_builds(flavor, &minecraft_version, include_experimental.unwrap_or(false))
+    })
+    .await
+}
+
+/// Downloads and verifies a server jar and makes it the one the server starts with.
+/// Progress is reported through `ProgressUpdate` events.
+#[command]
+pub async fn install_server_software(
+    flavor: ServerFlavor,
+    minecraft_version: String,
+    build: Option<String>,
+    software: State<'_, Arc<ServerSoftwareManager>>,
+) -> ApiResponse<InstalledSoftware> {
+    info!("'install_server_software' command received: {} {} {:?}", flavor, minecraft_version, build);
+    run_software_task("install_server_software", software.inner(), move |software| {
+        software.install(flavor, &minecraft_version, build.as_deref())
+    })
+    .await
+}
+
+/// Inspects the server directory and jar to report flavor, Minecraft version, loader
+/// version and the Java version the server needs.
+#[command]
+pub async fn detect_server_software(state: State<'_, Arc<AppState>>) -> ApiResponse<DetectedSoftware> {
+    info!("'detect_server_software' command received.");
+    let state_clone = state.inner().clone();
+    let result = tokio::task::spawn_blocking(move || {
+        software_detector::detect(&state_clone.server_directory, &state_clone.get_server_jar()?)
+    })
+    .await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for detect_server_software: {}", join_error);
+            ApiResponse::error(format!("Failed to execute detection task: {}", join_error))
+        }
+    }
+}
+
+/// Checks the provider for newer builds (with changelogs) and Minecraft versions of the
+/// installed server software.
+#[command]
+pub async fn check_server_upgrade(upgrader: State<'_, Arc<SoftwareUpgrader>>) -> ApiResponse<UpgradeCheck> {
+    info!("'check_server_upgrade' command received.");
+    let upgrader_clone = upgrader.inner().clone();
+    let result = tokio::task::spawn_blocking(move || upgrader_clone.check()).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for check_server_upgrade: {}", join_error);
+            ApiResponse::error(format!("Failed to execute upgrade check task: {}", join_error))
+        }
+    }
+}
+
+/// Backs up the world and config, installs the new jar and starts the server once. If that
+/// start fails, the previous jar and the backup are restored (`rolled_back` in the result).
+#[command]
+pub async fn upgrade_server_software(
+    request: UpgradeRequest,
+    upgrader: State<'_, Arc<SoftwareUpgrader>>,
+) -> ApiResponse<UpgradeResult> {
+    info!("'upgrade_server_software' command received: {:?}", request);
+    let upgrader_clone = upgrader.inner().clone();
+    let result = tokio::task::spawn_blocking(move || upgrader_clone.upgrade(request)).await;
+
+    match result {
+        Ok(inner_result) => ApiResponse::from_result(inner_result),
+        Err(join_error) => {
+            error!("Task execution error for upgrade_server_software: {}", join_error);
+            ApiResponse::error(format!("Failed to execute upgrade task: {}", join_error))
         }
     }
-}
\ No newline at end of file
+}
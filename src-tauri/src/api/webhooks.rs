@@ -0,0 +1,604 @@
+// src/api/webhooks.rs
+
+use crate::api::events::{self, Event};
+use crate::error::{AppError, Result};
+use crate::utils::fs_utils;
+use hmac::{Hmac, Mac};
+use lazy_static::lazy_static;
+use log::{debug, error, info, warn};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use std::collections::{HashMap, HashSet};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Event types delivered when an endpoint doesn't list any. High-frequency events
+/// (metrics, logs, progress) must be requested explicitly.
+const DEFAULT_EVENT_TYPES: &[&str] = &[
+    "ServerStarted",
+    "ServerStopped",
+    "ServerCrashed",
+    "ServerRestarting",
+    "PlayerJoined",
+    "PlayerLeft",
+    "Alert",
+    "BackupCompleted",
+];
+/// Filter entry matching every event type.
+const WILDCARD_EVENT_TYPE: &str = "*";
+/// Default header carrying the HMAC-SHA256 signature.
+const DEFAULT_SIGNATURE_HEADER: &str = "X-Mclh-Signature-256";
+/// Delay before the first retry; doubled on every further attempt.
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+/// Upper bound for the retry delay.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(300);
+/// Maximum response body length kept in test results and dead letters.
+const MAX_RESPONSE_SNIPPET: usize = 512;
+
+const GENERIC_TEMPLATE: &str = r#"{"event": "{{ event_type }}", "timestamp": {{ timestamp }}, "server": "{{ server }}", "summary": "{{ summary }}", "payload": {{ payload }}}"#;
+const DISCORD_TEMPLATE: &str = r#"{"username": "{{ server }}", "content": "{{ summary }}"}"#;
+const SLACK_TEMPLATE: &str = r#"{"text": "*{{ server }}*: {{ summary }}"}"#;
+
+lazy_static! {
+    static ref PLACEHOLDER_REGEX: Regex =
+        Regex::new(r"\{\{ (event_type|summary|server|timestamp|payload) \}\}").unwrap();
+}
+
+/// Payload format of a webhook endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookPreset {
+    /// Full event as JSON: type, timestamp, server, summary and the raw payload.
+    Generic,
+    /// Discord incoming webhook (`content` message).
+    Discord,
+    /// Slack incoming webhook (`text` message).
+    Slack,
+    /// User-supplied `template`.
+    Custom,
+}
+
+/// A configured webhook endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookEndpoint {
+    /// Unique identifier. Generated if left empty when saving.
+    pub id: String,
+    pub name: String,
+    /// Target URL (http or https).
+    pub url: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Event types (`Event` variant names, e.g. "ServerCrashed") to deliver.
+    /// Empty uses a default set of lifecycle/player/alert events; "*" delivers everything.
+    #[serde(default)]
+    pub event_types: Vec<String>,
+    pub preset: WebhookPreset,
+    /// JSON body template for the `Custom` preset. Placeholders: `{{ event_type }}`,
+    /// `{{ summary }}`, `{{ server }}` and `{{ timestamp }}` are inserted as escaped string
+    /// content, `{{ payload }}` is inserted as raw JSON.
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Shared secret for the HMAC-SHA256 signature header. No header is sent if unset.
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Name of the signature header.
+    #[serde(default = "default_signature_header")]
+    pub signature_header: String,
+    /// Retries after the first failed attempt before the delivery is dead-lettered.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Request timeout in seconds.
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_signature_header() -> String {
+    DEFAULT_SIGNATURE_HEADER.to_string()
+}
+
+fn default_max_retries() -> u32 {
+    5
+}
+
+fn default_timeout_secs() -> u64 {
+    10
+}
+
+/// Persisted webhook configuration (`webhooks.json` in the app data directory).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    /// Name used for `{{ server }}` in payloads (e.g., the Discord username).
+    #[serde(default = "default_server_name")]
+    pub server_name: String,
+    #[serde(default)]
+    pub endpoints: Vec<WebhookEndpoint>,
+}
+
+fn default_server_name() -> String {
+    "Minecraft Server".to_string()
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            server_name: default_server_name(),
+            endpoints: Vec::new(),
+        }
+    }
+}
+
+/// Outcome of a single HTTP attempt, returned by `test_endpoint`.
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookTestResult {
+    pub success: bool,
+    pub status: Option<u16>,
+    pub response_body: Option<String>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// A delivery that failed all attempts, appended to the dead-letter log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub endpoint_id: String,
+    pub endpoint_name: String,
+    pub url: String,
+    pub event_type: String,
+    pub body: String,
+    pub attempts: u32,
+    pub last_error: String,
+    /// UNIX timestamp of the final attempt.
+    pub failed_at: u64,
+}
+
+/// A rendered request waiting to be (re)sent.
+#[derive(Debug, Clone)]
+struct Delivery {
+    endpoint: WebhookEndpoint,
+    event_type: String,
+    body: String,
+    /// Attempts made so far.
+    attempts: u32,
+    next_attempt_at: Instant,
+}
+
+/// Delivers backend events to user-configured HTTP endpoints.
+///
+/// A subscriber thread renders matching events into request bodies and hands them to one
+/// delivery thread per endpoint, so a slow or unreachable endpoint only delays its own
+/// deliveries. Delivery threads retry failed requests with exponential backoff and append
+/// deliveries that exhausted their retries to a dead-letter log.
+pub struct WebhookDispatcher {
+    config: RwLock<WebhookConfig>,
+    config_path: PathBuf,
+    dead_letter_path: PathBuf,
+    client: reqwest::blocking::Client,
+    /// Queues of the delivery threads, by endpoint id. Started on the first delivery.
+    workers: Mutex<HashMap<String, Sender<Delivery>>>,
+}
+
+impl WebhookDispatcher {
+    /// Loads the configuration and starts the subscriber thread.
+    pub fn new(config_path: PathBuf, dead_letter_path: PathBuf) -> Arc<Self> {
+        let config = match fs_utils::read_file_to_string(&config_path) {
+            Ok(content) => serde_json::from_str::<WebhookConfig>(&content).unwrap_or_else(|e| {
+                error!("Webhook config {} is corrupt, starting without webhooks: {}", config_path.display(), e);
+                WebhookConfig::default()
+            }),
+            Err(_) => WebhookConfig::default(),
+        };
+        info!("Loaded {} webhook endpoints.", config.endpoints.len());
+
+        let dispatcher = Arc::new(Self {
+            config: RwLock::new(config),
+            config_path,
+            dead_letter_path,
+            client: reqwest::blocking::Client::builder()
+                .user_agent(concat!("minecraft-server-manager/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .unwrap_or_else(|_| reqwest::blocking::Client::new()),
+            workers: Mutex::new(HashMap::new()),
+        });
+
+        let receiver = events::subscribe();
+        let renderer = dispatcher.clone();
+        thread::spawn(move || {
+            debug!("Webhook event subscriber started.");
+            while let Ok(event) = receiver.recv() {
+                renderer.enqueue_event(&event);
+            }
+            debug!("Webhook event subscriber stopped.");
+        });
+
+        dispatcher
+    }
+
+    // --- Configuration ---
+
+    /// Returns the current configuration.
+    pub fn get_config(&self) -> Result<WebhookConfig> {
+        self.config
+            .read()
+            .map(|guard| guard.clone())
+            .map_err(|e| AppError::LockError(format!("Failed to lock webhook config: {}", e)))
+    }
+
+    /// Validates and saves a new configuration. Returns it with generated endpoint ids.
+    pub fn set_config(&self, mut config: WebhookConfig) -> Result<WebhookConfig> {
+        let mut ids = HashSet::new();
+        for endpoint in config.endpoints.iter_mut() {
+            validate_endpoint(endpoint, &config.server_name)?;
+            if !ids.insert(endpoint.id.clone()) {
+                return Err(AppError::ConfigError(format!("Duplicate webhook id '{}'", endpoint.id)));
+            }
+        }
+
+        let json = serde_json::to_string_pretty(&config)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize webhook config: {}", e)))?;
+        fs_utils::write_string_to_file_atomic(&self.config_path, &json)?;
+
+        let mut guard = self
+            .config
+            .write()
+            .map_err(|e| AppError::LockError(format!("Failed to lock webhook config for writing: {}", e)))?;
+        *guard = config.clone();
+        drop(guard);
+        // Dropping a queue stops the delivery thread of a removed endpoint
+        if let Ok(mut workers) = self.workers.lock() {
+            workers.retain(|id, _| config.endpoints.iter().any(|endpoint| &endpoint.id == id));
+        }
+        info!("Saved {} webhook endpoints.", config.endpoints.len());
+        Ok(config)
+    }
+
+    /// Sends a synthetic event to an endpoint once (no retries) and reports the response.
+    /// The endpoint doesn't need to be saved, so it can be tried before enabling it.
+    pub fn test_endpoint(&self, mut endpoint: WebhookEndpoint) -> Result<WebhookTestResult> {
+        let server_name = self.get_config()?.server_name;
+        validate_endpoint(&mut endpoint, &server_name)?;
+        let event = Event::Log(crate::models::log_entry::LogEntry::info(
+            format!("Test delivery for webhook '{}'", endpoint.name),
+            "Webhooks".to_string(),
+        ));
+        let body = render_body(&endpoint, &server_name, &event)?;
+
+        let started = Instant::now();
+        let result = self.send(&endpoint, "Test", &body);
+        let duration_ms = started.elapsed().as_millis() as u64;
+        Ok(match result {
+            Ok((status, response_body)) => WebhookTestResult {
+                success: (200..300).contains(&status),
+                status: Some(status),
+                response_body: Some(response_body),
+                error: None,
+                duration_ms,
+            },
+            Err(e) => WebhookTestResult {
+                success: false,
+                status: None,
+                response_body: None,
+                error: Some(e),
+                duration_ms,
+            },
+        })
+    }
+
+    /// Returns the most recent dead letters, newest last.
+    pub fn get_dead_letters(&self, limit: usize) -> Result<Vec<DeadLetter>> {
+        let file = match std::fs::File::open(&self.dead_letter_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(AppError::IoError(e)),
+        };
+        let letters: Vec<DeadLetter> = BufReader::new(file)
+            .lines()
+            .map_while(|line| line.ok())
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect();
+        let skip = letters.len().saturating_sub(limit);
+        Ok(letters.into_iter().skip(skip).collect())
+    }
+
+    /// Deletes the dead-letter log.
+    pub fn clear_dead_letters(&self) -> Result<()> {
+        match std::fs::remove_file(&self.dead_letter_path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(AppError::IoError(e)),
+        }
+    }
+
+    // --- Dispatch ---
+
+    /// Renders the event for every enabled endpoint whose filter matches and queues it.
+    fn enqueue_event(self: &Arc<Self>, event: &Event) {
+        let config = match self.config.read() {
+            Ok(guard) => guard.clone(),
+            Err(e) => {
+                error!("Failed to lock webhook config: {}", e);
+                return;
+            }
+        };
+        let event_type = event.type_name();
+
+        for endpoint in config.endpoints.iter().filter(|e| e.enabled && accepts(e, event_type)) {
+            match render_body(endpoint, &config.server_name, event) {
+                Ok(body) => {
+                    let delivery = Delivery {
+                        endpoint: endpoint.clone(),
+                        event_type: event_type.to_string(),
+                        body,
+                        attempts: 0,
+                        next_attempt_at: Instant::now(),
+                    };
+                    self.queue_delivery(delivery);
+                }
+                Err(e) => warn!("Failed to render {} event for webhook '{}': {}", event_type, endpoint.name, e),
+            }
+        }
+    }
+
+    /// Hands a delivery to its endpoint's delivery thread, starting the thread if needed.
+    fn queue_delivery(self: &Arc<Self>, delivery: Delivery) {
+        let mut workers = match self.workers.lock() {
+            Ok(guard) => guard,
+            Err(e) => {
+                error!("Failed to lock webhook delivery queues: {}", e);
+                return;
+            }
+        };
+        let id = delivery.endpoint.id.clone();
+        let delivery = match workers.get(&id) {
+            Some(sender) => match sender.send(delivery) {
+                Ok(()) => return,
+                // The thread is gone; start a new one below
+                Err(e) => e.0,
+            },
+            None => delivery,
+        };
+        let (sender, receiver) = channel::<Delivery>();
+        let worker = self.clone();
+        let name = delivery.endpoint.name.clone();
+        thread::spawn(move || worker.run_delivery_loop(&name, receiver));
+        if sender.send(delivery).is_err() {
+            error!("Webhook delivery thread for '{}' is gone, dropping event.", id);
+        }
+        workers.insert(id, sender);
+    }
+
+    /// Sends one endpoint's queued deliveries and retries failed ones once their backoff
+    /// has elapsed.
+    fn run_delivery_loop(&self, endpoint_name: &str, receiver: Receiver<Delivery>) {
+        debug!("Webhook delivery thread for '{}' started.", endpoint_name);
+        let mut retries: Vec<Delivery> = Vec::new();
+
+        loop {
+            let timeout = retries
+                .iter()
+                .map(|d| d.next_attempt_at.saturating_duration_since(Instant::now()))
+                .min()
+                .unwrap_or(Duration::from_secs(60));
+
+            match receiver.recv_timeout(timeout) {
+                Ok(delivery) => {
+                    if let Some(retry) = self.attempt(delivery) {
+                        retries.push(retry);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            let now = Instant::now();
+            let (due, waiting): (Vec<Delivery>, Vec<Delivery>) =
+                retries.drain(..).partition(|d| d.next_attempt_at <= now);
+            retries = waiting;
+            for delivery in due {
+                if let Some(retry) = self.attempt(delivery) {
+                    retries.push(retry);
+                }
+            }
+        }
+        debug!("Webhook delivery thread for '{}' stopped.", endpoint_name);
+    }
+
+    /// Performs one attempt. Returns the delivery rescheduled for a retry if it failed
+    /// and has retries left; dead-letters it otherwise.
+    fn attempt(&self, mut delivery: Delivery) -> Option<Delivery> {
+        delivery.attempts += 1;
+        let error = match self.send(&delivery.endpoint, &delivery.event_type, &delivery.body) {
+            Ok((status, _)) if (200..300).contains(&status) => {
+                debug!(
+                    "Delivered {} to webhook '{}' (HTTP {}, attempt {})",
+                    delivery.event_type, delivery.endpoint.name, status, delivery.attempts
+                );
+                return None;
+            }
+            Ok((status, body)) => format!("HTTP {}: {}", status, body),
+            Err(e) => e,
+        };
+
+        if delivery.attempts <= delivery.endpoint.max_retries {
+            let delay = RETRY_BASE_DELAY
+                .saturating_mul(2u32.saturating_pow(delivery.attempts - 1))
+                .min(RETRY_MAX_DELAY);
+            warn!(
+                "Webhook '{}' delivery of {} failed (attempt {}), retrying in {:?}: {}",
+                delivery.endpoint.name, delivery.event_type, delivery.attempts, delay, error
+            );
+            delivery.next_attempt_at = Instant::now() + delay;
+            return Some(delivery);
+        }
+
+        error!(
+            "Webhook '{}' delivery of {} failed after {} attempts, moving to dead-letter log: {}",
+            delivery.endpoint.name, delivery.event_type, delivery.attempts, error
+        );
+        self.write_dead_letter(&delivery, error);
+        None
+    }
+
+    /// Posts a body to an endpoint. Returns the status code and a response snippet,
+    /// or a description of the transport error.
+    fn send(&self, endpoint: &WebhookEndpoint, event_type: &str, body: &str) -> std::result::Result<(u16, String), String> {
+        let timestamp = now_secs().to_string();
+        let mut request = self
+            .client
+            .post(&endpoint.url)
+            .timeout(Duration::from_secs(endpoint.timeout_secs.max(1)))
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header("X-Mclh-Event", event_type)
+            .header("X-Mclh-Timestamp", &timestamp);
+
+        if let Some(secret) = endpoint.secret.as_deref().filter(|s| !s.is_empty()) {
+            request = request.header(endpoint.signature_header.as_str(), sign(secret, &timestamp, body));
+        }
+
+        let response = request.body(body.to_string()).send().map_err(|e| e.to_string())?;
+        let status = response.status().as_u16();
+        let mut text = response.text().unwrap_or_default();
+        truncate_at_char_boundary(&mut text, MAX_RESPONSE_SNIPPET);
+        Ok((status, text))
+    }
+
+    fn write_dead_letter(&self, delivery: &Delivery, last_error: String) {
+        let letter = DeadLetter {
+            endpoint_id: delivery.endpoint.id.clone(),
+            endpoint_name: delivery.endpoint.name.clone(),
+            url: delivery.endpoint.url.clone(),
+            event_type: delivery.event_type.clone(),
+            body: delivery.body.clone(),
+            attempts: delivery.attempts,
+            last_error,
+            failed_at: now_secs(),
+        };
+        let line = match serde_json::to_string(&letter) {
+            Ok(line) => line,
+            Err(e) => {
+                error!("Failed to serialize webhook dead letter: {}", e);
+                return;
+            }
+        };
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.dead_letter_path)
+            .and_then(|mut file| writeln!(file, "{}", line));
+        if let Err(e) = result {
+            error!("Failed to write webhook dead letter to {}: {}", self.dead_letter_path.display(), e);
+        }
+    }
+}
+
+/// Returns true if the endpoint's filter includes the event type.
+fn accepts(endpoint: &WebhookEndpoint, event_type: &str) -> bool {
+    if endpoint.event_types.is_empty() {
+        return DEFAULT_EVENT_TYPES.contains(&event_type);
+    }
+    endpoint
+        .event_types
+        .iter()
+        .any(|t| t == WILDCARD_EVENT_TYPE || t.eq_ignore_ascii_case(event_type))
+}
+
+/// Checks URL, template and retry settings and fills in a generated id.
+fn validate_endpoint(endpoint: &mut WebhookEndpoint, server_name: &str) -> Result<()> {
+    endpoint.name = endpoint.name.trim().to_string();
+    if endpoint.name.is_empty() {
+        return Err(AppError::ConfigError("Webhook name cannot be empty".to_string()));
+    }
+    let url = reqwest::Url::parse(endpoint.url.trim())
+        .map_err(|e| AppError::ConfigError(format!("Webhook '{}': invalid URL: {}", endpoint.name, e)))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(AppError::ConfigError(format!(
+            "Webhook '{}': URL must use http or https",
+            endpoint.name
+        )));
+    }
+    endpoint.url = url.to_string();
+    if endpoint.signature_header.trim().is_empty() {
+        endpoint.signature_header = default_signature_header();
+    }
+    if endpoint.preset == WebhookPreset::Custom && endpoint.template.as_deref().map_or(true, |t| t.trim().is_empty()) {
+        return Err(AppError::ConfigError(format!(
+            "Webhook '{}': custom preset requires a template",
+            endpoint.name
+        )));
+    }
+    // Render a sample event so template mistakes surface when saving, not on delivery
+    render_body(endpoint, server_name, &Event::ServerCrashed("Sample \"reason\"".to_string()))
+        .map_err(|e| AppError::ConfigError(format!("Webhook '{}': {}", endpoint.name, e)))?;
+    if endpoint.id.trim().is_empty() {
+        endpoint.id = format!("webhook-{:08x}", rand::random::<u32>());
+    }
+    Ok(())
+}
+
+/// Renders the request body for an event and checks that it is valid JSON.
+fn render_body(endpoint: &WebhookEndpoint, server_name: &str, event: &Event) -> Result<String> {
+    let template = match endpoint.preset {
+        WebhookPreset::Generic => GENERIC_TEMPLATE,
+        WebhookPreset::Discord => DISCORD_TEMPLATE,
+        WebhookPreset::Slack => SLACK_TEMPLATE,
+        WebhookPreset::Custom => endpoint.template.as_deref().unwrap_or_default(),
+    };
+
+    let payload = serde_json::to_value(event)
+        .map(|v| v.get("payload").cloned().unwrap_or(serde_json::Value::Null))
+        .map_err(|e| AppError::ConfigError(format!("Failed to serialize event: {}", e)))?;
+
+    // One pass over the template, so placeholders inside inserted values stay literal text
+    let body = PLACEHOLDER_REGEX.replace_all(template, |caps: &Captures| match &caps[1] {
+        "event_type" => json_escape(event.type_name()),
+        "summary" => json_escape(&event.summary()),
+        "server" => json_escape(server_name),
+        "timestamp" => now_secs().to_string(),
+        _ => payload.to_string(),
+    });
+
+    serde_json::from_str::<serde_json::Value>(&body)
+        .map_err(|e| AppError::ConfigError(format!("Template does not render to valid JSON: {}", e)))?;
+    Ok(body.into_owned())
+}
+
+/// Escapes a value for insertion between JSON string quotes.
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// Computes `sha256=<hex>` over `<timestamp>.<body>`, so receivers can reject replays.
+fn sign(secret: &str, timestamp: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+fn truncate_at_char_boundary(text: &mut String, max_len: usize) {
+    if text.len() > max_len {
+        let mut end = max_len;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
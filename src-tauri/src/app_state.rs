@@ -1,4 +1,5 @@
-﻿use crate::error::{AppError, Result};
+﻿use crate::config::manager_config::ManagerConfig;
+use crate::error::{AppError, Result};
 use crate::models::config::ServerConfig; // Import ServerConfig for direct property access (optional)
 use crate::models::metrics::MetricsData;
 use crate::models::server_status::ServerStatus;
@@ -20,10 +21,13 @@ pub struct AppState {
     pub server_directory: PathBuf,
     /// Path to the detected Java executable.
     pub java_path: PathBuf,
-    /// Name of the server JAR file (e.g., "server.jar", "paper.jar").
-    pub server_jar: String,
+    /// Name of the server JAR file (e.g., "server.jar", "paper.jar"). Replaced when new
+    /// server software is installed.
+    pub server_jar: RwLock<String>,
     /// Command-line arguments to pass to the Java process.
     pub server_args: RwLock<Vec<String>>,
+    /// Arguments appended after the jar on the next start only (e.g. `--forceUpgrade`).
+    pub next_start_args: Mutex<Vec<String>>,
     /// Handle to the running server process, if active. Managed by process_manager.
     pub process_handle: Mutex<Option<Child>>,
     /// Timeout in seconds for graceful server shutdown before forcing termination.
@@ -61,14 +65,32 @@ impl AppState {
             metrics: Mutex::new(MetricsData::default()), // player_count starts at 0 here
             server_directory: server_dir_path,
             java_path: java_path_buf,
-            server_jar,
+            server_jar: RwLock::new(server_jar),
             server_args: RwLock::new(default_java_args),
+            next_start_args: Mutex::new(Vec::new()),
             process_handle: Mutex::new(None),
             stop_timeout_secs: 30, // Default timeout
             server_properties: RwLock::new(initial_properties), // Start empty
         }))
     }
 
+    /// Creates the application state from the persisted manager config.
+    /// `java_path` is the resolved Java executable (the config override or the detected one).
+    pub fn from_config(server_directory: PathBuf, java_path: PathBuf, config: &ManagerConfig) -> Result<Arc<Self>> {
+        Ok(Arc::new(Self {
+            server_status: Mutex::new(ServerStatus::Stopped),
+            metrics: Mutex::new(MetricsData::default()),
+            server_directory,
+            java_path,
+            server_jar: RwLock::new(config.server_jar.clone()),
+            server_args: RwLock::new(config.server.java_args.clone()),
+            next_start_args: Mutex::new(Vec::new()),
+            process_handle: Mutex::new(None),
+            stop_timeout_secs: config.stop_timeout_secs,
+            server_properties: RwLock::new(config.server.server_properties.clone()),
+        }))
+    }
+
     // --- Helper methods ---
 
     /// Gets the current server status.
@@ -168,6 +190,40 @@ impl AppState {
         Ok(())
     }
 
+    /// Gets the name of the server JAR file.
+    pub fn get_server_jar(&self) -> Result<String> {
+        self.server_jar
+            .read()
+            .map(|guard| guard.clone())
+            .map_err(|e| AppError::LockError(format!("Failed to lock server_jar for reading: {}", e)))
+    }
+
+    /// Switches to another JAR inside the server directory (takes effect on the next start).
+    pub fn set_server_jar(&self, jar: String) -> Result<()> {
+        let mut guard = self.server_jar
+            .write()
+            .map_err(|e| AppError::LockError(format!("Failed to lock server_jar for writing: {}", e)))?;
+        *guard = jar;
+        Ok(())
+    }
+
+    /// Sets arguments for the next start only; they are cleared once the server is launched.
+    pub fn set_next_start_args(&self, args: Vec<String>) -> Result<()> {
+        let mut guard = self.next_start_args
+            .lock()
+            .map_err(|e| AppError::LockError(format!("Failed to lock next_start_args: {}", e)))?;
+        *guard = args;
+        Ok(())
+    }
+
+    /// Takes the one-time start arguments, leaving the list empty.
+    pub(crate) fn take_next_start_args(&self) -> Result<Vec<String>> {
+        self.next_start_args
+            .lock()
+            .map(|mut guard| std::mem::take(&mut *guard))
+            .map_err(|e| AppError::LockError(format!("Failed to lock next_start_args: {}", e)))
+    }
+
     /// Gets a clone of the cached server properties.
     pub fn get_server_properties(&self) -> Result<HashMap<String, String>> {
         self.server_properties
@@ -205,6 +261,14 @@ impl AppState {
         Ok(())
     }
 
+    /// Returns the OS process ID of the running server, read from the stored `Child` handle.
+    pub fn get_process_id(&self) -> Result<Option<u32>> {
+        self.process_handle
+            .lock()
+            .map(|guard| guard.as_ref().map(|child| child.id()))
+            .map_err(|e| AppError::LockError(format!("Failed to lock process_handle for PID: {}", e)))
+    }
+
     // --- Other Getters ---
 
     /// Gets the configured stop timeout.
@@ -212,9 +276,10 @@ impl AppState {
         Duration::from_secs(self.stop_timeout_secs)
     }
 
-    /// Gets the full path to the server JAR file.
-    pub fn get_server_jar_path(&self) -> PathBuf {
-        self.server_directory.join(&self.server_jar)
+    /// Gets the full path to the server JAR file. For an argument file launch
+    /// (`@libraries/.../unix_args.txt`) this is the argument file.
+    pub fn get_server_jar_path(&self) -> Result<PathBuf> {
+        Ok(self.server_directory.join(self.get_server_jar()?.trim_start_matches('@')))
     }
 }
 
@@ -0,0 +1,319 @@
+// src/commands/gamerules.rs
+
+use crate::api::events::{self, Event};
+use crate::app_state::AppState;
+use crate::commands::process_manager;
+use crate::error::{AppError, Result};
+use crate::models::gamerule::{GameruleInfo, GameruleReport, GameruleType, GameruleValue};
+use crate::models::server_status::ServerStatus;
+use crate::utils::fs_utils;
+use lazy_static::lazy_static;
+use log::{debug, error, info, warn};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Upper bound for collecting the answers to a full gamerule query.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+/// The query ends early once no answer arrived for this long.
+const QUERY_IDLE_TIMEOUT: Duration = Duration::from_millis(1500);
+
+lazy_static! {
+    // "Gamerule keepInventory is currently set to: false" (query) or "... is now set to: true" (set)
+    static ref GAMERULE_REGEX: Regex =
+        Regex::new(r"Gamerule (\w+) is (?:currently|now) set to: (\S+)").unwrap();
+}
+
+const fn bool_rule(name: &'static str, default: bool) -> GameruleInfo {
+    GameruleInfo { name, rule_type: GameruleType::Bool, default: GameruleValue::Bool(default), min: None }
+}
+
+const fn int_rule(name: &'static str, default: i64, min: i64) -> GameruleInfo {
+    GameruleInfo { name, rule_type: GameruleType::Int, default: GameruleValue::Int(default), min: Some(min) }
+}
+
+/// Vanilla gamerules (Java Edition 1.21). Rules missing from older versions simply don't
+/// answer a query.
+static GAMERULES: &[GameruleInfo] = &[
+    bool_rule("announceAdvancements", true),
+    bool_rule("blockExplosionDropDecay", true),
+    bool_rule("commandBlockOutput", true),
+    int_rule("commandModificationBlockLimit", 32768, 1),
+    bool_rule("disableElytraMovementCheck", false),
+    bool_rule("disableRaids", false),
+    bool_rule("doDaylightCycle", true),
+    bool_rule("doEntityDrops", true),
+    bool_rule("doFireTick", true),
+    bool_rule("doImmediateRespawn", false),
+    bool_rule("doInsomnia", true),
+    bool_rule("doLimitedCrafting", false),
+    bool_rule("doMobLoot", true),
+    bool_rule("doMobSpawning", true),
+    bool_rule("doPatrolSpawning", true),
+    bool_rule("doTileDrops", true),
+    bool_rule("doTraderSpawning", true),
+    bool_rule("doVinesSpread", true),
+    bool_rule("doWardenSpawning", true),
+    bool_rule("doWeatherCycle", true),
+    bool_rule("drowningDamage", true),
+    bool_rule("enderPearlsVanishOnDeath", true),
+    bool_rule("fallDamage", true),
+    bool_rule("fireDamage", true),
+    bool_rule("forgiveDeadPlayers", true),
+    bool_rule("freezeDamage", true),
+    bool_rule("globalSoundEvents", true),
+    bool_rule("keepInventory", false),
+    bool_rule("lavaSourceConversion", false),
+    bool_rule("logAdminCommands", true),
+    int_rule("maxCommandChainLength", 65536, 0),
+    int_rule("maxCommandForkCount", 65536, 0),
+    int_rule("maxEntityCramming", 24, 0),
+    bool_rule("mobExplosionDropDecay", true),
+    bool_rule("mobGriefing", true),
+    bool_rule("naturalRegeneration", true),
+    int_rule("playersNetherPortalCreativeDelay", 1, 0),
+    int_rule("playersNetherPortalDefaultDelay", 80, 0),
+    int_rule("playersSleepingPercentage", 100, 0),
+    bool_rule("projectilesCanBreakBlocks", true),
+    int_rule("randomTickSpeed", 3, 0),
+    bool_rule("reducedDebugInfo", false),
+    bool_rule("sendCommandFeedback", true),
+    bool_rule("showDeathMessages", true),
+    int_rule("snowAccumulationHeight", 1, 0),
+    int_rule("spawnChunkRadius", 2, 0),
+    int_rule("spawnRadius", 10, 0),
+    bool_rule("spectatorsGenerateChunks", true),
+    bool_rule("tntExplosionDropDecay", false),
+    bool_rule("universalAnger", false),
+    bool_rule("waterSourceConversion", true),
+];
+
+/// Looks up a vanilla gamerule.
+pub fn get_gamerule_info(name: &str) -> Option<&'static GameruleInfo> {
+    GAMERULES.iter().find(|rule| rule.name == name)
+}
+
+/// Parses and validates a value for a rule. Unknown (modded) rules accept any boolean or
+/// integer as long as the name is well-formed.
+pub fn parse_gamerule_value(name: &str, value: &str) -> Result<GameruleValue> {
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(AppError::ConfigError(format!("Invalid gamerule name '{}'", name)));
+    }
+    let parsed = GameruleValue::parse(value)
+        .ok_or_else(|| AppError::ConfigError(format!("Gamerule {}: '{}' is not a boolean or whole number", name, value)))?;
+    if let Some(info) = get_gamerule_info(name) {
+        if parsed.value_type() != info.rule_type {
+            let expected = match info.rule_type {
+                GameruleType::Bool => "true or false",
+                GameruleType::Int => "a whole number",
+            };
+            return Err(AppError::ConfigError(format!("Gamerule {} must be {}", name, expected)));
+        }
+        if let (GameruleValue::Int(number), Some(min)) = (parsed, info.min) {
+            if number < min {
+                return Err(AppError::ConfigError(format!("Gamerule {} must be at least {}", name, min)));
+            }
+        }
+    }
+    Ok(parsed)
+}
+
+/// Reads and sets gamerules through the server console.
+///
+/// Gamerules are stored in `level.dat`, so the manager can only change them while the
+/// server runs. Values set here are also recorded as "desired" (`gamerules.json` in the
+/// app data directory) and re-applied every time the server starts, which restores them
+/// after a world reset.
+pub struct GameruleService {
+    state: Arc<AppState>,
+    /// Values parsed from console output since the last query started.
+    observed: Mutex<HashMap<String, GameruleValue>>,
+    observed_changed: Condvar,
+    /// Serializes queries so their answers don't mix.
+    query_lock: Mutex<()>,
+    desired: RwLock<BTreeMap<String, GameruleValue>>,
+    storage_path: PathBuf,
+}
+
+impl GameruleService {
+    /// Loads the desired values and starts the subscriber that parses console output and
+    /// re-applies desired values on startup.
+    pub fn new(state: Arc<AppState>, storage_path: PathBuf) -> Arc<Self> {
+        let desired = match fs_utils::read_file_to_string(&storage_path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                error!("Gamerule file {} is corrupt, no gamerules will be enforced: {}", storage_path.display(), e);
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+
+        let service = Arc::new(Self {
+            state,
+            observed: Mutex::new(HashMap::new()),
+            observed_changed: Condvar::new(),
+            query_lock: Mutex::new(()),
+            desired: RwLock::new(desired),
+            storage_path,
+        });
+
+        let receiver = events::subscribe();
+        let worker = service.clone();
+        thread::spawn(move || {
+            debug!("Gamerule service event subscriber started.");
+            while let Ok(event) = receiver.recv() {
+                match event {
+                    Event::Log(entry) => worker.handle_console_line(&entry.message),
+                    Event::StatusChanged(ServerStatus::Running) => worker.apply_desired(),
+                    _ => {}
+                }
+            }
+            debug!("Gamerule service event subscriber stopped.");
+        });
+        service
+    }
+
+    /// Queries every known gamerule from the running server. While the server is stopped,
+    /// only the desired values are returned.
+    pub fn get_gamerules(&self) -> Result<GameruleReport> {
+        let running = self.state.get_status()? == ServerStatus::Running;
+        let values = if running { self.query_all()? } else { BTreeMap::new() };
+        Ok(GameruleReport {
+            running,
+            values,
+            desired: self.get_desired()?,
+            catalog: GAMERULES.to_vec(),
+        })
+    }
+
+    /// Validates and records a value, and sets it right away if the server is running.
+    pub fn set_gamerule(&self, name: &str, value: &str) -> Result<GameruleValue> {
+        let parsed = parse_gamerule_value(name, value)?;
+        self.record_desired(&BTreeMap::from([(name.to_string(), parsed)]))?;
+
+        if self.state.get_status()? == ServerStatus::Running {
+            process_manager::send_command_to_server(self.state.clone(), format!("gamerule {} {}", name, parsed))?;
+            info!("Set gamerule {} to {}", name, parsed);
+        } else {
+            info!("Server not running; gamerule {} = {} will be applied on the next start.", name, parsed);
+        }
+        Ok(parsed)
+    }
+
+    /// Stops enforcing a rule. The world keeps its current value.
+    pub fn clear_desired(&self, name: &str) -> Result<()> {
+        let mut guard = self.write_desired()?;
+        if guard.remove(name).is_some() {
+            self.save(&guard)?;
+            info!("Gamerule {} is no longer enforced.", name);
+        }
+        Ok(())
+    }
+
+    /// Returns the values re-applied on every start.
+    pub fn get_desired(&self) -> Result<BTreeMap<String, GameruleValue>> {
+        self.desired
+            .read()
+            .map(|guard| guard.clone())
+            .map_err(|e| AppError::LockError(format!("Failed to lock desired gamerules: {}", e)))
+    }
+
+    /// Adds values to the desired set without sending anything (used by presets).
+    pub fn record_desired(&self, values: &BTreeMap<String, GameruleValue>) -> Result<()> {
+        let mut guard = self.write_desired()?;
+        guard.extend(values.iter().map(|(k, v)| (k.clone(), *v)));
+        self.save(&guard)
+    }
+
+    // --- Internals ---
+
+    /// Sends one query per known rule and collects the answers from the console.
+    fn query_all(&self) -> Result<BTreeMap<String, GameruleValue>> {
+        let _query = self
+            .query_lock
+            .lock()
+            .map_err(|e| AppError::LockError(format!("Failed to lock gamerule query: {}", e)))?;
+        self.lock_observed()?.clear();
+
+        for rule in GAMERULES {
+            process_manager::send_command_to_server(self.state.clone(), format!("gamerule {}", rule.name))?;
+        }
+
+        let deadline = Instant::now() + QUERY_TIMEOUT;
+        let mut last_change = Instant::now();
+        let mut guard = self.lock_observed()?;
+        let mut answered = guard.len();
+        while answered < GAMERULES.len() {
+            let now = Instant::now();
+            if now >= deadline || (answered > 0 && now.duration_since(last_change) >= QUERY_IDLE_TIMEOUT) {
+                break;
+            }
+            guard = self
+                .observed_changed
+                .wait_timeout(guard, Duration::from_millis(200))
+                .map_err(|e| AppError::LockError(format!("Failed to wait for gamerule answers: {}", e)))?
+                .0;
+            if guard.len() != answered {
+                answered = guard.len();
+                last_change = Instant::now();
+            }
+        }
+
+        if answered < GAMERULES.len() {
+            debug!("{} of {} gamerules answered (others may not exist in this version).", answered, GAMERULES.len());
+        }
+        Ok(guard.iter().map(|(k, v)| (k.clone(), *v)).collect())
+    }
+
+    fn handle_console_line(&self, line: &str) {
+        let Some(caps) = GAMERULE_REGEX.captures(line) else { return };
+        let Some(value) = GameruleValue::parse(&caps[2]) else { return };
+        match self.lock_observed() {
+            Ok(mut guard) => {
+                guard.insert(caps[1].to_string(), value);
+                self.observed_changed.notify_all();
+            }
+            Err(e) => error!("{}", e),
+        }
+    }
+
+    /// Sends all desired values to the freshly started server.
+    fn apply_desired(&self) {
+        let desired = match self.get_desired() {
+            Ok(desired) => desired,
+            Err(e) => {
+                error!("{}", e);
+                return;
+            }
+        };
+        if desired.is_empty() {
+            return;
+        }
+        info!("Re-applying {} desired gamerules.", desired.len());
+        for (name, value) in desired {
+            if let Err(e) = process_manager::send_command_to_server(self.state.clone(), format!("gamerule {} {}", name, value)) {
+                warn!("Failed to apply gamerule {}: {}", name, e);
+            }
+        }
+    }
+
+    fn lock_observed(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, GameruleValue>>> {
+        self.observed
+            .lock()
+            .map_err(|e| AppError::LockError(format!("Failed to lock observed gamerules: {}", e)))
+    }
+
+    fn write_desired(&self) -> Result<std::sync::RwLockWriteGuard<'_, BTreeMap<String, GameruleValue>>> {
+        self.desired
+            .write()
+            .map_err(|e| AppError::LockError(format!("Failed to lock desired gamerules for writing: {}", e)))
+    }
+
+    fn save(&self, desired: &BTreeMap<String, GameruleValue>) -> Result<()> {
+        let json = serde_json::to_string_pretty(desired)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize gamerules: {}", e)))?;
+        fs_utils::write_string_to_file_atomic(&self.storage_path, &json)
+    }
+}
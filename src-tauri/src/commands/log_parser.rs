@@ -0,0 +1,77 @@
+// src/commands/log_parser.rs
+
+use crate::models::server_software::ServerFlavor;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+/// Regexes for one console log layout. Each pattern captures the player name in group 1.
+struct LogPatterns {
+    player_joined: Regex,
+    player_left: Regex,
+    startup_done: Regex,
+}
+
+impl LogPatterns {
+    /// Builds the patterns for a layout whose INFO lines start with `prefix`. Anchoring on the
+    /// prefix keeps chat messages such as "<Steve> Alex joined the game" from matching.
+    fn with_prefix(prefix: &str) -> Self {
+        Self {
+            player_joined: Regex::new(&format!(
+                r"{}([a-zA-Z0-9_]{{3,16}})(?:\[[^\]]+\] logged in| joined the game)",
+                prefix
+            ))
+            .unwrap(),
+            player_left: Regex::new(&format!(r"{}([a-zA-Z0-9_]{{3,16}})(?: lost connection| left the game)", prefix))
+                .unwrap(),
+            startup_done: Regex::new(&format!(r"{}Done \([^)]+\)!", prefix)).unwrap(),
+        }
+    }
+}
+
+lazy_static! {
+    // "[15:30:00] [Server thread/INFO]: Steve joined the game"
+    static ref VANILLA: LogPatterns = LogPatterns::with_prefix(r"^\[[^\]]+\] \[Server thread/INFO\]: ");
+    // "[15:30:00 INFO]: Steve joined the game"
+    static ref BUKKIT: LogPatterns = LogPatterns::with_prefix(r"^\[[^\]]+ INFO\]: ");
+    // Loaders add the logger name: "[15:30:00] [Server thread/INFO] [minecraft/MinecraftServer]: ..."
+    // or "[15:30:00] [Server thread/INFO] (Minecraft) ..."
+    static ref MODDED: LogPatterns =
+        LogPatterns::with_prefix(r"^\[[^\]]+\] \[Server thread/INFO\](?: \[[^\]]+\])?(?: \([^)]+\))?:? ");
+    // Unknown software: the original loose patterns that accept any of the layouts above
+    static ref GENERIC: LogPatterns = LogPatterns {
+        player_joined: Regex::new(r"\[[^\]]+\]: ([a-zA-Z0-9_]{3,16})(?:\[[^\]]+\] logged in| joined the game)").unwrap(),
+        player_left: Regex::new(r"\[[^\]]+\]: ([a-zA-Z0-9_]{3,16})(?: lost connection| left the game)").unwrap(),
+        startup_done: Regex::new(r#"Done \(.+s\)!|INFO\]: Server marked as active|For help, type "help""#).unwrap(),
+    };
+}
+
+/// Recognizes player joins/leaves and the end of startup in console output, using the log
+/// layout of the detected server software.
+#[derive(Clone, Copy)]
+pub struct LogParser {
+    patterns: &'static LogPatterns,
+}
+
+impl LogParser {
+    pub fn for_flavor(flavor: ServerFlavor) -> Self {
+        let patterns: &'static LogPatterns = match flavor {
+            ServerFlavor::Vanilla => &VANILLA,
+            ServerFlavor::Paper | ServerFlavor::Purpur | ServerFlavor::Spigot => &BUKKIT,
+            ServerFlavor::Fabric | ServerFlavor::Quilt | ServerFlavor::Forge | ServerFlavor::NeoForge => &MODDED,
+            ServerFlavor::Unknown => &GENERIC,
+        };
+        Self { patterns }
+    }
+
+    pub fn player_joined(&self, line: &str) -> Option<String> {
+        self.patterns.player_joined.captures(line).map(|caps| caps[1].to_string())
+    }
+
+    pub fn player_left(&self, line: &str) -> Option<String> {
+        self.patterns.player_left.captures(line).map(|caps| caps[1].to_string())
+    }
+
+    pub fn is_startup_done(&self, line: &str) -> bool {
+        self.patterns.startup_done.is_match(line)
+    }
+}
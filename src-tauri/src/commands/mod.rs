@@ -1,2 +1,6 @@
 ﻿pub mod process_manager;
-pub mod command_executor;
\ No newline at end of file
+pub mod command_executor;
+pub mod startup_queue;
+pub mod gamerules;
+pub mod player_access;
+pub mod log_parser;
\ No newline at end of file
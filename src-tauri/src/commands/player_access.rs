@@ -0,0 +1,421 @@
+// src/commands/player_access.rs
+
+use crate::app_state::AppState;
+use crate::commands::process_manager;
+use crate::config::server_properties;
+use crate::error::{AppError, Result};
+use crate::models::player_access::{
+    AccessList, BanEntry, BanRequest, CsvImportResult, IpBanEntry, OpEntry, PlayerAccessLists, PlayerProfile,
+    WhitelistEntry,
+};
+use crate::models::server_status::ServerStatus;
+use crate::utils::fs_utils;
+use crate::utils::uuid_resolver::{self, CachingResolver};
+use chrono::{DateTime, Utc};
+use log::{debug, info};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Date format used by the server in the ban lists.
+const BAN_DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S %z";
+/// Source recorded for bans added by the manager (the server uses the same for console bans).
+const BAN_SOURCE: &str = "Server";
+
+/// Manages `whitelist.json`, `ops.json`, `banned-players.json` and `banned-ips.json`.
+///
+/// While the server is stopped the files are edited directly. While it runs, changes go
+/// through console commands instead: the server keeps the lists in memory and would
+/// overwrite direct edits on its next save. While it starts or stops, changes are rejected.
+pub struct PlayerAccessManager {
+    state: Arc<AppState>,
+    resolver: Arc<CachingResolver>,
+    /// Serializes read-modify-write cycles on the list files.
+    file_lock: Mutex<()>,
+}
+
+impl PlayerAccessManager {
+    pub fn new(state: Arc<AppState>, resolver: Arc<CachingResolver>) -> Self {
+        Self {
+            state,
+            resolver,
+            file_lock: Mutex::new(()),
+        }
+    }
+
+    /// Reads all four lists from disk. Missing files are treated as empty lists.
+    pub fn get_lists(&self) -> Result<PlayerAccessLists> {
+        Ok(PlayerAccessLists {
+            whitelist: self.read_list(AccessList::Whitelist)?,
+            ops: self.read_list(AccessList::Ops)?,
+            banned_players: self.read_list(AccessList::BannedPlayers)?,
+            banned_ips: self.read_list(AccessList::BannedIps)?,
+        })
+    }
+
+    /// Resolves a name to the UUID the server will use: computed locally in offline mode,
+    /// looked up online (through the cache) otherwise.
+    pub fn resolve_player(&self, name: &str) -> Result<PlayerProfile> {
+        validate_player_name(name)?;
+        if !self.is_online_mode()? {
+            return Ok(PlayerProfile {
+                uuid: uuid_resolver::offline_uuid(name),
+                name: name.to_string(),
+            });
+        }
+        self.resolver
+            .resolve_online(name)?
+            .ok_or_else(|| AppError::ConfigError(format!("No Minecraft account named '{}'", name)))
+    }
+
+    pub fn whitelist_add(&self, name: &str) -> Result<()> {
+        let profile = self.resolve_player(name)?;
+        if self.is_running()? {
+            return self.send(format!("whitelist add {}", profile.name));
+        }
+        self.modify_list(AccessList::Whitelist, |list: &mut Vec<WhitelistEntry>| {
+            list.retain(|entry| entry.uuid != profile.uuid);
+            list.push(WhitelistEntry { uuid: profile.uuid.clone(), name: profile.name.clone() });
+            Ok(())
+        })
+    }
+
+    pub fn whitelist_remove(&self, name: &str) -> Result<()> {
+        validate_player_name(name)?;
+        if self.is_running()? {
+            return self.send(format!("whitelist remove {}", name));
+        }
+        self.modify_list(AccessList::Whitelist, |list: &mut Vec<WhitelistEntry>| {
+            remove_player(list, name, |entry| &entry.name)
+        })
+    }
+
+    /// Makes a player an operator with the given permission level (1-4).
+    ///
+    /// The console `op` command always uses `op-permission-level` from server.properties, so
+    /// while the server runs only that level can be granted.
+    pub fn op_add(&self, name: &str, level: u8, bypasses_player_limit: bool) -> Result<()> {
+        if !(1..=4).contains(&level) {
+            return Err(AppError::ConfigError(format!("Op level must be between 1 and 4, got {}", level)));
+        }
+        let profile = self.resolve_player(name)?;
+        if self.is_running()? {
+            let default_level = self.default_op_level()?;
+            if level != default_level || bypasses_player_limit {
+                return Err(AppError::ServerError(format!(
+                    "While the server is running, ops can only be added with the default level {} \
+                     (op-permission-level); stop the server to set level {} or the player limit bypass",
+                    default_level, level
+                )));
+            }
+            return self.send(format!("op {}", profile.name));
+        }
+        self.modify_list(AccessList::Ops, |list: &mut Vec<OpEntry>| {
+            list.retain(|entry| entry.uuid != profile.uuid);
+            list.push(OpEntry {
+                uuid: profile.uuid.clone(),
+                name: profile.name.clone(),
+                level,
+                bypasses_player_limit,
+            });
+            Ok(())
+        })
+    }
+
+    pub fn op_remove(&self, name: &str) -> Result<()> {
+        validate_player_name(name)?;
+        if self.is_running()? {
+            return self.send(format!("deop {}", name));
+        }
+        self.modify_list(AccessList::Ops, |list: &mut Vec<OpEntry>| remove_player(list, name, |entry| &entry.name))
+    }
+
+    /// Bans a player. Temporary bans have no console command, so they need the server stopped.
+    pub fn ban_player(&self, name: &str, request: &BanRequest) -> Result<()> {
+        let reason = validate_reason(request)?;
+        let profile = self.resolve_player(name)?;
+        if self.is_running()? {
+            if request.expires.is_some() {
+                return Err(AppError::ServerError(
+                    "Temporary bans can't be added through the console; stop the server first".to_string(),
+                ));
+            }
+            return self.send(console_with_reason(format!("ban {}", profile.name), &request.reason));
+        }
+        self.modify_list(AccessList::BannedPlayers, |list: &mut Vec<BanEntry>| {
+            list.retain(|entry| entry.uuid != profile.uuid);
+            list.push(BanEntry {
+                uuid: profile.uuid.clone(),
+                name: profile.name.clone(),
+                created: format_ban_date(Utc::now()),
+                source: BAN_SOURCE.to_string(),
+                expires: format_expiry(request.expires),
+                reason: reason.clone(),
+            });
+            Ok(())
+        })
+    }
+
+    pub fn pardon_player(&self, name: &str) -> Result<()> {
+        validate_player_name(name)?;
+        if self.is_running()? {
+            return self.send(format!("pardon {}", name));
+        }
+        self.modify_list(AccessList::BannedPlayers, |list: &mut Vec<BanEntry>| {
+            remove_player(list, name, |entry| &entry.name)
+        })
+    }
+
+    /// Bans an IP address. Like player bans, temporary bans need the server stopped.
+    pub fn ban_ip(&self, ip: &str, request: &BanRequest) -> Result<()> {
+        let ip = parse_ip(ip)?;
+        let reason = validate_reason(request)?;
+        if self.is_running()? {
+            if request.expires.is_some() {
+                return Err(AppError::ServerError(
+                    "Temporary bans can't be added through the console; stop the server first".to_string(),
+                ));
+            }
+            return self.send(console_with_reason(format!("ban-ip {}", ip), &request.reason));
+        }
+        self.modify_list(AccessList::BannedIps, |list: &mut Vec<IpBanEntry>| {
+            list.retain(|entry| entry.ip != ip);
+            list.push(IpBanEntry {
+                ip: ip.clone(),
+                created: format_ban_date(Utc::now()),
+                source: BAN_SOURCE.to_string(),
+                expires: format_expiry(request.expires),
+                reason: reason.clone(),
+            });
+            Ok(())
+        })
+    }
+
+    pub fn pardon_ip(&self, ip: &str) -> Result<()> {
+        let ip = parse_ip(ip)?;
+        if self.is_running()? {
+            return self.send(format!("pardon-ip {}", ip));
+        }
+        self.modify_list(AccessList::BannedIps, |list: &mut Vec<IpBanEntry>| {
+            let before = list.len();
+            list.retain(|entry| entry.ip != ip);
+            if list.len() == before {
+                return Err(AppError::ConfigError(format!("{} is not banned", ip)));
+            }
+            Ok(())
+        })
+    }
+
+    /// Adds entries from CSV text, one per line. Columns per list:
+    /// whitelist `name`; ops `name,level[,bypassesPlayerLimit]`;
+    /// banned players `name[,reason[,expires]]`; banned IPs `ip[,reason[,expires]]`.
+    /// `expires` is RFC 3339 or empty for a permanent ban. A header line is skipped.
+    /// Bad lines are reported and don't stop the import.
+    pub fn import_csv(&self, list: AccessList, csv: &str) -> Result<CsvImportResult> {
+        let mut result = CsvImportResult::default();
+        for (index, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields = split_csv_line(line);
+            let first = fields[0].to_lowercase();
+            if index == 0 && (first == "name" || first == "ip") {
+                continue;
+            }
+            match self.import_row(list, &fields) {
+                Ok(()) => result.imported += 1,
+                Err(e) => result.errors.push(format!("Line {}: {}", index + 1, e)),
+            }
+        }
+        info!(
+            "Imported {} entries into {} ({} rejected).",
+            result.imported,
+            list.file_name(),
+            result.errors.len()
+        );
+        Ok(result)
+    }
+
+    // --- Internals ---
+
+    fn import_row(&self, list: AccessList, fields: &[String]) -> Result<()> {
+        let field = |i: usize| fields.get(i).map(String::as_str).unwrap_or("");
+        match list {
+            AccessList::Whitelist => self.whitelist_add(field(0)),
+            AccessList::Ops => {
+                let level = match field(1) {
+                    "" => self.default_op_level()?,
+                    level => level
+                        .parse()
+                        .map_err(|_| AppError::ConfigError(format!("Invalid op level '{}'", level)))?,
+                };
+                let bypass = match field(2).to_lowercase().as_str() {
+                    "" | "false" => false,
+                    "true" => true,
+                    other => return Err(AppError::ConfigError(format!("Invalid bypassesPlayerLimit '{}'", other))),
+                };
+                self.op_add(field(0), level, bypass)
+            }
+            AccessList::BannedPlayers | AccessList::BannedIps => {
+                let expires = match field(2) {
+                    "" => None,
+                    expires => Some(
+                        DateTime::parse_from_rfc3339(expires)
+                            .map_err(|e| AppError::ConfigError(format!("Invalid expiry '{}': {}", expires, e)))?
+                            .with_timezone(&Utc),
+                    ),
+                };
+                let request = BanRequest {
+                    reason: Some(field(1).to_string()).filter(|reason| !reason.is_empty()),
+                    expires,
+                };
+                if list == AccessList::BannedPlayers {
+                    self.ban_player(field(0), &request)
+                } else {
+                    self.ban_ip(field(0), &request)
+                }
+            }
+        }
+    }
+
+    /// Whether changes go through the console (true) or the files (false). The JVM may hold
+    /// the lists from the moment it starts until it has exited, so the files are only edited
+    /// while it is stopped.
+    fn is_running(&self) -> Result<bool> {
+        match self.state.get_status()? {
+            ServerStatus::Stopped | ServerStatus::Error(_) => Ok(false),
+            ServerStatus::Running => Ok(true),
+            status => Err(AppError::ServerError(format!(
+                "Player lists can't be changed while the server is {}; try again once it is running or stopped",
+                status.to_string().to_lowercase()
+            ))),
+        }
+    }
+
+    fn is_online_mode(&self) -> Result<bool> {
+        let properties = server_properties::read_properties_file(&self.state)?;
+        Ok(properties.get("online-mode").map(|value| value.trim()) != Some("false"))
+    }
+
+    fn default_op_level(&self) -> Result<u8> {
+        let properties = server_properties::read_properties_file(&self.state)?;
+        Ok(properties
+            .get("op-permission-level")
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(4))
+    }
+
+    fn send(&self, command: String) -> Result<()> {
+        info!("Updating player access through the console: {}", command);
+        process_manager::send_command_to_server(self.state.clone(), command)
+    }
+
+    fn list_path(&self, list: AccessList) -> PathBuf {
+        self.state.server_directory.join(list.file_name())
+    }
+
+    fn read_list<T: DeserializeOwned>(&self, list: AccessList) -> Result<Vec<T>> {
+        let path = self.list_path(list);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs_utils::read_file_to_string(&path)?;
+        if content.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        serde_json::from_str(&content)
+            .map_err(|e| AppError::ConfigError(format!("Failed to parse {}: {}", path.display(), e)))
+    }
+
+    fn modify_list<T, F>(&self, list: AccessList, modify: F) -> Result<()>
+    where
+        T: DeserializeOwned + Serialize,
+        F: FnOnce(&mut Vec<T>) -> Result<()>,
+    {
+        let _guard = self
+            .file_lock
+            .lock()
+            .map_err(|e| AppError::LockError(format!("Failed to lock player access files: {}", e)))?;
+        let mut entries = self.read_list(list)?;
+        modify(&mut entries)?;
+        let json = serde_json::to_string_pretty(&entries)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize {}: {}", list.file_name(), e)))?;
+        fs_utils::write_string_to_file_atomic(&self.list_path(list), &json)?;
+        debug!("Wrote {} ({} entries).", list.file_name(), entries.len());
+        Ok(())
+    }
+}
+
+/// Player names are 3-16 characters of letters, digits and underscores.
+fn validate_player_name(name: &str) -> Result<()> {
+    if !(3..=16).contains(&name.len()) || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(AppError::ConfigError(format!("Invalid player name '{}'", name)));
+    }
+    Ok(())
+}
+
+fn parse_ip(ip: &str) -> Result<String> {
+    ip.trim()
+        .parse::<IpAddr>()
+        .map(|ip| ip.to_string())
+        .map_err(|_| AppError::ConfigError(format!("Invalid IP address '{}'", ip)))
+}
+
+/// Returns the reason to store, rejecting ones that would break a console command.
+fn validate_reason(request: &BanRequest) -> Result<String> {
+    match &request.reason {
+        Some(reason) if reason.contains(['\n', '\r']) => {
+            Err(AppError::ConfigError("Ban reason must be a single line".to_string()))
+        }
+        Some(reason) if !reason.trim().is_empty() => Ok(reason.trim().to_string()),
+        _ => Ok("Banned by an operator.".to_string()),
+    }
+}
+
+fn console_with_reason(command: String, reason: &Option<String>) -> String {
+    match reason.as_deref().map(str::trim) {
+        Some(reason) if !reason.is_empty() => format!("{} {}", command, reason),
+        _ => command,
+    }
+}
+
+fn remove_player<T>(list: &mut Vec<T>, name: &str, entry_name: impl Fn(&T) -> &String) -> Result<()> {
+    let before = list.len();
+    list.retain(|entry| !entry_name(entry).eq_ignore_ascii_case(name));
+    if list.len() == before {
+        return Err(AppError::ConfigError(format!("'{}' is not on the list", name)));
+    }
+    Ok(())
+}
+
+fn format_ban_date(date: DateTime<Utc>) -> String {
+    date.format(BAN_DATE_FORMAT).to_string()
+}
+
+fn format_expiry(expires: Option<DateTime<Utc>>) -> String {
+    expires.map_or_else(|| "forever".to_string(), format_ban_date)
+}
+
+/// Splits a CSV line, honouring double-quoted fields with `""` escapes.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut current).trim().to_string()),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current.trim().to_string());
+    fields
+}
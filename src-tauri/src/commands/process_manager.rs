@@ -1,17 +1,20 @@
 ﻿use crate::api::events::{
     self, emit_app_error, emit_event, emit_info, emit_log, emit_player_joined, // Import specific player events
-    emit_player_left, emit_status_change, emit_warn, Event,
+    emit_player_left, emit_server_crashed, emit_status_change, emit_warn, Event,
 };
 use crate::app_state::AppState;
+use crate::commands::log_parser::LogParser;
+use crate::config::software_detector;
 use crate::error::{AppError, Result};
 use crate::models::log_entry::{LogEntry, LogLevel}; // Import LogLevel
 use crate::models::metrics::MetricsData;
+use crate::models::server_software::ServerFlavor;
 use crate::models::server_status::ServerStatus;
-use lazy_static::lazy_static; // Use lazy_static for regex
+use crate::monitoring::jvm_metrics;
+use crate::utils::java_detector;
 use log::{debug, error, info, warn};
-use regex::Regex; // Import Regex
 use std::io::{BufRead, BufReader, Write};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command, Stdio};
 use std::sync::Arc;
 use std::thread;
@@ -21,32 +24,6 @@ use wait_timeout::ChildExt;
 const STDOUT_SOURCE: &str = "Server";
 const STDERR_SOURCE: &str = "Server";
 
-// --- Regex Definitions ---
-lazy_static! {
-    // Example Regex - Adjust patterns based on exact server log format (Vanilla / Paper)
-    // Captures: 1: Player Name (standard username chars/length)
-    static ref PLAYER_JOIN_REGEX: Regex = Regex::new(
-        // Matches lines like: "[15:30:00 INFO]: PlayerName[/127.0.0.1:12345] logged in with entity id ..."
-        // Or: "[Server thread/INFO]: PlayerName joined the game"
-        r"\[[^\]]+\]: ([a-zA-Z0-9_]{3,16})(?:\[[^\]]+\] logged in| joined the game)"
-    ).unwrap();
-    // Captures: 1: Player Name
-    static ref PLAYER_LEFT_REGEX: Regex = Regex::new(
-        // Matches lines like: "[15:30:05 INFO]: PlayerName lost connection: Disconnected"
-        // Or: "[Server thread/INFO]: PlayerName left the game"
-        r"\[[^\]]+\]: ([a-zA-Z0-9_]{3,16})(?: lost connection| left the game)"
-    ).unwrap();
-    // Regex for server startup detection (keep or adjust)
-    static ref SERVER_DONE_REGEX: Regex = Regex::new(
-        // Matches vanilla "Done", Paper "Server marked as active", and generic "help" message
-        r"Done \(.+s\)!|INFO\]: Server marked as active|For help, type \"help\""
-    ).unwrap();
-
-    // TODO: Add regex for TPS if applicable (e.g., Paper "/tps" command output)
-    // Example for Paper output:
-    // static ref TPS_REGEX: Regex = Regex::new(r"TPS from last 1m, 5m, 15m: (\*?\d+\.\d{2}), (\*?\d+\.\d{2}), (\*?\d+\.\d{2})").unwrap();
-}
-
 /// Starts the Minecraft server process.
 ///
 /// - Checks current state.
@@ -82,7 +59,7 @@ pub fn start_server(state: Arc<AppState>) -> Result<()> {
     } // Status lock released
 
     // --- Path and Config Validation ---
-    let server_jar_path = state.get_server_jar_path();
+    let server_jar_path = state.get_server_jar_path()?;
     if !server_jar_path.exists() {
         error!("Server JAR file not found at: {:?}", server_jar_path);
         // Revert state on failure
@@ -91,19 +68,51 @@ pub fn start_server(state: Arc<AppState>) -> Result<()> {
         return Err(AppError::ServerJarNotFound(server_jar_path));
     }
 
+    // --- Software Detection: picks the log parser and, if needed, another Java ---
+    let server_jar = state.get_server_jar()?;
+    let detected = match software_detector::detect(&state.server_directory, &server_jar) {
+        Ok(detected) => Some(detected),
+        Err(e) => {
+            warn!("Could not detect server software, using generic log parsing: {}", e);
+            None
+        }
+    };
+    let log_parser = LogParser::for_flavor(detected.as_ref().map_or(ServerFlavor::Unknown, |d| d.flavor));
+    let java_path = select_java(&state, detected.as_ref().and_then(|d| d.required_java));
+
     let java_args = state.get_server_args()?; // Read args using lock helper
     let mut final_args = java_args.clone(); // Start with configured JVM args
-    // "-jar" should already be in default_args, but check just in case
-    if !final_args.contains(&"-jar".to_string()) {
+    // Enable the GC log read by the JVM metrics collector unless the user configured -Xlog themselves
+    if !final_args.iter().any(|arg| arg.starts_with("-Xlog:gc") || arg.starts_with("-Xloggc")) {
+        match java_detector::get_java_version(&java_path) {
+            Ok((major, _, _, _)) if major >= 9 => {
+                if let Err(e) = std::fs::create_dir_all(state.server_directory.join("logs")) {
+                    warn!("Could not create logs directory for GC log: {}", e);
+                }
+                final_args.extend(jvm_metrics::gc_log_jvm_args());
+            }
+            Ok((major, _, _, _)) => {
+                debug!("Java {} has no unified logging; JVM metrics will use jstat.", major);
+            }
+            Err(e) => warn!("Could not determine Java version, GC log not enabled: {}", e),
+        }
+    }
+    // Forge/NeoForge 1.17+ start through an argument file ("@libraries/.../unix_args.txt")
+    // that names the main class itself, so there is no jar to pass
+    if server_jar.starts_with('@') {
+        final_args.retain(|arg| arg != "-jar");
+    } else if !final_args.contains(&"-jar".to_string()) {
+        // "-jar" should already be in default_args, but check just in case
         final_args.push("-jar".to_string());
     }
-    final_args.push(state.server_jar.clone()); // Add the specific jar name
+    final_args.push(server_jar); // Add the specific jar name
     // Add nogui if needed for server type (often prevents separate GUI window)
     final_args.push("nogui".to_string());
+    final_args.extend(state.take_next_start_args()?);
     debug!("Java arguments: {:?}", final_args);
 
     // --- Process Spawning ---
-    let mut command = Command::new(&state.java_path);
+    let mut command = Command::new(&java_path);
     command
         .args(&final_args)
         .current_dir(&state.server_directory)
@@ -113,7 +122,7 @@ pub fn start_server(state: Arc<AppState>) -> Result<()> {
 
     info!(
         "Spawning Java process: {:?} with args {:?}",
-        state.java_path, final_args
+        java_path, final_args
     );
     let mut process: Child = match command.spawn() {
         Ok(p) => p,
@@ -158,30 +167,24 @@ pub fn start_server(state: Arc<AppState>) -> Result<()> {
                     emit_log(LogLevel::Info, line.clone(), STDOUT_SOURCE.to_string()); // Use LogLevel::Info
 
                     // --- Player Count Parsing ---
-                    if let Some(caps) = PLAYER_JOIN_REGEX.captures(&line) {
-                        if let Some(player_name) = caps.get(1) {
-                            let name = player_name.as_str().to_string();
-                            debug!("Detected player join: {}", name);
-                            state_stdout.increment_player_count();
-                            emit_player_joined(name); // Use specific event helper
-                        }
-                    } else if let Some(caps) = PLAYER_LEFT_REGEX.captures(&line) {
-                        if let Some(player_name) = caps.get(1) {
-                            let name = player_name.as_str().to_string();
-                            debug!("Detected player leave: {}", name);
-                            state_stdout.decrement_player_count();
-                            emit_player_left(name); // Use specific event helper
-                        }
+                    if let Some(name) = log_parser.player_joined(&line) {
+                        debug!("Detected player join: {}", name);
+                        state_stdout.increment_player_count();
+                        emit_player_joined(name); // Use specific event helper
+                    } else if let Some(name) = log_parser.player_left(&line) {
+                        debug!("Detected player leave: {}", name);
+                        state_stdout.decrement_player_count();
+                        emit_player_left(name); // Use specific event helper
                     }
 
                     // --- Server Startup Detection ---
-                    // Use SERVER_DONE_REGEX
-                    if !detected_running && SERVER_DONE_REGEX.is_match(&line) {
+                    if !detected_running && log_parser.is_startup_done(&line) {
                         debug!("Detected server startup completion message: '{}'", line);
                         match state_stdout.get_status() {
                             Ok(ServerStatus::Starting) => {
                                 if state_stdout.set_status(ServerStatus::Running).is_ok() {
                                     emit_status_change(ServerStatus::Running);
+                                    emit_event(Event::ServerStarted);
                                     info!("Server status updated to Running.");
                                     detected_running = true;
                                 } else {
@@ -228,7 +231,7 @@ pub fn start_server(state: Arc<AppState>) -> Result<()> {
                 state_stdout.reset_player_count();
                 if state_stdout.set_status(ServerStatus::Stopped).is_ok() {
                     emit_status_change(ServerStatus::Stopped);
-                    emit_warn(
+                    emit_server_crashed(
                         "Server process stopped unexpectedly.".to_string(),
                         "ProcessManager".to_string(),
                     );
@@ -275,6 +278,34 @@ pub fn start_server(state: Arc<AppState>) -> Result<()> {
     Ok(())
 }
 
+/// Returns the Java to launch with: the configured one, unless the software needs a newer
+/// major version (`required`) and a suitable installation exists. Also used for installers.
+pub(crate) fn select_java(state: &AppState, required: Option<u32>) -> PathBuf {
+    let Some(required) = required else {
+        return state.java_path.clone();
+    };
+    match java_detector::get_java_version(&state.java_path) {
+        Ok((major, _, _, _)) if major >= required => state.java_path.clone(),
+        Ok((major, _, _, _)) => match java_detector::find_java_with_min_major(required) {
+            Ok(path) => {
+                info!("Configured Java {} is too old (needs {}+), using {}", major, required, path.display());
+                path
+            }
+            Err(_) => {
+                emit_warn(
+                    format!("The server needs Java {}+ but only Java {} was found; it will likely fail to start.", required, major),
+                    "ProcessManager".to_string(),
+                );
+                state.java_path.clone()
+            }
+        },
+        Err(e) => {
+            warn!("Could not determine version of configured Java: {}", e);
+            state.java_path.clone()
+        }
+    }
+}
+
 /// Stops the Minecraft server process gracefully, with a timeout and force kill fallback.
 ///
 /// - Checks current state.
@@ -450,6 +481,7 @@ pub fn stop_server(state: Arc<AppState>) -> Result<()> {
         state_stop.reset_player_count(); // Reset player count on confirmed stop
         if state_stop.set_status(ServerStatus::Stopped).is_ok() {
             emit_status_change(ServerStatus::Stopped);
+            emit_event(Event::ServerStopped);
         } else {
             error!("Failed to lock state to set status to Stopped in stop thread.");
         }
@@ -465,6 +497,7 @@ pub fn stop_server(state: Arc<AppState>) -> Result<()> {
 /// Restarts the server by stopping it and then starting it again.
 pub fn restart_server(state: Arc<AppState>) -> Result<()> {
     info!("Restart command received. Stopping server first...");
+    emit_event(Event::ServerRestarting);
     // Call stop_server. It handles state changes and runs async in a thread for waiting.
     stop_server(state.clone())?;
 
@@ -536,6 +569,13 @@ fn send_command_internal(
     handle_guard: &mut std::sync::MutexGuard<Option<Child>>, // Pass the mutable lock guard
     command: String,
 ) -> Result<()> {
+    // A line break would end this command and start another one on the console
+    if command.chars().any(char::is_control) {
+        warn!("Rejected command containing control characters: {:?}", command);
+        return Err(AppError::ServerError(
+            "Commands must not contain line breaks or other control characters".to_string(),
+        ));
+    }
     if let Some(process) = handle_guard.as_mut() {
         if let Some(stdin) = process.stdin.as_mut() {
             debug!("Writing command '{}' to stdin...", command);
@@ -0,0 +1,119 @@
+// src/commands/startup_queue.rs
+
+use crate::api::events::{self, Event};
+use crate::app_state::AppState;
+use crate::commands::process_manager;
+use crate::error::{AppError, Result};
+use crate::models::server_status::ServerStatus;
+use crate::utils::fs_utils;
+use log::{debug, error, info, warn};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Console commands waiting for the next successful server start.
+///
+/// The queue is persisted so commands survive a restart of the manager. When the server
+/// reaches `Running`, the commands are sent in order; ones that fail to send stay queued.
+pub struct StartupCommandQueue {
+    state: Arc<AppState>,
+    commands: Mutex<Vec<String>>,
+    storage_path: PathBuf,
+}
+
+impl StartupCommandQueue {
+    /// Loads the queue and starts the event subscriber that flushes it on startup.
+    pub fn new(state: Arc<AppState>, storage_path: PathBuf) -> Arc<Self> {
+        let commands = match fs_utils::read_file_to_string(&storage_path) {
+            Ok(content) => serde_json::from_str::<Vec<String>>(&content).unwrap_or_else(|e| {
+                error!("Startup command queue {} is corrupt, starting empty: {}", storage_path.display(), e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        if !commands.is_empty() {
+            info!("{} console commands queued for the next server start.", commands.len());
+        }
+
+        let queue = Arc::new(Self {
+            state,
+            commands: Mutex::new(commands),
+            storage_path,
+        });
+
+        let receiver = events::subscribe();
+        let worker = queue.clone();
+        thread::spawn(move || {
+            debug!("Startup command queue subscriber started.");
+            while let Ok(event) = receiver.recv() {
+                if let Event::StatusChanged(ServerStatus::Running) = event {
+                    worker.flush();
+                }
+            }
+            debug!("Startup command queue subscriber stopped.");
+        });
+        queue
+    }
+
+    /// Appends commands to the queue.
+    pub fn enqueue(&self, commands: Vec<String>) -> Result<()> {
+        let mut guard = self.lock()?;
+        guard.extend(commands.into_iter().map(|c| c.trim().to_string()).filter(|c| !c.is_empty()));
+        self.save(&guard)?;
+        debug!("Startup command queue now holds {} commands.", guard.len());
+        Ok(())
+    }
+
+    /// Returns the queued commands in the order they will be sent.
+    pub fn get_pending(&self) -> Result<Vec<String>> {
+        self.lock().map(|guard| guard.clone())
+    }
+
+    /// Drops all queued commands.
+    pub fn clear(&self) -> Result<()> {
+        let mut guard = self.lock()?;
+        guard.clear();
+        self.save(&guard)?;
+        info!("Cleared the startup command queue.");
+        Ok(())
+    }
+
+    /// Sends the queued commands to the running server.
+    fn flush(&self) {
+        let mut guard = match self.lock() {
+            Ok(guard) => guard,
+            Err(e) => {
+                error!("{}", e);
+                return;
+            }
+        };
+        if guard.is_empty() {
+            return;
+        }
+
+        info!("Server started, sending {} queued commands.", guard.len());
+        let mut failed = Vec::new();
+        for command in guard.drain(..) {
+            if let Err(e) = process_manager::send_command_to_server(self.state.clone(), command.clone()) {
+                warn!("Queued command '{}' could not be sent, keeping it for the next start: {}", command, e);
+                failed.push(command);
+            }
+        }
+        *guard = failed;
+        if let Err(e) = self.save(&guard) {
+            error!("Failed to persist the startup command queue: {}", e);
+        }
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, Vec<String>>> {
+        self.commands
+            .lock()
+            .map_err(|e| AppError::LockError(format!("Failed to lock startup command queue: {}", e)))
+    }
+
+    fn save(&self, commands: &[String]) -> Result<()> {
+        let json = serde_json::to_string_pretty(commands)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize startup command queue: {}", e)))?;
+        fs_utils::write_string_to_file_atomic(&self.storage_path, &json)
+    }
+}
@@ -0,0 +1,106 @@
+// src/config/backup.rs
+
+use crate::api::events::{emit_event, Event};
+use crate::app_state::AppState;
+use crate::commands::process_manager;
+use crate::config::server_properties;
+use crate::error::Result;
+use crate::models::server_status::ServerStatus;
+use crate::utils::fs_utils;
+use log::{info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// Root-level files copied into backups, by extension.
+const CONFIG_EXTENSIONS: &[&str] = &["properties", "json", "yml", "yaml", "toml", "txt"];
+/// Config directories copied into backups.
+const CONFIG_DIRECTORIES: &[&str] = &["config", "defaultconfigs"];
+/// How long a running server gets to write its chunks after `save-all flush`.
+const SAVE_FLUSH_WAIT: Duration = Duration::from_secs(5);
+
+/// Backs up the world dimensions and config files to `backups/backup-<time>`.
+///
+/// A running server is told to flush its chunks and stop autosaving while the files are
+/// copied (`save-off`, `save-all flush`, then `save-on`).
+pub fn create_backup(state: &Arc<AppState>) -> Result<PathBuf> {
+    with_backup_events(|| {
+        let backup_dir = new_backup_dir(state, "backup")?;
+        let running = state.get_status()? == ServerStatus::Running;
+        let result = if running { flush_world(state) } else { Ok(()) }
+            .and_then(|_| copy_world_and_configs(state, &backup_dir));
+        if running {
+            if let Err(e) = process_manager::send_command_to_server(state.clone(), "save-on".to_string()) {
+                warn!("Could not re-enable autosave after the backup: {}", e);
+            }
+        }
+        let size = result?;
+        info!("Backup written to {} ({} bytes)", backup_dir.display(), size);
+        Ok((backup_dir, size))
+    })
+}
+
+/// Stops autosaving and has the running server write all chunks to disk.
+fn flush_world(state: &Arc<AppState>) -> Result<()> {
+    for command in ["save-off", "save-all flush"] {
+        process_manager::send_command_to_server(state.clone(), command.to_string())?;
+    }
+    thread::sleep(SAVE_FLUSH_WAIT);
+    Ok(())
+}
+
+/// Runs `backup` between `BackupStarted` and `BackupCompleted` events. `backup` returns the
+/// backup directory and the number of bytes it copied.
+pub(crate) fn with_backup_events(backup: impl FnOnce() -> Result<(PathBuf, u64)>) -> Result<PathBuf> {
+    emit_event(Event::BackupStarted);
+    let result = backup();
+    emit_event(Event::BackupCompleted {
+        result: result.as_ref().map(|_| ()).map_err(|e| e.to_string()),
+        size_bytes: result.as_ref().ok().map(|(_, size)| *size),
+    });
+    result.map(|(dir, _)| dir)
+}
+
+/// Creates `backups/<prefix>-<time>` in the server directory.
+pub(crate) fn new_backup_dir(state: &Arc<AppState>, prefix: &str) -> Result<PathBuf> {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let backup_dir = state.server_directory.join("backups").join(format!("{}-{}", prefix, timestamp));
+    fs_utils::ensure_directory(&backup_dir)?;
+    Ok(backup_dir)
+}
+
+/// Copies the world dimensions, config directories and root-level config files into
+/// `backup_dir`. Returns the number of bytes copied.
+pub(crate) fn copy_world_and_configs(state: &Arc<AppState>, backup_dir: &Path) -> Result<u64> {
+    let server_dir = &state.server_directory;
+    let level_name = server_properties::read_properties_file(state)?
+        .get("level-name")
+        .cloned()
+        .unwrap_or_else(|| "world".to_string());
+    let mut size = 0;
+    let directories = [level_name.clone(), format!("{}_nether", level_name), format!("{}_the_end", level_name)]
+        .into_iter()
+        .chain(CONFIG_DIRECTORIES.iter().map(|d| d.to_string()));
+    for name in directories {
+        let source = server_dir.join(&name);
+        if source.is_dir() {
+            size += fs_utils::copy_directory(&source, &backup_dir.join(&name))?;
+        }
+    }
+    for entry in fs::read_dir(server_dir)? {
+        let path = entry?.path();
+        let is_config = path.is_file()
+            && path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| CONFIG_EXTENSIONS.contains(&ext));
+        if is_config {
+            if let Some(name) = path.file_name() {
+                size += fs::copy(&path, backup_dir.join(name))?;
+            }
+        }
+    }
+    Ok(size)
+}
@@ -0,0 +1,618 @@
+// src/config/config_files.rs
+
+use crate::app_state::AppState;
+use crate::config::server_properties;
+use crate::error::{AppError, Result};
+use crate::models::config_file::{ConfigEditResult, ConfigFileContent, ConfigFileInfo, ConfigFormat};
+use crate::utils::fs_utils;
+use log::{debug, info, warn};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use toml_edit::DocumentMut;
+use walkdir::WalkDir;
+
+/// Files larger than this are not listed or opened (logs and caches, not configs).
+const MAX_CONFIG_FILE_SIZE: u64 = 4 * 1024 * 1024;
+
+/// Well-known config files in the server root, with what they configure.
+const KNOWN_FILES: &[(&str, &str)] = &[
+    ("bukkit.yml", "Bukkit server settings"),
+    ("spigot.yml", "Spigot settings"),
+    ("paper.yml", "Paper settings (before 1.19)"),
+    ("config/paper-global.yml", "Paper global settings"),
+    ("config/paper-world-defaults.yml", "Paper default world settings"),
+    ("purpur.yml", "Purpur settings"),
+    ("commands.yml", "Command aliases"),
+    ("permissions.yml", "Bukkit permissions"),
+    ("help.yml", "Help topics"),
+    ("ops.json", "Server operators"),
+    ("whitelist.json", "Whitelisted players"),
+    ("banned-players.json", "Banned players"),
+    ("banned-ips.json", "Banned IP addresses"),
+];
+
+/// Directories scanned for mod and plugin configs, with the maximum depth below them.
+const SCANNED_DIRECTORIES: &[(&str, usize)] = &[
+    ("config", 4),         // Forge/Fabric/Quilt mod configs, Paper 1.19+
+    ("defaultconfigs", 3), // Forge configs copied into new worlds
+    ("plugins", 3),        // Bukkit plugin configs
+];
+
+// --- Discovery ---
+
+/// Lists the editable config files in the server directory.
+pub fn list_config_files(state: &Arc<AppState>) -> Result<Vec<ConfigFileInfo>> {
+    let server_dir = &state.server_directory;
+    let mut files: BTreeMap<String, ConfigFileInfo> = BTreeMap::new();
+
+    for (relative, description) in KNOWN_FILES {
+        if let Some(info) = describe_file(server_dir, &server_dir.join(relative)) {
+            files.insert(info.path.clone(), ConfigFileInfo {
+                description: Some(description.to_string()),
+                ..info
+            });
+        }
+    }
+
+    // Forge 1.13-1.20 keeps per-world server configs inside the world folder
+    let level_name = server_properties::read_properties_file(state)?
+        .get("level-name")
+        .cloned()
+        .unwrap_or_else(|| "world".to_string());
+    let world_config_dir = format!("{}/serverconfig", level_name);
+    let scanned = SCANNED_DIRECTORIES
+        .iter()
+        .map(|(dir, depth)| (dir.to_string(), *depth))
+        .chain(std::iter::once((world_config_dir, 1)));
+
+    for (dir, max_depth) in scanned {
+        let root = server_dir.join(&dir);
+        if !root.is_dir() {
+            continue;
+        }
+        for entry in WalkDir::new(&root).max_depth(max_depth).into_iter().filter_map(|e| e.ok()) {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            if let Some(info) = describe_file(server_dir, entry.path()) {
+                files.entry(info.path.clone()).or_insert(info);
+            }
+        }
+    }
+
+    debug!("Found {} config files in {}", files.len(), server_dir.display());
+    Ok(files.into_values().collect())
+}
+
+/// Builds the listing entry for a file, or None if it isn't an editable config.
+fn describe_file(server_dir: &Path, path: &Path) -> Option<ConfigFileInfo> {
+    let format = ConfigFormat::from_extension(path.extension()?.to_str()?)?;
+    let metadata = path.metadata().ok().filter(|m| m.is_file())?;
+    if metadata.len() > MAX_CONFIG_FILE_SIZE {
+        return None;
+    }
+    let relative = path.strip_prefix(server_dir).ok()?;
+    let relative = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+        .join("/");
+    let description = if relative.ends_with("-server.toml") {
+        Some("Forge mod server config".to_string())
+    } else {
+        None
+    };
+    Some(ConfigFileInfo {
+        path: relative,
+        format,
+        size_bytes: metadata.len(),
+        description,
+    })
+}
+
+// --- Reading ---
+
+/// Reads a config file and parses it into a tree.
+pub fn read_config_file(state: &Arc<AppState>, relative_path: &str) -> Result<ConfigFileContent> {
+    let (path, format) = resolve_config_path(state, relative_path)?;
+    let text = read_text(&path)?;
+    let tree = parse_tree(format, &text, relative_path)?;
+    Ok(ConfigFileContent {
+        path: relative_path.to_string(),
+        format,
+        text,
+        tree,
+    })
+}
+
+/// Returns the value at a dotted key path, e.g. `world-settings.default.view-distance`.
+/// Numeric segments index into lists.
+pub fn get_config_value(state: &Arc<AppState>, relative_path: &str, key_path: &str) -> Result<Value> {
+    let content = read_config_file(state, relative_path)?;
+    let segments = split_key_path(key_path)?;
+    let mut current = &content.tree;
+    for segment in &segments {
+        current = match current {
+            Value::Object(map) => map.get(segment.as_str()),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        }
+        .ok_or_else(|| AppError::ConfigError(format!("Key '{}' not found in {}", key_path, relative_path)))?;
+    }
+    Ok(current.clone())
+}
+
+// --- Writing ---
+
+/// Sets the value at a dotted key path, creating missing mappings/tables on the way.
+/// Comments are kept for TOML and, for simple scalar changes, for YAML. JSON keeps its key
+/// order; JSON5 is rewritten as plain JSON, so its comments are lost. The result is
+/// re-parsed before it is written, so a failed edit never corrupts the file.
+pub fn set_config_value(
+    state: &Arc<AppState>,
+    relative_path: &str,
+    key_path: &str,
+    value: Value,
+) -> Result<ConfigEditResult> {
+    let (path, format) = resolve_config_path(state, relative_path)?;
+    let segments = split_key_path(key_path)?;
+    let text = read_text(&path)?;
+    info!("Setting '{}' in {}", key_path, relative_path);
+
+    let (updated, comments_preserved) = match format {
+        ConfigFormat::Yaml => yaml_set(&text, &segments, &value, relative_path)?,
+        ConfigFormat::Toml => (toml_set(&text, &segments, &value, relative_path)?, true),
+        ConfigFormat::Json | ConfigFormat::Json5 => {
+            let mut tree = parse_tree(format, &text, relative_path)?;
+            json_set(&mut tree, &segments, value)?;
+            let mut json = serde_json::to_string_pretty(&tree)
+                .map_err(|e| AppError::ConfigError(format!("Failed to serialize {}: {}", relative_path, e)))?;
+            if text.ends_with('\n') {
+                json.push('\n');
+            }
+            // JSON has no comments; JSON5 comments can't be carried over
+            (json, format == ConfigFormat::Json)
+        }
+    };
+    if !comments_preserved {
+        warn!("{} was rewritten; comments in it were not preserved.", relative_path);
+    }
+
+    validate_config_text(format, &updated, relative_path)?;
+    fs_utils::write_string_to_file_atomic(&path, &updated)?;
+    Ok(ConfigEditResult { comments_preserved })
+}
+
+/// Replaces the whole file with text from the raw editor, after checking its syntax.
+pub fn save_config_file(state: &Arc<AppState>, relative_path: &str, text: &str) -> Result<()> {
+    let (path, format) = resolve_config_path(state, relative_path)?;
+    validate_config_text(format, text, relative_path)?;
+    fs_utils::write_string_to_file_atomic(&path, text)?;
+    info!("Saved {}", relative_path);
+    Ok(())
+}
+
+/// Checks unsaved editor text for a file without writing it.
+pub fn validate_config_file(state: &Arc<AppState>, relative_path: &str, text: &str) -> Result<()> {
+    let (_, format) = resolve_config_path(state, relative_path)?;
+    validate_config_text(format, text, relative_path)
+}
+
+/// Checks that `text` is syntactically valid for the format. Errors include the position.
+pub fn validate_config_text(format: ConfigFormat, text: &str, name: &str) -> Result<()> {
+    parse_tree(format, text, name).map(|_| ())
+}
+
+// --- Helpers ---
+
+/// Resolves a path relative to the server directory. Rejects absolute paths, `..` and
+/// unsupported extensions so the editor can't reach outside the server.
+fn resolve_config_path(state: &AppState, relative_path: &str) -> Result<(PathBuf, ConfigFormat)> {
+    let relative = Path::new(relative_path);
+    if relative_path.is_empty() || relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return Err(AppError::ConfigError(format!(
+            "Invalid config file path '{}': must be relative to the server directory",
+            relative_path
+        )));
+    }
+    let format = relative
+        .extension()
+        .and_then(|e| e.to_str())
+        .and_then(ConfigFormat::from_extension)
+        .ok_or_else(|| AppError::ConfigError(format!("Unsupported config file type: {}", relative_path)))?;
+    Ok((state.server_directory.join(relative), format))
+}
+
+fn read_text(path: &Path) -> Result<String> {
+    let size = path
+        .metadata()
+        .map_err(|e| AppError::ConfigError(format!("Cannot open {}: {}", path.display(), e)))?
+        .len();
+    if size > MAX_CONFIG_FILE_SIZE {
+        return Err(AppError::ConfigError(format!(
+            "{} is too large to edit ({} bytes)",
+            path.display(),
+            size
+        )));
+    }
+    let text = fs_utils::read_file_to_string(path)?;
+    Ok(text.strip_prefix('\u{feff}').map(str::to_string).unwrap_or(text))
+}
+
+fn split_key_path(key_path: &str) -> Result<Vec<String>> {
+    let segments: Vec<String> = key_path.split('.').map(str::to_string).collect();
+    if segments.iter().any(|s| s.is_empty()) {
+        return Err(AppError::ConfigError(format!("Invalid key path '{}'", key_path)));
+    }
+    Ok(segments)
+}
+
+/// Parses a document of any supported format into a JSON tree.
+fn parse_tree(format: ConfigFormat, text: &str, name: &str) -> Result<Value> {
+    match format {
+        ConfigFormat::Yaml => {
+            let yaml: serde_yaml::Value = serde_yaml::from_str(text)
+                .map_err(|e| AppError::ConfigError(format!("Invalid YAML in {}: {}", name, e)))?;
+            Ok(yaml_to_json(yaml))
+        }
+        ConfigFormat::Toml => {
+            text.parse::<DocumentMut>()
+                .map_err(|e| AppError::ConfigError(format!("Invalid TOML in {}: {}", name, e)))?;
+            toml_edit::de::from_str(text)
+                .map_err(|e| AppError::ConfigError(format!("Invalid TOML in {}: {}", name, e)))
+        }
+        ConfigFormat::Json => serde_json::from_str(text).map_err(|e| {
+            AppError::ConfigError(format!("Invalid JSON in {} (line {}, column {}): {}", name, e.line(), e.column(), e))
+        }),
+        ConfigFormat::Json5 => json5::from_str(text)
+            .map_err(|e| AppError::ConfigError(format!("Invalid JSON5 in {}: {}", name, e))),
+    }
+}
+
+/// Sets a value in a JSON tree, creating missing objects.
+fn json_set(root: &mut Value, segments: &[String], value: Value) -> Result<()> {
+    let Some((last, parents)) = segments.split_last() else {
+        return Err(AppError::ConfigError("Empty key path".to_string()));
+    };
+    let mut current = root;
+    for segment in parents {
+        current = match current {
+            Value::Object(map) => map.entry(segment.clone()).or_insert_with(|| Value::Object(Map::new())),
+            Value::Array(items) => array_slot(items, segment)?,
+            _ => return Err(AppError::ConfigError(format!("'{}' is not inside a mapping or list", segment))),
+        };
+    }
+    match current {
+        Value::Object(map) => {
+            map.insert(last.clone(), value);
+        }
+        Value::Array(items) => *array_slot(items, last)? = value,
+        _ => return Err(AppError::ConfigError(format!("'{}' is not inside a mapping or list", last))),
+    }
+    Ok(())
+}
+
+fn array_slot<'a>(items: &'a mut [Value], segment: &str) -> Result<&'a mut Value> {
+    let len = items.len();
+    segment
+        .parse::<usize>()
+        .ok()
+        .and_then(move |i| items.get_mut(i))
+        .ok_or_else(|| AppError::ConfigError(format!("List index '{}' out of range (length {})", segment, len)))
+}
+
+// --- YAML ---
+
+/// Converts YAML to JSON. Non-string keys are stringified and tags are dropped.
+fn yaml_to_json(yaml: serde_yaml::Value) -> Value {
+    match yaml {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                n.as_f64().map(Value::from).unwrap_or(Value::Null)
+            }
+        }
+        serde_yaml::Value::String(s) => Value::String(s),
+        serde_yaml::Value::Sequence(items) => Value::Array(items.into_iter().map(yaml_to_json).collect()),
+        serde_yaml::Value::Mapping(mapping) => Value::Object(
+            mapping
+                .into_iter()
+                .map(|(k, v)| {
+                    let key = match k {
+                        serde_yaml::Value::String(s) => s,
+                        other => serde_yaml::to_string(&other).unwrap_or_default().trim_end().to_string(),
+                    };
+                    (key, yaml_to_json(v))
+                })
+                .collect(),
+        ),
+        serde_yaml::Value::Tagged(tagged) => yaml_to_json(tagged.value),
+    }
+}
+
+/// Sets a YAML value. Scalars on `key: value` lines are replaced in place so comments and
+/// formatting survive; anything else falls back to re-serializing the document.
+/// Returns the new text and whether comments were preserved.
+fn yaml_set(text: &str, segments: &[String], value: &Value, name: &str) -> Result<(String, bool)> {
+    if let Some(updated) = yaml_replace_scalar(text, segments, value) {
+        // Only trust the line edit if the file now parses to the intended value
+        let reparsed = serde_yaml::from_str::<serde_yaml::Value>(&updated).ok().map(yaml_to_json);
+        let mut expected = parse_tree(ConfigFormat::Yaml, text, name)?;
+        json_set(&mut expected, segments, value.clone())?;
+        if reparsed.as_ref() == Some(&expected) {
+            return Ok((updated, true));
+        }
+        debug!("In-place YAML edit of {} didn't round-trip, re-serializing instead.", name);
+    }
+
+    let mut tree: serde_yaml::Value = serde_yaml::from_str(text)
+        .map_err(|e| AppError::ConfigError(format!("Invalid YAML in {}: {}", name, e)))?;
+    let new_value = serde_yaml::to_value(value)
+        .map_err(|e| AppError::ConfigError(format!("Cannot convert value to YAML: {}", e)))?;
+    yaml_tree_set(&mut tree, segments, new_value)?;
+    let updated = serde_yaml::to_string(&tree)
+        .map_err(|e| AppError::ConfigError(format!("Failed to serialize {}: {}", name, e)))?;
+    Ok((updated, !text.contains('#')))
+}
+
+fn yaml_tree_set(root: &mut serde_yaml::Value, segments: &[String], value: serde_yaml::Value) -> Result<()> {
+    let mut current = root;
+    for (idx, segment) in segments.iter().enumerate() {
+        let is_last = idx + 1 == segments.len();
+        if current.is_null() {
+            *current = serde_yaml::Value::Mapping(serde_yaml::Mapping::new());
+        }
+        current = match current {
+            serde_yaml::Value::Mapping(mapping) => {
+                // Keep existing numeric keys (e.g. `1:`) addressable by their text
+                let numeric_key = segment
+                    .parse::<i64>()
+                    .ok()
+                    .map(|n| serde_yaml::Value::Number(n.into()))
+                    .filter(|k| mapping.contains_key(k));
+                let key = numeric_key.unwrap_or_else(|| serde_yaml::Value::String(segment.clone()));
+                mapping.entry(key).or_insert(serde_yaml::Value::Null)
+            }
+            serde_yaml::Value::Sequence(items) => {
+                let len = items.len();
+                segment.parse::<usize>().ok().and_then(move |i| items.get_mut(i)).ok_or_else(|| {
+                    AppError::ConfigError(format!("List index '{}' out of range (length {})", segment, len))
+                })?
+            }
+            _ => return Err(AppError::ConfigError(format!("'{}' is not inside a mapping or list", segment))),
+        };
+        if is_last {
+            *current = value;
+            return Ok(());
+        }
+    }
+    Err(AppError::ConfigError("Empty key path".to_string()))
+}
+
+/// Replaces the scalar of an existing `key: value` line reached through block mappings.
+/// Returns None if the path doesn't lead to such a line or the value isn't a plain scalar.
+fn yaml_replace_scalar(text: &str, segments: &[String], value: &Value) -> Option<String> {
+    if value.is_object() || value.is_array() {
+        return None;
+    }
+    let scalar = serde_yaml::to_string(value).ok()?;
+    let scalar = scalar.trim_end();
+    if scalar.contains('\n') {
+        return None; // Multi-line strings need block syntax
+    }
+
+    let mut lines: Vec<String> = text.split('\n').map(str::to_string).collect();
+    let indent_of = |line: &str| -> Option<usize> {
+        let line = line.trim_end_matches('\r');
+        let content = line.trim_start_matches(' ');
+        if content.is_empty() || content.starts_with('#') {
+            None // Blank and comment lines don't affect structure
+        } else {
+            Some(line.len() - content.len())
+        }
+    };
+
+    let (mut start, mut end) = (0, lines.len());
+    let mut target = None;
+    for (depth, segment) in segments.iter().enumerate() {
+        let mut child_indent = None;
+        let mut hit = None;
+        for idx in start..end {
+            let Some(indent) = indent_of(&lines[idx]) else { continue };
+            if *child_indent.get_or_insert(indent) != indent {
+                continue;
+            }
+            let content = lines[idx].trim_end_matches('\r')[indent..].to_string();
+            if let Some((key, after_colon)) = yaml_line_key(&content) {
+                if key == *segment {
+                    hit = Some((idx, indent + after_colon));
+                    break;
+                }
+            }
+        }
+        let (idx, value_offset) = hit?;
+        if depth + 1 == segments.len() {
+            target = Some((idx, value_offset));
+            break;
+        }
+        // A parent must be a block mapping: nothing but a comment after the colon
+        let (rest, _) = split_yaml_comment(&lines[idx].trim_end_matches('\r')[value_offset..]);
+        if !rest.trim().is_empty() {
+            return None;
+        }
+        let indent = child_indent?;
+        start = idx + 1;
+        end = (start..end)
+            .find(|&i| indent_of(&lines[i]).map_or(false, |ind| ind <= indent))
+            .unwrap_or(end);
+    }
+
+    let (idx, value_offset) = target?;
+    let line = &lines[idx];
+    let (body, line_end) = match line.strip_suffix('\r') {
+        Some(body) => (body, "\r"),
+        None => (line.as_str(), ""),
+    };
+    let (value_part, comment) = split_yaml_comment(&body[value_offset..]);
+    let current = value_part.trim();
+    if current.is_empty() || current.starts_with(['|', '>', '&', '*', '!', '[', '{']) {
+        return None; // Block, anchored, tagged or flow values
+    }
+    let comment = if comment.is_empty() {
+        String::new()
+    } else {
+        let gap = &value_part[value_part.trim_end().len()..];
+        format!("{}{}", if gap.is_empty() { " " } else { gap }, comment)
+    };
+    let new_line = format!("{} {}{}{}", &body[..value_offset], scalar, comment, line_end);
+    lines[idx] = new_line;
+    Some(lines.join("\n"))
+}
+
+/// Parses the key of a block mapping line. Returns the key and the byte offset just after
+/// its colon, or None for list items and lines that aren't `key:` entries.
+fn yaml_line_key(content: &str) -> Option<(String, usize)> {
+    if content == "-" || content.starts_with("- ") {
+        return None;
+    }
+    let (key, key_end) = if let Some(rest) = content.strip_prefix('"') {
+        let mut escaped = false;
+        let close = rest.char_indices().find(|&(_, c)| {
+            let is_close = c == '"' && !escaped;
+            escaped = c == '\\' && !escaped;
+            is_close
+        })?.0 + 1;
+        (serde_yaml::from_str::<String>(&content[..=close]).ok()?, close + 1)
+    } else if let Some(rest) = content.strip_prefix('\'') {
+        let mut close = None;
+        let bytes = rest.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            if bytes[i] == b'\'' {
+                if bytes.get(i + 1) == Some(&b'\'') {
+                    i += 2;
+                    continue;
+                }
+                close = Some(i + 1);
+                break;
+            }
+            i += 1;
+        }
+        let close = close?;
+        (content[1..close].replace("''", "'"), close + 1)
+    } else {
+        let colon = content.char_indices().find(|&(i, c)| {
+            c == ':' && content[i + 1..].chars().next().map_or(true, char::is_whitespace)
+        })?.0;
+        if content[..colon].contains(" #") {
+            return None;
+        }
+        (content[..colon].trim_end().to_string(), colon)
+    };
+
+    let after_key = &content[key_end..];
+    let colon = after_key.len() - after_key.trim_start_matches(' ').len();
+    if !after_key[colon..].starts_with(':') {
+        return None;
+    }
+    Some((key, key_end + colon + 1))
+}
+
+/// Splits a value from a trailing comment (` # ...` outside quotes).
+fn split_yaml_comment(text: &str) -> (&str, &str) {
+    let mut quote: Option<char> = None;
+    let mut previous = ' ';
+    for (i, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if (c == '"' || c == '\'') && previous.is_whitespace() => quote = Some(c),
+            None if c == '#' && previous.is_whitespace() => return (&text[..i], &text[i..]),
+            None => {}
+        }
+        previous = c;
+    }
+    (text, "")
+}
+
+// --- TOML ---
+
+/// Sets a TOML value with `toml_edit`, which keeps comments and layout. The decoration
+/// (surrounding whitespace and trailing comment) of a replaced value is carried over.
+fn toml_set(text: &str, segments: &[String], value: &Value, name: &str) -> Result<String> {
+    let mut document = text
+        .parse::<DocumentMut>()
+        .map_err(|e| AppError::ConfigError(format!("Invalid TOML in {}: {}", name, e)))?;
+    let new_value = json_to_toml(value)?;
+    let Some((last, parents)) = segments.split_last() else {
+        return Err(AppError::ConfigError("Empty key path".to_string()));
+    };
+
+    let mut current = document.as_item_mut();
+    for segment in parents {
+        let is_table = current.is_table();
+        let table = current.as_table_like_mut().ok_or_else(|| {
+            AppError::ConfigError(format!("'{}' is not inside a table (lists can only be replaced as a whole)", segment))
+        })?;
+        if table.get(segment).is_none() {
+            let child = if is_table {
+                toml_edit::table()
+            } else {
+                toml_edit::Item::Value(toml_edit::Value::InlineTable(toml_edit::InlineTable::new()))
+            };
+            table.insert(segment, child);
+        }
+        current = table
+            .get_mut(segment)
+            .ok_or_else(|| AppError::ConfigError(format!("Failed to create table '{}'", segment)))?;
+    }
+
+    let table = current.as_table_like_mut().ok_or_else(|| {
+        AppError::ConfigError(format!("'{}' is not inside a table (lists can only be replaced as a whole)", last))
+    })?;
+    match table.get_mut(last) {
+        Some(toml_edit::Item::Value(existing)) => {
+            let decor = existing.decor().clone();
+            *existing = new_value;
+            *existing.decor_mut() = decor;
+        }
+        Some(existing) => *existing = toml_edit::Item::Value(new_value),
+        None => {
+            table.insert(last, toml_edit::Item::Value(new_value));
+        }
+    }
+    Ok(document.to_string())
+}
+
+fn json_to_toml(value: &Value) -> Result<toml_edit::Value> {
+    Ok(match value {
+        Value::Null => return Err(AppError::ConfigError("TOML has no null value".to_string())),
+        Value::Bool(b) => toml_edit::Value::from(*b),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => toml_edit::Value::from(i),
+            None => toml_edit::Value::from(n.as_f64().unwrap_or_default()),
+        },
+        Value::String(s) => toml_edit::Value::from(s.as_str()),
+        Value::Array(items) => {
+            let mut array = toml_edit::Array::new();
+            for item in items {
+                array.push(json_to_toml(item)?);
+            }
+            toml_edit::Value::Array(array)
+        }
+        Value::Object(map) => {
+            let mut table = toml_edit::InlineTable::new();
+            for (key, item) in map {
+                table.insert(key, json_to_toml(item)?);
+            }
+            toml_edit::Value::InlineTable(table)
+        }
+    })
+}
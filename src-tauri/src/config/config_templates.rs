@@ -1,11 +1,16 @@
 ﻿use crate::app_state::AppState;
+use crate::config::properties_document::{self, PropertiesDocument};
+use crate::config::property_catalog;
 use crate::error::{AppError, Result};
+use crate::models::template::{ProfileFile, RenderedFile, TemplateProfile};
+use crate::utils::fs_utils;
 use log::{debug, info, warn};
+use minijinja::{Environment, UndefinedBehavior};
+use serde_json::{json, Map, Value};
+use similar::TextDiff;
 use std::collections::HashMap;
-use std::fs::{self, File};
-use std::io;
-use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
 use std::sync::Arc;
 
 /// Returns the path to the templates directory within the server directory.
@@ -13,96 +18,338 @@ fn get_templates_dir(state: &AppState) -> PathBuf {
     state.server_directory.join("templates")
 }
 
+/// Returns the directory holding profile definitions (`<name>.json`).
+fn get_profiles_dir(state: &AppState) -> PathBuf {
+    get_templates_dir(state).join("profiles")
+}
+
+// --- Rendering ---
+
+/// Creates a template environment that loads templates from `templates_dir`.
+///
+/// Templates use Jinja syntax: `{{ var }}`, `{% if %}`, `{% for %}`, `{{ var | default(1) }}`
+/// and the built-in filters, plus:
+/// - `properties`: escapes a value for `.properties` files
+/// - `bool`: renders any value as `true`/`false`
+/// - `yaml`: renders a value as a YAML scalar (quoted if needed)
+///
+/// In strict mode, using an undefined variable is an error unless it's guarded by
+/// `is defined` or `default`.
+fn create_environment(templates_dir: &Path, strict: bool) -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_loader(minijinja::path_loader(templates_dir.to_path_buf()));
+    env.set_undefined_behavior(if strict { UndefinedBehavior::Strict } else { UndefinedBehavior::Lenient });
+    env.set_keep_trailing_newline(true);
+    // Block tags on their own line don't leave blank lines behind
+    env.set_trim_blocks(true);
+    env.set_lstrip_blocks(true);
+
+    env.add_filter("properties", |value: minijinja::Value| properties_document::escape_value(&value.to_string()));
+    env.add_filter("bool", |value: minijinja::Value| -> std::result::Result<String, minijinja::Error> {
+        // Strings are parsed rather than tested for truthiness, so "false" stays false
+        match value.as_str() {
+            Some(text) if text.trim().eq_ignore_ascii_case("true") => Ok("true".to_string()),
+            Some(text) if text.trim().eq_ignore_ascii_case("false") => Ok("false".to_string()),
+            Some(text) => Err(minijinja::Error::new(
+                minijinja::ErrorKind::InvalidOperation,
+                format!("'{}' is not a boolean", text),
+            )),
+            None => Ok(value.is_true().to_string()),
+        }
+    });
+    env.add_filter("yaml", |value: minijinja::Value| -> std::result::Result<String, minijinja::Error> {
+        serde_yaml::to_string(&value)
+            .map(|yaml| yaml.trim_end().to_string())
+            .map_err(|e| minijinja::Error::new(minijinja::ErrorKind::InvalidOperation, e.to_string()))
+    });
+    env
+}
+
+/// Renders a template from the `templates` directory with the given variables.
+/// `timestamp` is always available; templates that use it differ on every render, so the
+/// default templates don't.
+pub fn render_template(
+    template_name: &str,
+    variables: &Map<String, Value>,
+    strict: bool,
+    state: &Arc<AppState>,
+) -> Result<String> {
+    let templates_dir = get_templates_dir(state);
+    let env = create_environment(&templates_dir, strict);
+
+    let mut context = Map::new();
+    context.insert("timestamp".to_string(), Value::String(chrono::Local::now().to_rfc3339()));
+    context.extend(variables.clone());
+
+    debug!("Rendering template '{}' (strict: {})", template_name, strict);
+    let template = env.get_template(template_name).map_err(|e| {
+        AppError::ConfigError(format!("Failed to load template '{}': {}", template_name, e))
+    })?;
+    template
+        .render(Value::Object(context))
+        .map_err(|e| AppError::ConfigError(format!("Failed to render template '{}': {}", template_name, e)))
+}
+
 /// Applies a template file, replacing placeholders with provided values.
 ///
 /// Reads a template file (`template_name`) from the `templates` subdirectory,
-/// replaces `{{ key }}` placeholders with values from the `replacements` map,
-/// and writes the result to `output_path`.
+/// renders it with the `replacements` map as variables (strict: missing variables are
+/// an error instead of being left in the output), and writes the result to `output_path`.
 pub fn apply_template(
     template_name: &str,
     replacements: &HashMap<String, String>,
     output_path: &Path,
     state: &Arc<AppState>, // Borrow Arc directly
 ) -> Result<()> {
-    let templates_dir = get_templates_dir(state);
-    let template_path = templates_dir.join(template_name);
     info!(
         "Applying template '{}' to output '{}'",
-        template_path.display(),
+        template_name,
         output_path.display()
     );
 
-    if !template_path.exists() {
-        warn!("Template file not found: {}", template_path.display());
-        return Err(AppError::ConfigError(format!(
-            "Template file not found: {}",
-            template_path.display()
-        )));
+    let variables: Map<String, Value> = replacements
+        .iter()
+        .map(|(key, value)| (key.trim().to_string(), Value::String(value.clone())))
+        .collect();
+    let result = render_template(template_name, &variables, true, state)?;
+
+    // Atomic write also creates the output directory
+    fs_utils::write_string_to_file_atomic(output_path, &result)?;
+
+    info!(
+        "Successfully applied template '{}' to '{}'",
+        template_name,
+        output_path.display()
+    );
+    Ok(())
+}
+
+// --- Profiles ---
+
+/// Lists all profiles in `templates/profiles`. Unreadable profiles are skipped with a warning.
+pub fn list_profiles(state: &Arc<AppState>) -> Result<Vec<TemplateProfile>> {
+    let profiles_dir = get_profiles_dir(state);
+    if !profiles_dir.is_dir() {
+        return Ok(Vec::new());
     }
 
-    debug!("Reading template file: {}", template_path.display());
-    let template_content = fs::read_to_string(&template_path).map_err(|e| {
-        AppError::IoError(io::Error::new(
-            e.kind(),
-            format!("Failed to read template {}: {}", template_path.display(), e),
-        ))
-    })?;
+    let mut profiles = Vec::new();
+    for path in fs_utils::list_files_with_extension(&profiles_dir, "json")? {
+        match read_profile_file(&path) {
+            Ok(profile) => profiles.push(profile),
+            Err(e) => warn!("Skipping template profile {}: {}", path.display(), e),
+        }
+    }
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(profiles)
+}
 
-    let mut result = template_content;
-    for (key, value) in replacements {
-        let placeholder = format!("{{{{ {} }}}}", key.trim()); // Ensure key is trimmed
-        debug!("Replacing '{}' with '{}'", placeholder, value);
-        result = result.replace(&placeholder, value);
+/// Loads a profile by name.
+pub fn get_profile(name: &str, state: &Arc<AppState>) -> Result<TemplateProfile> {
+    validate_profile_name(name)?;
+    let path = get_profiles_dir(state).join(format!("{}.json", name));
+    if !path.exists() {
+        return Err(AppError::ConfigError(format!("Template profile '{}' not found", name)));
     }
+    read_profile_file(&path)
+}
 
-    // Ensure the output directory exists
-    if let Some(parent_dir) = output_path.parent() {
-        if !parent_dir.exists() {
-            debug!("Creating parent directory: {}", parent_dir.display());
-            fs::create_dir_all(parent_dir).map_err(|e| {
-                AppError::IoError(io::Error::new(
-                    e.kind(),
-                    format!(
-                        "Failed to create directory {}: {}",
-                        parent_dir.display(),
-                        e
-                    ),
-                ))
-            })?;
+/// Creates or replaces a profile. Its templates must exist and its outputs must stay
+/// inside the server directory.
+pub fn save_profile(profile: &TemplateProfile, state: &Arc<AppState>) -> Result<()> {
+    validate_profile_name(&profile.name)?;
+    if profile.files.is_empty() {
+        return Err(AppError::ConfigError("A template profile needs at least one file".to_string()));
+    }
+    let templates_dir = get_templates_dir(state);
+    for file in &profile.files {
+        resolve_output_path(&file.template, &templates_dir)?;
+        if !templates_dir.join(&file.template).is_file() {
+            return Err(AppError::ConfigError(format!("Template '{}' not found", file.template)));
         }
-    } else {
-        warn!("Output path '{}' has no parent directory.", output_path.display());
-        // Optionally return an error here depending on expected usage
+        resolve_output_path(&file.output, &state.server_directory)?;
     }
 
-    debug!("Writing processed template to: {}", output_path.display());
-    let mut file = File::create(output_path).map_err(|e| {
-        AppError::IoError(io::Error::new(
-            e.kind(),
-            format!("Failed to create output file {}: {}", output_path.display(), e),
-        ))
-    })?;
-    file.write_all(result.as_bytes()).map_err(|e| {
-        AppError::IoError(io::Error::new(
-            e.kind(),
-            format!(
-                "Failed to write to output file {}: {}",
-                output_path.display(),
-                e
-            ),
-        ))
-    })?;
+    let json = serde_json::to_string_pretty(profile)
+        .map_err(|e| AppError::ConfigError(format!("Failed to serialize template profile: {}", e)))?;
+    fs_utils::write_string_to_file_atomic(&get_profiles_dir(state).join(format!("{}.json", profile.name)), &json)?;
+    info!("Saved template profile '{}'", profile.name);
+    Ok(())
+}
+
+/// Deletes a profile. The templates it references are kept.
+pub fn delete_profile(name: &str, state: &Arc<AppState>) -> Result<()> {
+    validate_profile_name(name)?;
+    let path = get_profiles_dir(state).join(format!("{}.json", name));
+    if !path.exists() {
+        return Err(AppError::ConfigError(format!("Template profile '{}' not found", name)));
+    }
+    fs_utils::remove_file(&path)?;
+    info!("Deleted template profile '{}'", name);
+    Ok(())
+}
+
+/// Renders all files of a profile without writing anything and diffs them against the
+/// current files. `overrides` take precedence over the profile's variables.
+pub fn preview_profile(
+    name: &str,
+    overrides: &Map<String, Value>,
+    state: &Arc<AppState>,
+) -> Result<Vec<RenderedFile>> {
+    let profile = get_profile(name, state)?;
+    let mut variables = profile.variables.clone();
+    variables.extend(overrides.clone());
+
+    profile
+        .files
+        .iter()
+        .map(|file| render_profile_file(file, &variables, profile.strict, state))
+        .collect()
+}
+
+/// Renders a profile and writes the files that changed. Everything is rendered before the
+/// first write, so a template error leaves all files untouched. Replaced files are backed up.
+/// `.properties` outputs are merged into the existing file key by key, keeping comments and
+/// keys the template doesn't set.
+pub fn apply_profile(
+    name: &str,
+    overrides: &Map<String, Value>,
+    state: &Arc<AppState>,
+) -> Result<Vec<RenderedFile>> {
+    info!("Applying template profile '{}'", name);
+    let rendered = preview_profile(name, overrides, state)?;
+
+    for file in rendered.iter().filter(|f| f.changed) {
+        let output_path = resolve_output_path(&file.output, &state.server_directory)?;
+        if file.exists {
+            let backup = fs_utils::backup_file(&output_path)?;
+            debug!("Backed up {} to {}", output_path.display(), backup.display());
+        }
+        if is_properties_output(&file.output) {
+            // `content` is already the merged file; setting its entries again re-applies the
+            // template's keys while keeping the file's encoding
+            let mut document = PropertiesDocument::load(&output_path)?;
+            merge_properties(&mut document, &PropertiesDocument::parse(&file.content));
+            document.save(&output_path)?;
+        } else {
+            fs_utils::write_string_to_file_atomic(&output_path, &file.content)?;
+        }
+        info!("Wrote {} from template '{}'", file.output, file.template);
+    }
 
     info!(
-        "Successfully applied template '{}' to '{}'",
-        template_path.display(),
-        output_path.display()
+        "Template profile '{}' applied ({} of {} files changed).",
+        name,
+        rendered.iter().filter(|f| f.changed).count(),
+        rendered.len()
     );
-    Ok(())
+    Ok(rendered)
+}
+
+fn render_profile_file(
+    file: &ProfileFile,
+    variables: &Map<String, Value>,
+    strict: bool,
+    state: &Arc<AppState>,
+) -> Result<RenderedFile> {
+    let output_path = resolve_output_path(&file.output, &state.server_directory)?;
+    let rendered = render_template(&file.template, variables, strict, state)?;
+
+    let exists = output_path.is_file();
+    let (current, content) = if is_properties_output(&file.output) {
+        let template_values = PropertiesDocument::parse(&rendered);
+        validate_rendered_properties(&file.output, &template_values)?;
+        let mut document = PropertiesDocument::load(&output_path)?;
+        let current = document.to_string();
+        merge_properties(&mut document, &template_values);
+        (current, document.to_string())
+    } else if exists {
+        (fs_utils::read_file_to_string(&output_path)?, rendered)
+    } else {
+        (String::new(), rendered)
+    };
+    let changed = !exists || current != content;
+    let diff = if changed {
+        TextDiff::from_lines(&current, &content)
+            .unified_diff()
+            .context_radius(3)
+            .header(&format!("a/{}", file.output), &format!("b/{}", file.output))
+            .to_string()
+    } else {
+        String::new()
+    };
+
+    Ok(RenderedFile {
+        template: file.template.clone(),
+        output: file.output.clone(),
+        content,
+        exists,
+        changed,
+        diff,
+    })
+}
+
+fn is_properties_output(output: &str) -> bool {
+    output.ends_with(".properties")
+}
+
+/// Sets every entry of `values` on `document`, keeping its comments and other keys.
+fn merge_properties(document: &mut PropertiesDocument, values: &PropertiesDocument) {
+    for (key, value) in values.entries() {
+        document.set(key, value);
+    }
+}
+
+/// Checks rendered `server.properties` values against the property catalog. Other
+/// `.properties` files (mod configs) have no catalog and are merged as-is.
+fn validate_rendered_properties(output: &str, values: &PropertiesDocument) -> Result<()> {
+    if Path::new(output).file_name().and_then(|n| n.to_str()) != Some("server.properties") {
+        return Ok(());
+    }
+    let errors = property_catalog::validate_properties(&values.to_map());
+    if errors.is_empty() {
+        return Ok(());
+    }
+    let details: Vec<String> = errors.iter().map(|e| format!("{}: {}", e.key, e.message)).collect();
+    Err(AppError::ConfigError(format!("Template renders invalid {}: {}", output, details.join("; "))))
+}
+
+fn read_profile_file(path: &Path) -> Result<TemplateProfile> {
+    let content = fs_utils::read_file_to_string(path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| AppError::ConfigError(format!("Invalid template profile {}: {}", path.display(), e)))
+}
+
+fn validate_profile_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(AppError::ConfigError(format!(
+            "Invalid profile name '{}': use lowercase letters, digits, '-' and '_'",
+            name
+        )))
+    }
 }
 
-/// Installs default template files into the `templates` subdirectory if they don't exist.
-/// Note: These templates are not used by the default `server_properties` or `eula_manager` logic.
+/// Joins a relative path onto `base`, rejecting absolute paths and `..`.
+fn resolve_output_path(relative: &str, base: &Path) -> Result<PathBuf> {
+    let path = Path::new(relative);
+    if relative.is_empty() || path.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return Err(AppError::ConfigError(format!(
+            "Invalid path '{}': must be relative and stay inside {}",
+            relative,
+            base.display()
+        )));
+    }
+    Ok(base.join(path))
+}
+
+// --- Defaults ---
+
+/// Installs default template files and profiles into the `templates` subdirectory if they
+/// don't exist. Existing files are never overwritten, so user edits are kept.
 pub fn install_default_templates(state: &Arc<AppState>) -> Result<()> {
     let templates_dir = get_templates_dir(state);
     info!(
@@ -118,50 +365,71 @@ pub fn install_default_templates(state: &Arc<AppState>) -> Result<()> {
         fs::create_dir_all(&templates_dir)?;
     }
 
-    // --- Template Content Definitions ---
-    // Using include_str! to embed templates directly in the binary is often better
-    // for distribution, avoiding the need to manage separate template files.
-    // Example: static SERVER_PROPERTIES_TEMPLATE: &str = include_str!("../../../templates/server.properties.tmpl");
-    // For now, we keep the hardcoded strings as per your original code.
-
     let templates_to_install = [
         (
             "server.properties.tmpl",
-            r#"# Minecraft server properties template
-# Generated on {{ timestamp }}
-# Placeholders: {{ port }}, {{ gamemode }}, {{ difficulty }}, etc.
-server-port={{ port }}
-gamemode={{ gamemode }}
-difficulty={{ difficulty }}
-level-seed={{ seed }}
-enable-command-block={{ command_blocks }}
-max-players={{ max_players }}
-spawn-protection={{ spawn_protection }}
-view-distance={{ view_distance }}
-# Add other properties as needed...
+            r#"# Minecraft server properties
+# Generated from a template by the server manager
+server-port={{ port | default(25565) }}
+motd={{ motd | default("A Minecraft Server") | properties }}
+gamemode={{ gamemode | default("survival") }}
+force-gamemode={{ force_gamemode | default(false) | bool }}
+difficulty={{ difficulty | default("normal") }}
+hardcore={{ hardcore | default(false) | bool }}
+level-name={{ level_name | default("world") | properties }}
+level-seed={{ seed | default("") | properties }}
+level-type={{ level_type | default("minecraft:normal") | properties }}
+{% if generator_settings is defined %}
+generator-settings={{ generator_settings | properties }}
+{% endif %}
+generate-structures={{ generate_structures | default(true) | bool }}
+allow-nether={{ allow_nether | default(true) | bool }}
+max-players={{ max_players | default(20) }}
+online-mode={{ online_mode | default(true) | bool }}
+white-list={{ whitelist | default(false) | bool }}
+enforce-whitelist={{ whitelist | default(false) | bool }}
+pvp={{ pvp | default(true) | bool }}
+enable-command-block={{ command_blocks | default(false) | bool }}
+spawn-protection={{ spawn_protection | default(16) }}
+view-distance={{ view_distance | default(10) }}
+simulation-distance={{ simulation_distance | default(10) }}
+{% for key, value in extra_properties | default({}) | dictsort %}
+{{ key }}={{ value | properties }}
+{% endfor %}
 "#,
         ),
         (
             "spigot.yml.tmpl",
-            r#"# Spigot configuration template
-# Example placeholder: {{ minecraft_version }}
+            r#"# Spigot configuration
+# Generated from a template by the server manager
 settings:
-  # ... (rest of your spigot template)
-  timeout-time: 60
+  timeout-time: {{ timeout_time | default(60) }}
+  restart-on-crash: false
+  bungeecord: {{ bungeecord | default(false) | bool }}
 world-settings:
   default:
-    # Example placeholder: {{ view_distance }}
-    view-distance: {{ view_distance }}
-    # ... (rest of your spigot template)
+    view-distance: {{ view_distance | default("default") | yaml }}
+    simulation-distance: {{ simulation_distance | default("default") | yaml }}
+    mob-spawn-range: {{ mob_spawn_range | default(8) }}
+    entity-activation-range:
+      animals: {{ activation_range_animals | default(32) }}
+      monsters: {{ activation_range_monsters | default(32) }}
+      villagers: {{ activation_range_villagers | default(32) }}
 "#,
         ),
         (
             "bukkit.yml.tmpl",
-            r#"# Bukkit configuration template
-# (No placeholders shown in your example, add if needed)
+            r#"# Bukkit configuration
+# Generated from a template by the server manager
 settings:
-  allow-end: true
-  # ... (rest of your bukkit template)
+  allow-end: {{ allow_end | default(true) | bool }}
+  warn-on-overload: true
+  connection-throttle: {{ connection_throttle | default(4000) }}
+spawn-limits:
+  monsters: {{ monster_spawn_limit | default(70) }}
+  animals: {{ animal_spawn_limit | default(10) }}
+  water-animals: {{ water_animal_spawn_limit | default(5) }}
+  ambient: {{ ambient_spawn_limit | default(15) }}
 "#,
         ),
     ];
@@ -170,13 +438,106 @@ settings:
         let template_path = templates_dir.join(filename);
         if !template_path.exists() {
             info!("Installing default template: {}", template_path.display());
-            let mut file = File::create(&template_path)?;
-            file.write_all(content.as_bytes())?;
+            fs_utils::write_string_to_file_atomic(&template_path, content)?;
         } else {
             debug!("Template already exists: {}", template_path.display());
         }
     }
 
+    let profiles_dir = get_profiles_dir(state);
+    for profile in default_profiles() {
+        let profile_path = profiles_dir.join(format!("{}.json", profile.name));
+        if profile_path.exists() {
+            debug!("Template profile already exists: {}", profile_path.display());
+            continue;
+        }
+        info!("Installing default template profile: {}", profile.name);
+        let json = serde_json::to_string_pretty(&profile)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize template profile: {}", e)))?;
+        fs_utils::write_string_to_file_atomic(&profile_path, &json)?;
+    }
+
     info!("Default template check complete.");
     Ok(())
-}
\ No newline at end of file
+}
+
+/// The profiles shipped with the manager.
+fn default_profiles() -> Vec<TemplateProfile> {
+    let file = |template: &str, output: &str| ProfileFile {
+        template: template.to_string(),
+        output: output.to_string(),
+    };
+    let variables = |value: Value| value.as_object().cloned().unwrap_or_default();
+    // A flat world of bedrock, two dirt and a grass layer
+    let flat_layers = r#"{"layers":[{"block":"minecraft:bedrock","height":1},{"block":"minecraft:dirt","height":2},{"block":"minecraft:grass_block","height":1}],"biome":"minecraft:plains"}"#;
+
+    vec![
+        TemplateProfile {
+            name: "survival-small".to_string(),
+            description: "Survival for a small group of friends: whitelist on, reduced view distance.".to_string(),
+            variables: variables(json!({
+                "motd": "Small survival server",
+                "max_players": 10,
+                "difficulty": "normal",
+                "whitelist": true,
+                "view_distance": 8,
+                "simulation_distance": 6,
+            })),
+            files: vec![
+                file("server.properties.tmpl", "server.properties"),
+                file("spigot.yml.tmpl", "spigot.yml"),
+            ],
+            strict: true,
+        },
+        TemplateProfile {
+            name: "creative-flat".to_string(),
+            description: "Creative building on a flat world without monsters or structures.".to_string(),
+            variables: variables(json!({
+                "motd": "Creative build server",
+                "gamemode": "creative",
+                "force_gamemode": true,
+                "difficulty": "peaceful",
+                "level_type": "minecraft:flat",
+                "generator_settings": flat_layers,
+                "generate_structures": false,
+                "pvp": false,
+                "command_blocks": true,
+                "spawn_protection": 0,
+                "monster_spawn_limit": 0,
+            })),
+            files: vec![
+                file("server.properties.tmpl", "server.properties"),
+                file("bukkit.yml.tmpl", "bukkit.yml"),
+            ],
+            strict: true,
+        },
+        TemplateProfile {
+            name: "minigame-lobby".to_string(),
+            description: "Adventure-mode lobby: many players, no PvP, mobs, Nether or End.".to_string(),
+            variables: variables(json!({
+                "motd": "Minigame lobby",
+                "gamemode": "adventure",
+                "force_gamemode": true,
+                "difficulty": "peaceful",
+                "level_type": "minecraft:flat",
+                "generator_settings": flat_layers,
+                "generate_structures": false,
+                "allow_nether": false,
+                "allow_end": false,
+                "pvp": false,
+                "max_players": 100,
+                "spawn_protection": 64,
+                "view_distance": 6,
+                "simulation_distance": 4,
+                "monster_spawn_limit": 0,
+                "animal_spawn_limit": 0,
+            })),
+            files: vec![
+                file("server.properties.tmpl", "server.properties"),
+                file("spigot.yml.tmpl", "spigot.yml"),
+                file("bukkit.yml.tmpl", "bukkit.yml"),
+            ],
+            strict: true,
+        },
+    ]
+}
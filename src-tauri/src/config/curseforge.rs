@@ -0,0 +1,351 @@
+// src/config/curseforge.rs
+
+use crate::error::{AppError, Result};
+use crate::models::config::{ManualDownload, ModpackLoader};
+use crate::models::server_software::{Checksum, ServerFlavor};
+use crate::utils::download::DownloadJob;
+use log::{debug, info, warn};
+use reqwest::blocking::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path};
+
+/// Manifest at the root of a CurseForge pack export.
+pub const MANIFEST_FILE: &str = "manifest.json";
+/// Number of files downloaded at the same time.
+pub const DOWNLOAD_THREADS: usize = 6;
+/// CurseForge class ids of client-side content that a server doesn't load.
+const CLIENT_ONLY_CLASSES: &[u32] = &[12 /* resource packs */, 6552 /* shaders */];
+/// Server directories of the CurseForge classes a server loads from a fixed place. Other
+/// classes (worlds, data packs, customization) are skipped.
+const CLASS_DIRECTORIES: &[(u32, &str)] = &[(6, "mods"), (5, "plugins")];
+/// Directory for files whose project (and so class) the API didn't return.
+const DEFAULT_DIRECTORY: &str = "mods";
+/// Hash algorithm ids used by the CurseForge API.
+const HASH_SHA1: u32 = 1;
+const HASH_MD5: u32 = 2;
+
+/// CurseForge API access (`curseforge.json` in the app data directory). The API needs a key
+/// from the CurseForge developer console; the URL can point at a compatible proxy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurseForgeSettings {
+    pub api_url: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+impl Default for CurseForgeSettings {
+    fn default() -> Self {
+        Self {
+            api_url: "https://api.curseforge.com".to_string(),
+            api_key: None,
+        }
+    }
+}
+
+impl CurseForgeSettings {
+    pub fn validate(&self) -> Result<()> {
+        if !(self.api_url.starts_with("http://") || self.api_url.starts_with("https://")) {
+            return Err(AppError::ConfigError(format!("Invalid CurseForge API URL '{}': must be http(s)", self.api_url)));
+        }
+        Ok(())
+    }
+
+    fn api_key(&self) -> Result<&str> {
+        self.api_key
+            .as_deref()
+            .filter(|key| !key.trim().is_empty())
+            .ok_or_else(|| AppError::ModpackError("Installing CurseForge packs needs a CurseForge API key".to_string()))
+    }
+}
+
+/// `manifest.json` of a CurseForge pack (manifest version 1).
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeManifest {
+    pub minecraft: ManifestMinecraft,
+    pub manifest_type: String,
+    pub manifest_version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub author: String,
+    pub files: Vec<ManifestFile>,
+    /// Directory in the zip whose content is copied over the server directory.
+    #[serde(default = "default_overrides")]
+    pub overrides: String,
+}
+
+fn default_overrides() -> String {
+    "overrides".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestMinecraft {
+    pub version: String,
+    #[serde(default)]
+    pub mod_loaders: Vec<ManifestModLoader>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ManifestModLoader {
+    /// "forge-47.2.0", "neoforge-21.1.57", "fabric-0.15.11", "quilt-0.26.0".
+    pub id: String,
+    #[serde(default)]
+    pub primary: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ManifestFile {
+    #[serde(rename = "projectID")]
+    pub project_id: u64,
+    #[serde(rename = "fileID")]
+    pub file_id: u64,
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+impl CurseForgeManifest {
+    /// The primary mod loader (or the only one); None for vanilla packs.
+    pub fn loader(&self) -> Result<Option<ModpackLoader>> {
+        let loaders = &self.minecraft.mod_loaders;
+        let Some(loader) = loaders.iter().find(|l| l.primary).or(loaders.first()) else {
+            return Ok(None);
+        };
+        let (name, version) = loader
+            .id
+            .split_once('-')
+            .ok_or_else(|| AppError::ModpackError(format!("Unrecognized mod loader '{}'", loader.id)))?;
+        let flavor = match name {
+            "forge" => ServerFlavor::Forge,
+            "neoforge" => ServerFlavor::NeoForge,
+            "fabric" => ServerFlavor::Fabric,
+            "quilt" => ServerFlavor::Quilt,
+            _ => return Err(AppError::ModpackError(format!("Unsupported mod loader '{}'", loader.id))),
+        };
+        Ok(Some(ModpackLoader {
+            flavor,
+            minecraft_version: self.minecraft.version.clone(),
+            loader_version: Some(version.to_string()),
+        }))
+    }
+}
+
+/// Whether the zip at `archive` is a CurseForge pack export.
+pub fn is_curseforge_pack(archive: &Path) -> bool {
+    read_manifest_value(archive)
+        .is_some_and(|manifest| manifest.get("manifestType").and_then(|t| t.as_str()) == Some("minecraftModpack"))
+}
+
+fn read_manifest_value(archive: &Path) -> Option<serde_json::Value> {
+    let mut zip = zip::ZipArchive::new(File::open(archive).ok()?).ok()?;
+    let mut content = String::new();
+    zip.by_name(MANIFEST_FILE).ok()?.read_to_string(&mut content).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Reads and validates the manifest of a CurseForge pack.
+pub fn read_manifest(archive: &Path) -> Result<CurseForgeManifest> {
+    let file = File::open(archive)?;
+    let mut zip = zip::ZipArchive::new(file)
+        .map_err(|e| AppError::ModpackError(format!("Failed to open zip archive: {}", e)))?;
+    let mut content = String::new();
+    zip.by_name(MANIFEST_FILE)
+        .map_err(|e| AppError::ModpackError(format!("{} not found in pack: {}", MANIFEST_FILE, e)))?
+        .read_to_string(&mut content)?;
+    let manifest: CurseForgeManifest = serde_json::from_str(&content)
+        .map_err(|e| AppError::ModpackError(format!("Invalid {}: {}", MANIFEST_FILE, e)))?;
+
+    if manifest.manifest_type != "minecraftModpack" {
+        return Err(AppError::ModpackError(format!("Unsupported manifest type '{}'", manifest.manifest_type)));
+    }
+    if manifest.manifest_version != 1 {
+        return Err(AppError::ModpackError(format!("Unsupported manifest version {}", manifest.manifest_version)));
+    }
+    if !Path::new(&manifest.overrides).components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(AppError::ModpackError(format!("Overrides path '{}' is not allowed", manifest.overrides)));
+    }
+    info!("Read {} {} ({} files)", manifest.name, manifest.version, manifest.files.len());
+    Ok(manifest)
+}
+
+// --- API ---
+
+#[derive(Deserialize)]
+struct ApiList<T> {
+    data: Vec<T>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiFile {
+    id: u64,
+    mod_id: u64,
+    file_name: String,
+    /// Null when the author disallows third-party distribution.
+    download_url: Option<String>,
+    #[serde(default)]
+    hashes: Vec<ApiHash>,
+}
+
+#[derive(Deserialize)]
+struct ApiHash {
+    value: String,
+    algo: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiMod {
+    id: u64,
+    name: String,
+    #[serde(default)]
+    class_id: Option<u32>,
+    #[serde(default)]
+    links: Option<ApiLinks>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiLinks {
+    #[serde(default)]
+    website_url: Option<String>,
+}
+
+/// Pack files resolved through the API.
+#[derive(Debug, Default)]
+pub struct ResolvedFiles {
+    /// Files to download into the target directory.
+    pub jobs: Vec<DownloadJob>,
+    pub manual_downloads: Vec<ManualDownload>,
+    pub skipped: Vec<String>,
+    /// Files CurseForge publishes no SHA-1 or MD5 for; downloaded without verification.
+    pub unverified: Vec<String>,
+}
+
+fn post_json<T: DeserializeOwned>(
+    client: &Client,
+    settings: &CurseForgeSettings,
+    path: &str,
+    body: serde_json::Value,
+) -> Result<T> {
+    let url = format!("{}{}", settings.api_url.trim_end_matches('/'), path);
+    debug!("POST {}", url);
+    let response = client
+        .post(&url)
+        .header("x-api-key", settings.api_key()?)
+        .json(&body)
+        .send()
+        .map_err(|e| AppError::DownloadError(format!("Request to {} failed: {}", url, e)))?;
+    match response.status() {
+        status if status.is_success() => response
+            .json()
+            .map_err(|e| AppError::DownloadError(format!("Invalid response from {}: {}", url, e))),
+        reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::UNAUTHORIZED => {
+            Err(AppError::ModpackError("CurseForge rejected the API key".to_string()))
+        }
+        status => Err(AppError::DownloadError(format!("{} returned HTTP {}", url, status))),
+    }
+}
+
+/// Looks up the manifest's files and their projects. Optional files, client-only content and
+/// classes a server has no directory for are skipped; files without a download URL become
+/// manual downloads instead of failing the install. Nothing is downloaded yet.
+pub fn resolve_files(
+    client: &Client,
+    settings: &CurseForgeSettings,
+    manifest: &CurseForgeManifest,
+    target_dir: &Path,
+) -> Result<ResolvedFiles> {
+    let mut resolved = ResolvedFiles::default();
+    let (required, optional): (Vec<&ManifestFile>, Vec<&ManifestFile>) =
+        manifest.files.iter().partition(|file| file.required);
+    resolved
+        .skipped
+        .extend(optional.iter().map(|f| format!("project {} file {} (optional)", f.project_id, f.file_id)));
+    if required.is_empty() {
+        return Ok(resolved);
+    }
+
+    let file_ids: Vec<u64> = required.iter().map(|f| f.file_id).collect();
+    let files: ApiList<ApiFile> = post_json(client, settings, "/v1/mods/files", json!({ "fileIds": file_ids }))?;
+    let mod_ids: Vec<u64> = required.iter().map(|f| f.project_id).collect();
+    let mods: ApiList<ApiMod> = post_json(client, settings, "/v1/mods", json!({ "modIds": mod_ids }))?;
+    let mods: HashMap<u64, ApiMod> = mods.data.into_iter().map(|m| (m.id, m)).collect();
+    let files: HashMap<u64, ApiFile> = files.data.into_iter().map(|f| (f.id, f)).collect();
+
+    for entry in required {
+        let file = files.get(&entry.file_id).ok_or_else(|| {
+            AppError::ModpackError(format!("CurseForge has no file {} of project {}", entry.file_id, entry.project_id))
+        })?;
+        if file.file_name.is_empty() || file.file_name.starts_with('.') || file.file_name.contains(['/', '\\']) {
+            return Err(AppError::ModpackError(format!("File name '{}' is not allowed", file.file_name)));
+        }
+        let project = mods.get(&file.mod_id);
+        let name = project.map_or_else(|| format!("project {}", file.mod_id), |m| m.name.clone());
+        let directory = match project.and_then(|m| m.class_id) {
+            Some(class) if CLIENT_ONLY_CLASSES.contains(&class) => {
+                resolved.skipped.push(format!("{} ({}, client-only)", name, file.file_name));
+                continue;
+            }
+            Some(class) => match CLASS_DIRECTORIES.iter().find(|(id, _)| *id == class) {
+                Some((_, directory)) => *directory,
+                None => {
+                    resolved.skipped.push(format!("{} ({}, unsupported content class {})", name, file.file_name, class));
+                    continue;
+                }
+            },
+            None => {
+                warn!("No project info for {} ({}), installing it into {}/", name, file.file_name, DEFAULT_DIRECTORY);
+                DEFAULT_DIRECTORY
+            }
+        };
+
+        let destination = format!("{}/{}", directory, file.file_name);
+        match &file.download_url {
+            Some(url) => {
+                let checksum = file
+                    .hashes
+                    .iter()
+                    .find(|h| h.algo == HASH_SHA1)
+                    .map(|h| Checksum::Sha1(h.value.clone()))
+                    .or_else(|| file.hashes.iter().find(|h| h.algo == HASH_MD5).map(|h| Checksum::Md5(h.value.clone())));
+                if checksum.is_none() {
+                    warn!("CurseForge lists no SHA-1 or MD5 for {} ({}); it is downloaded unverified", name, file.file_name);
+                    resolved.unverified.push(format!("{} ({})", name, file.file_name));
+                }
+                resolved.jobs.push(DownloadJob {
+                    urls: vec![url.clone()],
+                    checksum,
+                    destination: target_dir.join(&destination),
+                    label: file.file_name.clone(),
+                });
+            }
+            None => {
+                let page = project
+                    .and_then(|m| m.links.as_ref())
+                    .and_then(|links| links.website_url.clone())
+                    .map(|site| format!("{}/files/{}", site.trim_end_matches('/'), file.id))
+                    .unwrap_or_else(|| format!("https://www.curseforge.com/projects/{}", file.mod_id));
+                info!("{} ({}) disallows third-party downloads", name, file.file_name);
+                resolved.manual_downloads.push(ManualDownload {
+                    name,
+                    file_name: file.file_name.clone(),
+                    url: page,
+                    destination,
+                });
+            }
+        }
+    }
+    Ok(resolved)
+}
@@ -0,0 +1,294 @@
+// src/config/manager_config.rs
+
+use crate::api::prometheus::ExporterConfig;
+use crate::error::{AppError, Result};
+use crate::models::alert::AlertRule;
+use crate::models::config::ServerConfig;
+use crate::monitoring::alert_manager;
+use crate::utils::fs_utils;
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Schema version written by this build. Bump it and add a migration when the layout changes.
+pub const MANAGER_CONFIG_VERSION: u32 = 1;
+
+/// File name of the manager config inside the app data directory.
+pub const MANAGER_CONFIG_FILE: &str = "manager.json";
+
+/// Migration from `version` to `version + 1`, operating on the raw JSON document.
+type Migration = fn(&mut Value) -> Result<()>;
+
+/// Forward migrations, indexed by the version they upgrade from.
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1];
+
+/// Settings for recurring tasks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchedulerSettings {
+    /// Start the server when the manager launches.
+    #[serde(default)]
+    pub auto_start: bool,
+    /// Start the server again after a crash.
+    #[serde(default)]
+    pub auto_restart_on_crash: bool,
+    /// Daily restart times in local time ("HH:MM").
+    #[serde(default)]
+    pub restart_times: Vec<String>,
+    /// Interval between automatic backups. None disables scheduled backups.
+    #[serde(default)]
+    pub backup_interval_minutes: Option<u64>,
+}
+
+impl Default for SchedulerSettings {
+    fn default() -> Self {
+        Self {
+            auto_start: false,
+            auto_restart_on_crash: false,
+            restart_times: Vec::new(),
+            backup_interval_minutes: None,
+        }
+    }
+}
+
+/// Persistent settings of the manager itself (`manager.json` in the app data directory).
+/// `AppState` is built from this at startup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagerConfig {
+    /// Schema version of the file, used to run forward migrations.
+    pub schema_version: u32,
+    /// server.properties values, Java args and modpack info.
+    #[serde(default)]
+    pub server: ServerConfig,
+    /// Name of the server JAR inside the server directory.
+    #[serde(default = "default_server_jar")]
+    pub server_jar: String,
+    /// Java executable to use instead of the auto-detected one.
+    #[serde(default)]
+    pub java_path: Option<PathBuf>,
+    /// Seconds to wait for a graceful stop before the process is killed.
+    #[serde(default = "default_stop_timeout_secs")]
+    pub stop_timeout_secs: u64,
+    #[serde(default = "alert_manager::default_rules")]
+    pub alert_rules: Vec<AlertRule>,
+    #[serde(default)]
+    pub scheduler: SchedulerSettings,
+    #[serde(default)]
+    pub exporter: ExporterConfig,
+    /// Extra globs (relative to the server directory) modpack installs must leave alone.
+    #[serde(default)]
+    pub modpack_protected_paths: Vec<String>,
+}
+
+fn default_server_jar() -> String {
+    "server.jar".to_string()
+}
+
+fn default_stop_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for ManagerConfig {
+    fn default() -> Self {
+        Self {
+            schema_version: MANAGER_CONFIG_VERSION,
+            server: ServerConfig::default(),
+            server_jar: default_server_jar(),
+            java_path: None,
+            stop_timeout_secs: default_stop_timeout_secs(),
+            alert_rules: alert_manager::default_rules(),
+            scheduler: SchedulerSettings::default(),
+            exporter: ExporterConfig::default(),
+            modpack_protected_paths: Vec::new(),
+        }
+    }
+}
+
+/// The manager-level settings editable from the settings page.
+/// Jar name, Java path and stop timeout take effect the next time the manager starts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManagerSettings {
+    pub server_jar: String,
+    pub java_path: Option<PathBuf>,
+    pub stop_timeout_secs: u64,
+    pub scheduler: SchedulerSettings,
+}
+
+impl ManagerSettings {
+    /// Validates the settings and copies them into `config`.
+    pub fn apply_to(self, config: &mut ManagerConfig) -> Result<()> {
+        let jar = self.server_jar.trim();
+        if jar.is_empty() || jar.contains(['/', '\\']) {
+            return Err(AppError::ConfigError(format!(
+                "Invalid server jar name '{}': must be a file name inside the server directory",
+                self.server_jar
+            )));
+        }
+        if let Some(java_path) = &self.java_path {
+            if !java_path.is_file() {
+                return Err(AppError::ConfigError(format!("Java executable not found: {}", java_path.display())));
+            }
+        }
+        if self.stop_timeout_secs == 0 {
+            return Err(AppError::ConfigError("Stop timeout must be at least 1 second".to_string()));
+        }
+        for time in &self.scheduler.restart_times {
+            if chrono::NaiveTime::parse_from_str(time, "%H:%M").is_err() {
+                return Err(AppError::ConfigError(format!("Invalid restart time '{}', expected HH:MM", time)));
+            }
+        }
+        config.server_jar = jar.to_string();
+        config.java_path = self.java_path;
+        config.stop_timeout_secs = self.stop_timeout_secs;
+        config.scheduler = self.scheduler;
+        Ok(())
+    }
+}
+
+/// Owns the manager config and writes every change back to disk atomically.
+pub struct ManagerConfigStore {
+    path: PathBuf,
+    config: RwLock<ManagerConfig>,
+}
+
+impl ManagerConfigStore {
+    /// Loads the config from `path`, migrating it to the current schema if needed.
+    /// Creates the file with defaults if it doesn't exist. A corrupt or too-new file is an
+    /// error rather than silently replaced, so user settings are never lost.
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let config = if path.exists() {
+            let content = fs_utils::read_file_to_string(&path)?;
+            let (config, migrated) = parse_and_migrate(&content, &path)?;
+            if migrated {
+                // Keep the pre-migration file around in case something went wrong
+                let backup = fs_utils::backup_file(&path)?;
+                info!("Backed up pre-migration manager config to {}", backup.display());
+                write_config(&path, &config)?;
+            }
+            info!("Loaded manager config (schema v{}) from {}", config.schema_version, path.display());
+            config
+        } else {
+            info!("No manager config at {}, creating defaults.", path.display());
+            let config = ManagerConfig::default();
+            write_config(&path, &config)?;
+            config
+        };
+
+        Ok(Self {
+            path,
+            config: RwLock::new(config),
+        })
+    }
+
+    /// Returns a clone of the current config.
+    pub fn get(&self) -> Result<ManagerConfig> {
+        self.config
+            .read()
+            .map(|guard| guard.clone())
+            .map_err(|e| AppError::LockError(format!("Failed to lock manager config for reading: {}", e)))
+    }
+
+    /// Applies `update` to the config and persists the result. Returns the updated config.
+    /// Nothing is changed if `update` returns an error.
+    pub fn update<F>(&self, update: F) -> Result<ManagerConfig>
+    where
+        F: FnOnce(&mut ManagerConfig) -> Result<()>,
+    {
+        let mut guard = self
+            .config
+            .write()
+            .map_err(|e| AppError::LockError(format!("Failed to lock manager config for writing: {}", e)))?;
+        let mut updated = guard.clone();
+        update(&mut updated)?;
+        updated.schema_version = MANAGER_CONFIG_VERSION;
+        // Write first so the in-memory copy never diverges from a failed save
+        write_config(&self.path, &updated)?;
+        *guard = updated.clone();
+        debug!("Manager config saved to {}", self.path.display());
+        Ok(updated)
+    }
+}
+
+/// Parses the file, runs migrations from its version to the current one and deserializes it.
+/// Returns the config and whether any migration ran.
+fn parse_and_migrate(content: &str, path: &Path) -> Result<(ManagerConfig, bool)> {
+    let mut document: Value = serde_json::from_str(content).map_err(|e| corrupt_error(path, &e))?;
+    if !document.is_object() {
+        return Err(AppError::ConfigError(format!(
+            "Manager config file {} is corrupt: expected a JSON object. Fix the file or delete it to restore defaults.",
+            path.display()
+        )));
+    }
+
+    // Files written before versioning was introduced have no version field
+    let version = match document.get("schema_version") {
+        None => 0,
+        Some(v) => v.as_u64().map(|v| v as u32).ok_or_else(|| {
+            AppError::ConfigError(format!(
+                "Manager config file {} is corrupt: 'schema_version' must be a number.",
+                path.display()
+            ))
+        })?,
+    };
+    if version > MANAGER_CONFIG_VERSION {
+        return Err(AppError::ConfigError(format!(
+            "Manager config file {} has schema version {}, but this version of the manager only supports up to {}. Please update the application.",
+            path.display(),
+            version,
+            MANAGER_CONFIG_VERSION
+        )));
+    }
+
+    for from in version..MANAGER_CONFIG_VERSION {
+        info!("Migrating manager config from schema v{} to v{}", from, from + 1);
+        MIGRATIONS[from as usize](&mut document)?;
+        document["schema_version"] = Value::from(from + 1);
+    }
+
+    let config = serde_json::from_value::<ManagerConfig>(document).map_err(|e| corrupt_error(path, &e))?;
+    Ok((config, version < MANAGER_CONFIG_VERSION))
+}
+
+fn corrupt_error(path: &Path, error: &serde_json::Error) -> AppError {
+    let location = if error.line() > 0 {
+        format!(" (line {}, column {})", error.line(), error.column())
+    } else {
+        String::new()
+    };
+    AppError::ConfigError(format!(
+        "Manager config file {} is corrupt{}: {}. Fix the file or delete it to restore defaults.",
+        path.display(),
+        location,
+        error
+    ))
+}
+
+fn write_config(path: &Path, config: &ManagerConfig) -> Result<()> {
+    let json = serde_json::to_string_pretty(config)
+        .map_err(|e| AppError::ConfigError(format!("Failed to serialize manager config: {}", e)))?;
+    fs_utils::write_string_to_file_atomic(path, &json)
+}
+
+// --- Migrations ---
+
+/// v0 (unversioned) stored `ServerConfig` fields at the top level.
+fn migrate_v0_to_v1(document: &mut Value) -> Result<()> {
+    let object = document
+        .as_object_mut()
+        .ok_or_else(|| AppError::ConfigError("Manager config must be a JSON object".to_string()))?;
+    if object.contains_key("server") {
+        return Ok(());
+    }
+    let mut server = serde_json::Map::new();
+    for key in ["server_properties", "java_args", "modpack"] {
+        if let Some(value) = object.remove(key) {
+            server.insert(key.to_string(), value);
+        }
+    }
+    if !server.is_empty() {
+        warn!("Moving top-level server settings of an unversioned manager config into 'server'.");
+        object.insert("server".to_string(), Value::Object(server));
+    }
+    Ok(())
+}
@@ -1,4 +1,17 @@
 ﻿pub mod server_properties;
+pub mod backup;
+pub mod properties_document;
+pub mod property_catalog;
+pub mod config_files;
+pub mod presets;
 pub mod config_templates;
 pub mod eula_manager;
-pub mod modpack_installer;
\ No newline at end of file
+pub mod curseforge;
+pub mod modpack_installer;
+pub mod modpack_staging;
+pub mod modpack_update;
+pub mod mrpack;
+pub mod server_software;
+pub mod software_detector;
+pub mod software_upgrade;
+pub mod manager_config;
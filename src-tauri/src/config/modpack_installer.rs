@@ -1,33 +1,691 @@
 ﻿// src/config/modpack_installer.rs
 
-use crate::api::events::{emit_error, emit_info, emit_progress};
+use crate::api::events::{emit_eula_status, emit_progress, emit_warn};
 use crate::app_state::AppState;
+use crate::config::curseforge::{self, CurseForgeManifest, CurseForgeSettings, ResolvedFiles};
+use crate::config::eula_manager;
+use crate::config::manager_config::ManagerConfigStore;
+use crate::config::modpack_staging::{self, PreviousState, Protection};
+use crate::config::modpack_update::{self, PackBase};
+use crate::config::mrpack::{self, MrpackFile, MrpackIndex};
+use crate::config::server_properties;
+use crate::config::server_software::ServerSoftwareManager;
 use crate::error::{AppError, Result};
-use log::{debug, error, info};
+use crate::models::config::{
+    ConflictResolution, ManualDownload, ModpackConfig, ModpackConflict, ModpackInstallResult, ModpackLoader,
+    ModpackMergeSummary, ModpackMergeView, ModpackUpdateResult,
+};
+use crate::models::server_software::{InstalledSoftware, ServerFlavor};
+use crate::models::server_status::ServerStatus;
+use crate::utils::{download, fs_utils};
+use log::{debug, error, info, warn};
+use reqwest::blocking::Client;
+use std::fmt;
 use std::fs::{self, File};
-use std::io::{self, Cursor}; // Use io::Cursor for in-memory zip reading
+use std::io::{self, Read, Write};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
-
-/// Installs a modpack from a given URL.
-///
-/// Downloads the zip file, validates it, clears the server directory (optional),
-/// extracts the contents, and potentially performs post-install actions.
-/// Emits `ProgressUpdate` events during download and extraction.
-pub fn install(state: Arc<AppState>, url: &str) -> Result<()> {
-    info!("Starting modpack installation from URL: {}", url);
-
-    // --- 1. Define Download Path ---
-    // Use a temporary directory or a dedicated downloads folder within AppData
-    let temp_dir = state.server_directory.join(".temp_download");
-    if !temp_dir.exists() {
-        fs::create_dir_all(&temp_dir)?;
-    }
-    let filename = url.split('/').last().unwrap_or("modpack.zip"); // Basic filename extraction
-    let download_path = temp_dir.join(filename);
-    info!("Downloading to: {}", download_path.display());
-
-    // --- 2. Download the Modpack ---
+use std::sync::{Arc, Mutex, MutexGuard, RwLock, TryLockError};
+use std::time::Duration;
+
+/// Installs modpacks into the server directory and records them in the manager config.
+pub struct ModpackInstaller {
+    state: Arc<AppState>,
+    software: Arc<ServerSoftwareManager>,
+    config_store: Arc<ManagerConfigStore>,
+    curseforge: RwLock<CurseForgeSettings>,
+    curseforge_path: PathBuf,
+    client: Client,
+    /// Held for the whole of an install, update, rollback or conflict resolution, which all
+    /// work on the same staging and previous-state directories.
+    operation: Mutex<()>,
+}
+
+impl ModpackInstaller {
+    pub fn new(
+        state: Arc<AppState>,
+        software: Arc<ServerSoftwareManager>,
+        config_store: Arc<ManagerConfigStore>,
+        curseforge_path: PathBuf,
+    ) -> Self {
+        let curseforge = match fs_utils::read_file_to_string(&curseforge_path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                error!("CurseForge settings {} are corrupt, using defaults: {}", curseforge_path.display(), e);
+                CurseForgeSettings::default()
+            }),
+            Err(_) => CurseForgeSettings::default(),
+        };
+        Self {
+            state,
+            software,
+            config_store,
+            curseforge: RwLock::new(curseforge),
+            curseforge_path,
+            client: download::http_client(),
+            operation: Mutex::new(()),
+        }
+    }
+
+    pub fn get_curseforge_settings(&self) -> Result<CurseForgeSettings> {
+        self.curseforge
+            .read()
+            .map(|guard| guard.clone())
+            .map_err(|e| AppError::LockError(format!("Failed to lock CurseForge settings: {}", e)))
+    }
+
+    /// Validates and persists the CurseForge API URL and key.
+    pub fn set_curseforge_settings(&self, settings: CurseForgeSettings) -> Result<()> {
+        settings.validate()?;
+        let mut guard = self
+            .curseforge
+            .write()
+            .map_err(|e| AppError::LockError(format!("Failed to lock CurseForge settings for writing: {}", e)))?;
+        let json = serde_json::to_string_pretty(&settings)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize CurseForge settings: {}", e)))?;
+        fs_utils::write_string_to_file_atomic(&self.curseforge_path, &json)?;
+        *guard = settings;
+        info!("CurseForge settings updated.");
+        Ok(())
+    }
+
+    /// Installs a modpack from a URL or a local file or directory.
+    ///
+    /// Downloads (or copies) the zip file, validates it and assembles the pack in a staging directory
+    /// (extracting it, or, for Modrinth `.mrpack` and CurseForge packs, downloading their files
+    /// and applying their overrides). The staged pack then replaces the live files except
+    /// protected paths such as worlds and player lists; the replaced files are kept so the
+    /// install can be rolled back. Finally the mod loader is installed (a failure rolls back)
+    /// and the pack is recorded as `ModpackConfig`. The format is detected from the content,
+    /// not the file name. Emits `ProgressUpdate` events during download, extraction and the
+    /// loader installer run.
+    pub fn install(&self, source: &ModpackSource, loader: Option<ModpackLoader>) -> Result<ModpackInstallResult> {
+        let _operation = self.lock_operation()?;
+        self.install_pack(source, loader, None).map(|(result, _)| result)
+    }
+
+    /// Updates the installed modpack to the version at `source`.
+    ///
+    /// Works like `install`, but before the swap the new version is merged with the server
+    /// directory against the pack as it was installed: files the user never changed are
+    /// updated, the user's own changes are kept and text files changed on both sides are
+    /// merged. Overlapping changes are reported as conflicts for the merge view; until they
+    /// are resolved the user's version stays in place.
+    pub fn update(&self, source: &ModpackSource, loader: Option<ModpackLoader>) -> Result<ModpackUpdateResult> {
+        let _operation = self.lock_operation()?;
+        let previous = self.config_store.get()?.server.modpack;
+        if previous.is_none() {
+            return Err(AppError::ModpackError("No modpack is installed; install one first".to_string()));
+        }
+        let base = PackBase::load(&self.state.server_directory)?.ok_or_else(|| {
+            AppError::ModpackError(
+                "The installed modpack has no recorded base to merge against; reinstall it to enable updates"
+                    .to_string(),
+            )
+        })?;
+        let (install, merge) = self.install_pack(source, loader, Some(base))?;
+        Ok(ModpackUpdateResult {
+            previous,
+            install,
+            merge: merge.unwrap_or_default(),
+        })
+    }
+
+    /// Unresolved conflicts of the last update.
+    pub fn list_conflicts(&self) -> Result<Vec<ModpackConflict>> {
+        modpack_update::list_conflicts(&self.state.server_directory)
+    }
+
+    /// The three versions of a conflicting file.
+    pub fn merge_view(&self, path: &str) -> Result<ModpackMergeView> {
+        modpack_update::merge_view(&self.state.server_directory, path)
+    }
+
+    pub fn resolve_conflict(&self, path: &str, resolution: ConflictResolution) -> Result<()> {
+        let _operation = self.lock_operation()?;
+        modpack_update::resolve_conflict(&self.state.server_directory, path, resolution)
+    }
+
+    /// Downloads, stages and swaps in a pack. With a `base`, the staged pack is merged with
+    /// the user's changes first.
+    fn install_pack(
+        &self,
+        source: &ModpackSource,
+        loader: Option<ModpackLoader>,
+        base: Option<PackBase>,
+    ) -> Result<(ModpackInstallResult, Option<ModpackMergeSummary>)> {
+        info!("Starting modpack installation from {}", source);
+        let state = &self.state;
+        match state.get_status()? {
+            ServerStatus::Stopped | ServerStatus::Error(_) => {}
+            status => {
+                return Err(AppError::ModpackError(format!(
+                    "Stop the server before installing a modpack (current state: {})",
+                    status
+                )))
+            }
+        }
+        if let Some(loader) = &loader {
+            if !matches!(
+                loader.flavor,
+                ServerFlavor::Forge | ServerFlavor::NeoForge | ServerFlavor::Fabric | ServerFlavor::Quilt
+            ) {
+                return Err(AppError::ModpackError(format!("{} is not a mod loader", loader.flavor)));
+            }
+        }
+        source.validate(&state.server_directory)?;
+
+        // --- 1. Define Download Path ---
+        // Use a temporary directory or a dedicated downloads folder within AppData
+        let temp_dir = state.server_directory.join(".temp_download");
+        if !temp_dir.exists() {
+            fs::create_dir_all(&temp_dir)?;
+        }
+        let filename = source.file_name();
+        let download_path = temp_dir.join(&filename);
+
+        // --- 2. Get the Modpack ---
+        // Local packs are copied (directories zipped) first, so a slow or flaky network share
+        // can't fail halfway through staging and every source goes through the same checks
+        match source {
+            ModpackSource::Url(url) => {
+                info!("Downloading to: {}", download_path.display());
+                download_pack(url, &download_path)?;
+            }
+            ModpackSource::Path(path) if path.is_dir() => pack_directory(path, &download_path)?,
+            ModpackSource::Path(path) => copy_pack(path, &download_path)?,
+        }
+        zip::ZipArchive::new(File::open(&download_path)?)
+            .map_err(|e| AppError::ModpackError(format!("{} is not a zip archive: {}", filename, e)))?;
+
+        // Read the pack's index (and look up CurseForge files) before anything is staged,
+        // so a broken pack or a missing API key changes nothing
+        let staging_dir = state.server_directory.join(modpack_staging::STAGING_DIR);
+        let format = ModpackFormat::detect(&download_path);
+        info!("Modpack format: {:?}", format);
+        let contents = match format {
+            ModpackFormat::Mrpack => PackContents::Mrpack(mrpack::read_index(&download_path)?),
+            ModpackFormat::CurseForge => {
+                let manifest = curseforge::read_manifest(&download_path)?;
+                emit_progress("Setup", -1.0, &format!("Resolving {} CurseForge files...", manifest.files.len()));
+                let files = curseforge::resolve_files(
+                    &self.client,
+                    &self.get_curseforge_settings()?,
+                    &manifest,
+                    &staging_dir,
+                )?;
+                PackContents::CurseForge(manifest, files)
+            }
+            ModpackFormat::Zip => PackContents::Zip,
+        };
+
+        // --- 3. Stage the Modpack ---
+        // The pack is assembled next to the live files, which stay untouched until the swap
+        emit_progress("Setup", 0.0, "Preparing staging directory...");
+        modpack_staging::prepare_staging(&state.server_directory)?;
+        let staged = match contents {
+            PackContents::Mrpack(index) => stage_mrpack(&self.client, &download_path, index, &staging_dir),
+            PackContents::CurseForge(manifest, files) => {
+                stage_curseforge(&self.client, &download_path, manifest, files, &staging_dir)
+            }
+            PackContents::Zip => {
+                info!("Starting extraction of {}...", download_path.display());
+                emit_progress("Extract", 0.0, "Starting extraction...");
+                // Plain zips carry no metadata, so the name is taken from the file name
+                let name = Path::new(&filename)
+                    .file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .unwrap_or(&filename)
+                    .to_string();
+                extract_zip(&download_path, "", &staging_dir).map(|()| StagedPack {
+                    name,
+                    version: "unknown".to_string(),
+                    loader: None,
+                    minecraft_version: None,
+                    skipped_files: Vec::new(),
+                    manual_downloads: Vec::new(),
+                })
+            }
+        };
+        // Record the pristine pack before any user files are merged into it
+        let staged = staged.and_then(|staged| PackBase::snapshot(&staging_dir).map(|()| staged));
+        let mut staged = match staged {
+            Ok(staged) => staged,
+            Err(e) => {
+                // Nothing live was touched; just drop the half-built staging directory
+                if let Err(cleanup) = fs_utils::remove_directory(&staging_dir) {
+                    error!("Failed to remove staging directory: {}", cleanup);
+                }
+                return Err(e);
+            }
+        };
+        emit_progress("Extract", 100.0, "Modpack staged.");
+        // An explicit loader overrides the one the pack declares
+        if let Some(loader) = loader {
+            staged.minecraft_version = Some(loader.minecraft_version.clone());
+            staged.loader = Some(loader);
+        }
+
+        // --- 4. Swap it into the Server Directory ---
+        let protection = self.protection()?;
+        let (merge, untracked) = match &base {
+            Some(base) => {
+                emit_progress("Setup", 10.0, "Merging with your changes...");
+                let outcome =
+                    modpack_update::merge_into_staging(&staging_dir, &state.server_directory, base, &protection)?;
+                (Some(outcome.summary), outcome.untracked)
+            }
+            None => (None, Vec::new()),
+        };
+        let changes =
+            modpack_staging::compute_changes(&staging_dir, &state.server_directory, &protection, &untracked)?;
+        info!(
+            "Modpack changes: {} added, {} modified, {} removed, {} unchanged, {} protected",
+            changes.added.len(),
+            changes.modified.len(),
+            changes.removed.len(),
+            changes.unchanged,
+            changes.protected.len()
+        );
+        emit_progress("Setup", 20.0, "Replacing server files...");
+        let previous = PreviousState {
+            server_jar: state.get_server_jar()?,
+            modpack: self.config_store.get()?.server.modpack,
+            created: chrono::Utc::now(),
+        };
+        modpack_staging::swap(&staging_dir, &state.server_directory, &protection, &untracked, &previous)?;
+
+        // --- 5. Install the Loader ---
+        // Runs against the live directory (the Forge installer writes libraries next to the
+        // jar); a failure puts the previous files back
+        let installed = match self.install_runtime(staged.loader.as_ref(), staged.minecraft_version.as_deref()) {
+            Ok(installed) => installed,
+            Err(e) => {
+                error!("Loader installation failed, rolling back the modpack install: {}", e);
+                emit_progress("Setup", 50.0, "Loader installation failed, rolling back...");
+                self.restore_previous(&protection)?;
+                return Err(e);
+            }
+        };
+        let mut modpack = modpack_config(staged.name, staged.version, staged.loader.as_ref(), installed.as_ref());
+        if modpack.minecraft_version.is_none() {
+            modpack.minecraft_version = staged.minecraft_version;
+        }
+        modpack.source_url = Some(source.to_url());
+        let result = ModpackInstallResult {
+            modpack,
+            skipped_files: staged.skipped_files,
+            manual_downloads: staged.manual_downloads,
+            changes,
+        };
+
+        // --- 6. Post-Installation Steps ---
+        info!("Running post-installation steps...");
+        emit_progress("Setup", 75.0, "Running post-install tasks...");
+        // server.properties and eula.txt are protected, so this only fills in a first install
+        server_properties::create_default_properties_if_missing(state)?;
+        emit_eula_status(eula_manager::is_eula_accepted(state).unwrap_or(false));
+
+        self.config_store.update(|config| {
+            config.server.modpack = Some(result.modpack.clone());
+            Ok(())
+        })?;
+
+        emit_progress("Setup", 100.0, "Installation complete.");
+        info!("Modpack installation finished successfully.");
+        if !result.manual_downloads.is_empty() {
+            emit_warn(
+                format!(
+                    "{} mod(s) don't allow automatic downloads and must be downloaded by hand: {}",
+                    result.manual_downloads.len(),
+                    result.manual_downloads.iter().map(|m| m.name.as_str()).collect::<Vec<_>>().join(", ")
+                ),
+                "ModpackInstaller".to_string(),
+            );
+        }
+        if let Some(conflicts) = merge.as_ref().map(|merge| &merge.conflicts).filter(|c| !c.is_empty()) {
+            emit_warn(
+                format!(
+                    "{} file(s) changed both by you and by the new pack version; your version was kept until resolved: {}",
+                    conflicts.len(),
+                    conflicts.iter().map(|c| c.path.as_str()).collect::<Vec<_>>().join(", ")
+                ),
+                "ModpackInstaller".to_string(),
+            );
+        }
+
+        // --- 7. Cleanup ---
+        debug!("Cleaning up temporary files...");
+        fs::remove_dir_all(&temp_dir)?; // Remove the .temp_download directory
+
+        Ok((result, merge))
+    }
+
+    /// Puts the server directory, server jar and modpack record back to how they were before
+    /// the last install. Protected paths (worlds, player lists, ...) are left as they are.
+    pub fn rollback(&self) -> Result<Option<ModpackConfig>> {
+        let _operation = self.lock_operation()?;
+        match self.state.get_status()? {
+            ServerStatus::Stopped | ServerStatus::Error(_) => {}
+            status => {
+                return Err(AppError::ModpackError(format!(
+                    "Stop the server before rolling back a modpack install (current state: {})",
+                    status
+                )))
+            }
+        }
+        if !modpack_staging::has_previous(&self.state.server_directory) {
+            return Err(AppError::ModpackError("There is no previous modpack install to roll back to".to_string()));
+        }
+        emit_progress("Setup", 0.0, "Rolling back modpack install...");
+        let previous = self.restore_previous(&self.protection()?)?;
+        emit_progress("Setup", 100.0, "Rollback complete.");
+        Ok(previous.modpack)
+    }
+
+    /// Globs of paths (relative to the server directory) a modpack install must not change,
+    /// in addition to worlds and the built-in player lists.
+    pub fn get_protected_paths(&self) -> Result<Vec<String>> {
+        Ok(self.config_store.get()?.modpack_protected_paths)
+    }
+
+    pub fn set_protected_paths(&self, paths: Vec<String>) -> Result<()> {
+        modpack_staging::validate_globs(&paths)?;
+        self.config_store.update(|config| {
+            config.modpack_protected_paths = paths;
+            Ok(())
+        })?;
+        info!("Modpack protected paths updated.");
+        Ok(())
+    }
+
+    /// Claims the installer for one operation; a second one started meanwhile is rejected.
+    fn lock_operation(&self) -> Result<MutexGuard<'_, ()>> {
+        match self.operation.try_lock() {
+            Ok(guard) => Ok(guard),
+            // A panicked operation left nothing locked on disk
+            Err(TryLockError::Poisoned(poisoned)) => Ok(poisoned.into_inner()),
+            Err(TryLockError::WouldBlock) => {
+                Err(AppError::ModpackError("Another modpack operation is in progress".to_string()))
+            }
+        }
+    }
+
+    /// Protected paths of the live server directory.
+    fn protection(&self) -> Result<Protection> {
+        let level_name = server_properties::read_properties_file(&self.state)
+            .ok()
+            .and_then(|properties| properties.get("level-name").cloned());
+        Protection::for_server(
+            &self.state.server_directory,
+            level_name.as_deref(),
+            &self.config_store.get()?.modpack_protected_paths,
+        )
+    }
+
+    /// Restores the files kept by the last swap and the jar and modpack record they belong to.
+    fn restore_previous(&self, protection: &Protection) -> Result<PreviousState> {
+        let previous = modpack_staging::rollback(&self.state.server_directory, protection)?;
+        self.software.set_server_jar(&previous.server_jar)?;
+        self.config_store.update(|config| {
+            config.server.modpack = previous.modpack.clone();
+            Ok(())
+        })?;
+        Ok(previous)
+    }
+
+    /// Installs the pack's loader, the vanilla server for packs without one, or nothing if the
+    /// Minecraft version isn't known (plain zips that bring their own jar).
+    fn install_runtime(
+        &self,
+        loader: Option<&ModpackLoader>,
+        minecraft_version: Option<&str>,
+    ) -> Result<Option<InstalledSoftware>> {
+        match (loader, minecraft_version) {
+            (Some(loader), _) => self.install_loader(loader).map(Some),
+            (None, Some(minecraft_version)) => self
+                .software
+                .install(ServerFlavor::Vanilla, minecraft_version, None)
+                .map(Some)
+                .map_err(|e| AppError::ModpackError(format!("Installing the vanilla server failed: {}", e))),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Installs the pack's mod loader through its server software provider.
+    fn install_loader(&self, loader: &ModpackLoader) -> Result<InstalledSoftware> {
+        let version = loader.loader_version.as_deref().unwrap_or("latest");
+        info!("Installing {} {} for Minecraft {}", loader.flavor, version, loader.minecraft_version);
+        emit_progress(
+            "Setup",
+            25.0,
+            &format!("Installing {} {} for Minecraft {}...", loader.flavor, version, loader.minecraft_version),
+        );
+        self.software
+            .install(loader.flavor, &loader.minecraft_version, loader.loader_version.as_deref())
+            .map_err(|e| AppError::ModpackError(format!("Installing {} failed: {}", loader.flavor, e)))
+    }
+}
+
+/// Where a modpack is installed from.
+#[derive(Debug, Clone)]
+pub enum ModpackSource {
+    Url(String),
+    /// A pack file (zip, `.mrpack` or CurseForge export, whatever its extension) or a
+    /// directory holding an unpacked one.
+    Path(PathBuf),
+}
+
+impl ModpackSource {
+    fn validate(&self, server_dir: &Path) -> Result<()> {
+        match self {
+            ModpackSource::Url(url) => {
+                let scheme = reqwest::Url::parse(url).map(|u| u.scheme().to_string());
+                if !matches!(scheme.as_deref(), Ok("http" | "https")) {
+                    return Err(AppError::ModpackError(format!("'{}' is not an http(s) URL", url)));
+                }
+            }
+            ModpackSource::Path(path) => {
+                if !path.is_absolute() {
+                    return Err(AppError::ModpackError(format!("'{}' is not an absolute path", path.display())));
+                }
+                if !path.exists() {
+                    return Err(AppError::ModpackError(format!("'{}' does not exist", path.display())));
+                }
+                // Packing the server directory (or part of it) would pick up the staging files
+                let server_dir = server_dir.canonicalize()?;
+                let path = path.canonicalize()?;
+                if path.is_dir() && (path.starts_with(&server_dir) || server_dir.starts_with(&path)) {
+                    return Err(AppError::ModpackError(
+                        "The modpack directory must be outside the server directory".to_string(),
+                    ));
+                }
+                // The installer copies the pack into .temp_download (truncating a source that
+                // is already there) and replaces its other working directories
+                let internal = modpack_staging::INTERNAL_DIRS.iter().chain([&modpack_staging::BASE_DIR]);
+                if let Some(dir) = internal.map(|dir| server_dir.join(dir)).find(|dir| path.starts_with(dir)) {
+                    return Err(AppError::ModpackError(format!(
+                        "The modpack file can't be inside the installer's working directory {}",
+                        dir.display()
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Name of the pack file, used for the temporary copy and as the name of plain zips.
+    fn file_name(&self) -> String {
+        let name = match self {
+            ModpackSource::Url(url) => url.rsplit('/').next().map(|name| name.to_string()), // Basic filename extraction
+            ModpackSource::Path(path) => path.file_name().map(|name| name.to_string_lossy().to_string()),
+        };
+        name.filter(|name| !name.is_empty()).unwrap_or_else(|| "modpack.zip".to_string())
+    }
+
+    /// Recorded as the pack's `source_url`; local paths become `file://` URLs.
+    fn to_url(&self) -> String {
+        match self {
+            ModpackSource::Url(url) => url.clone(),
+            ModpackSource::Path(path) => reqwest::Url::from_file_path(path)
+                .map(|url| url.to_string())
+                .unwrap_or_else(|()| path.display().to_string()),
+        }
+    }
+}
+
+impl fmt::Display for ModpackSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModpackSource::Url(url) => write!(f, "URL {}", url),
+            ModpackSource::Path(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Layout of a downloaded modpack, detected from its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModpackFormat {
+    /// Server files, extracted as they are.
+    Zip,
+    /// Modrinth `.mrpack`: an index of files to download plus overrides.
+    Mrpack,
+    /// CurseForge export: `manifest.json` listing project/file ids plus overrides.
+    CurseForge,
+}
+
+impl ModpackFormat {
+    fn detect(archive: &Path) -> Self {
+        if mrpack::is_mrpack(archive) {
+            ModpackFormat::Mrpack
+        } else if curseforge::is_curseforge_pack(archive) {
+            ModpackFormat::CurseForge
+        } else {
+            ModpackFormat::Zip
+        }
+    }
+}
+
+/// A pack whose index has been read, ready to install.
+enum PackContents {
+    Zip,
+    Mrpack(MrpackIndex),
+    CurseForge(CurseForgeManifest, ResolvedFiles),
+}
+
+/// A pack assembled in the staging directory, with what is needed to install its loader.
+struct StagedPack {
+    name: String,
+    version: String,
+    loader: Option<ModpackLoader>,
+    minecraft_version: Option<String>,
+    skipped_files: Vec<String>,
+    manual_downloads: Vec<ManualDownload>,
+}
+
+/// Builds the record of an installed pack. The source URL is filled in by the caller.
+fn modpack_config(
+    name: String,
+    version: String,
+    loader: Option<&ModpackLoader>,
+    installed: Option<&InstalledSoftware>,
+) -> ModpackConfig {
+    let loader_version = installed.map(|software| software.build.clone());
+    let flavor = loader.map(|loader| loader.flavor);
+    ModpackConfig {
+        name,
+        version,
+        source_url: None,
+        forge_version: loader_version
+            .clone()
+            .filter(|_| matches!(flavor, Some(ServerFlavor::Forge | ServerFlavor::NeoForge))),
+        fabric_version: loader_version.filter(|_| matches!(flavor, Some(ServerFlavor::Fabric | ServerFlavor::Quilt))),
+        minecraft_version: loader.map(|loader| loader.minecraft_version.clone()),
+        loader: flavor,
+    }
+}
+
+/// Downloads the server files of a Modrinth pack into `staging_dir` and applies `overrides/`
+/// and then `server-overrides/`. The loader comes from the `dependencies` block.
+fn stage_mrpack(client: &Client, archive: &Path, index: MrpackIndex, staging_dir: &Path) -> Result<StagedPack> {
+    let (files, skipped): (Vec<&MrpackFile>, Vec<&MrpackFile>) =
+        index.files.iter().partition(|file| file.is_for_server());
+    for file in &skipped {
+        debug!("Skipping client-only file {}", file.path);
+    }
+    info!(
+        "Staging {} {}: {} files, {} client-only skipped",
+        index.name,
+        index.version_id,
+        files.len(),
+        skipped.len()
+    );
+
+    emit_progress("Download", 0.0, &format!("Downloading {} files...", files.len()));
+    mrpack::download_files(client, &files, staging_dir)?;
+    emit_progress("Download", 100.0, "Download complete.");
+
+    emit_progress("Extract", 0.0, "Applying overrides...");
+    for prefix in mrpack::OVERRIDE_DIRECTORIES {
+        extract_zip(archive, prefix, staging_dir)?;
+    }
+
+    Ok(StagedPack {
+        name: index.name.clone(),
+        version: index.version_id.clone(),
+        loader: index.loader()?,
+        minecraft_version: Some(index.minecraft_version()?.to_string()),
+        skipped_files: skipped.iter().map(|file| file.path.clone()).collect(),
+        manual_downloads: Vec::new(),
+    })
+}
+
+/// Downloads the resolved mods of a CurseForge pack into `staging_dir` and applies the
+/// manifest's overrides directory. Mods that can't be downloaded automatically are returned
+/// as manual downloads.
+fn stage_curseforge(
+    client: &Client,
+    archive: &Path,
+    manifest: CurseForgeManifest,
+    files: ResolvedFiles,
+    staging_dir: &Path,
+) -> Result<StagedPack> {
+    info!(
+        "Staging {} {}: {} files, {} manual downloads, {} skipped",
+        manifest.name,
+        manifest.version,
+        files.jobs.len(),
+        files.manual_downloads.len(),
+        files.skipped.len()
+    );
+
+    emit_progress("Download", 0.0, &format!("Downloading {} files...", files.jobs.len()));
+    download::download_all(client, &files.jobs, curseforge::DOWNLOAD_THREADS, "Download")?;
+    emit_progress("Download", 100.0, "Download complete.");
+    if !files.unverified.is_empty() {
+        emit_warn(
+            format!(
+                "{} file(s) have no published checksum and were installed unverified: {}",
+                files.unverified.len(),
+                files.unverified.join(", ")
+            ),
+            "ModpackInstaller".to_string(),
+        );
+    }
+
+    emit_progress("Extract", 0.0, "Applying overrides...");
+    extract_zip(archive, &format!("{}/", manifest.overrides), staging_dir)?;
+
+    Ok(StagedPack {
+        loader: manifest.loader()?,
+        minecraft_version: Some(manifest.minecraft.version.clone()),
+        name: manifest.name,
+        version: manifest.version,
+        skipped_files: files.skipped,
+        manual_downloads: files.manual_downloads,
+    })
+}
+
+/// Downloads the pack at `url` to `download_path`, emitting `Download` progress.
+fn download_pack(url: &str, download_path: &Path) -> Result<()> {
     emit_progress("Download", 0.0, "Starting download...");
     // Use reqwest for downloading. Needs to be run in an async context
     // Since this function is called via spawn_blocking, we need to setup a local runtime
@@ -54,7 +712,7 @@ pub fn install(state: Arc<AppState>, url: &str) -> Result<()> {
         .unwrap_or(0); // Get expected size for progress
 
     let mut downloaded_bytes: u64 = 0;
-    let mut download_dest = File::create(&download_path)?;
+    let mut download_dest = File::create(download_path)?;
     let mut stream = response; // reqwest::blocking::Response is a reader
 
     let mut buffer = [0; 8192]; // 8KB buffer
@@ -85,77 +743,76 @@ pub fn install(state: Arc<AppState>, url: &str) -> Result<()> {
     download_dest.flush()?; // Ensure buffer is written
     info!("Download complete: {} bytes", downloaded_bytes);
     emit_progress("Download", 100.0, "Download complete.");
-
-    // --- 3. Clear Server Directory (Optional but Recommended) ---
-    // Decide which files/folders to keep (e.g., maybe keep world data?, backups?)
-    info!("Clearing server directory before extraction (WARNING: DELETES FILES)...");
-    // Example: Simple clear - THIS IS DESTRUCTIVE! Add more sophisticated logic later.
-    clear_server_directory(&state.server_directory, &temp_dir)?; // Pass temp_dir to avoid deleting it
-    emit_progress("Setup", 0.0, "Preparing server directory...");
-
-
-    // --- 4. Extract the Modpack ---
-    info!("Starting extraction of {}...", download_path.display());
-    emit_progress("Extract", 0.0, "Starting extraction...");
-    extract_zip(&download_path, &state.server_directory)?; // Pass server dir as target
-    emit_progress("Extract", 100.0, "Extraction complete.");
-
-    // --- 5. Post-Installation Steps ---
-    // - Run Forge/Fabric installer if needed? (More complex)
-    // - Ensure correct server JAR is selected in AppState?
-    // - Apply default configs?
-    info!("Running post-installation steps...");
-    emit_progress("Setup", 50.0, "Running post-install tasks...");
-    // Example: ensure default properties exist if server.properties wasn't in the pack
-    create_default_properties_if_missing(&state)?;
-    // Example: ensure EULA is prompted again
-    // You might want to *check* if eula.txt was in the zip and respect it? Or always force re-accept?
-    // Forcing re-accept is safer:
-    // fs::remove_file(state.server_directory.join("eula.txt")).ok(); // Ignore error if not present
-    emit_eula_status(false); // Assume EULA needs re-accepting
-
-
-    emit_progress("Setup", 100.0, "Installation complete.");
-    info!("Modpack installation finished successfully.");
-
-    // --- 6. Cleanup ---
-    debug!("Cleaning up temporary files...");
-    fs::remove_dir_all(&temp_dir)?; // Remove the .temp_download directory
-
     Ok(())
 }
 
-
-/// Helper function to clear the server directory before extraction.
-/// BE VERY CAREFUL with this function. It deletes files!
-fn clear_server_directory(server_dir: &Path, exclude_dir: &Path) -> Result<()> {
-    info!("Clearing contents of {}", server_dir.display());
-    for entry_result in fs::read_dir(server_dir)? {
-        let entry = entry_result?;
-        let path = entry.path();
-
-        // IMPORTANT: Do not delete the directory we downloaded to!
-        if path == exclude_dir {
-            debug!("Skipping deletion of excluded directory: {}", path.display());
-            continue;
+/// Copies a local pack file to `destination`, emitting `Download` progress.
+fn copy_pack(source: &Path, destination: &Path) -> Result<()> {
+    info!("Copying {} to {}", source.display(), destination.display());
+    emit_progress("Download", 0.0, "Copying modpack...");
+    let total_size = fs::metadata(source)?.len();
+    let mut reader = File::open(source)?;
+    let mut writer = File::create(destination)?;
+    let mut copied: u64 = 0;
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let bytes_read = reader.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
         }
-
-        // Add more exclusions? e.g., keep world saves, backups, specific configs?
-        // Example: if path.file_name().map_or(false, |n| n == "world" || n == "backups") { continue; }
-
-        debug!("Removing: {}", path.display());
-        if path.is_dir() {
-            fs::remove_dir_all(&path)?;
-        } else {
-            fs::remove_file(&path)?;
+        writer.write_all(&buffer[..bytes_read])?;
+        copied += bytes_read as u64;
+        if total_size > 0 {
+            let progress = (copied as f32 / total_size as f32) * 100.0;
+            emit_progress("Download", progress, &format!("Copying... {:.1}%", progress));
         }
     }
+    writer.flush()?;
+    emit_progress("Download", 100.0, "Copy complete.");
     Ok(())
 }
 
+/// Zips a local pack directory (an unpacked zip, `.mrpack` or CurseForge export) into
+/// `destination`, so it is detected and installed like the archive it came from.
+fn pack_directory(source: &Path, destination: &Path) -> Result<()> {
+    info!("Packing directory {} into {}", source.display(), destination.display());
+    emit_progress("Download", 0.0, "Reading modpack directory...");
+    let entries: Vec<walkdir::DirEntry> = walkdir::WalkDir::new(source)
+        .min_depth(1)
+        .into_iter()
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|e| AppError::ModpackError(format!("Failed to read {}: {}", source.display(), e)))?;
+
+    let mut writer = zip::ZipWriter::new(File::create(destination)?);
+    // Stored: the archive is only read back once, so compressing would just cost time
+    let options = zip::write::FileOptions::default()
+        .compression_method(zip::CompressionMethod::Stored)
+        .large_file(true);
+    for (i, entry) in entries.iter().enumerate() {
+        let Ok(relative) = entry.path().strip_prefix(source) else { continue };
+        let name = relative.to_string_lossy().replace('\\', "/");
+        let zip_error = |e: zip::result::ZipError| AppError::ModpackError(format!("Failed to pack {}: {}", name, e));
+        if entry.file_type().is_dir() {
+            writer.add_directory(format!("{}/", name), options).map_err(zip_error)?;
+        } else if entry.file_type().is_file() {
+            writer.start_file(name.as_str(), options).map_err(zip_error)?;
+            io::copy(&mut File::open(entry.path())?, &mut writer)?;
+        }
+        if i % 50 == 0 || i + 1 == entries.len() {
+            let progress = ((i + 1) as f32 / entries.len() as f32) * 100.0;
+            emit_progress("Download", progress, &format!("Reading: {}", name));
+        }
+    }
+    writer
+        .finish()
+        .map_err(|e| AppError::ModpackError(format!("Failed to write {}: {}", destination.display(), e)))?;
+    emit_progress("Download", 100.0, "Modpack directory read.");
+    Ok(())
+}
 
-/// Helper function to extract a zip archive.
-fn extract_zip(zip_path: &Path, target_dir: &Path) -> Result<()> {
+/// Helper function to extract a zip archive, or only the entries below `prefix` (such as
+/// "overrides/") with the prefix removed. An empty prefix extracts everything.
+fn extract_zip(zip_path: &Path, prefix: &str, target_dir: &Path) -> Result<()> {
     let file = File::open(zip_path)?;
     let mut archive = zip::ZipArchive::new(file)
         .map_err(|e| AppError::ModpackError(format!("Failed to open zip archive: {}", e)))?;
@@ -169,7 +826,10 @@ fn extract_zip(zip_path: &Path, target_dir: &Path) -> Result<()> {
 
         // Sanitize file path: prevent path traversal (../../..)
         let outpath = match file.enclosed_name() {
-            Some(path) => target_dir.join(path),
+            Some(path) => match path.strip_prefix(prefix.trim_end_matches('/')) {
+                Ok(relative) if !relative.as_os_str().is_empty() => target_dir.join(relative),
+                _ => continue, // Outside the requested prefix
+            },
             None => {
                 warn!("Skipping potentially unsafe file path in zip: {}", file.name());
                 continue;
@@ -0,0 +1,346 @@
+// src/config/modpack_staging.rs
+
+use crate::error::{AppError, Result};
+use crate::models::config::{ModpackChangeSet, ModpackConfig};
+use crate::utils::fs_utils;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use log::{debug, error, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Directory inside the server directory the new pack is assembled in.
+pub const STAGING_DIR: &str = ".modpack-staging";
+/// Directory holding the state before the last install, for rollback.
+pub const PREVIOUS_DIR: &str = ".modpack-previous";
+/// Pristine copy of the installed pack, used to merge updates. Moved with the pack files by
+/// `swap` and `rollback`, but never part of a change set.
+pub const BASE_DIR: &str = ".modpack-base";
+/// Working directories of the installer, never moved and never part of a change set.
+pub(crate) const INTERNAL_DIRS: &[&str] = &[".temp_download", STAGING_DIR, PREVIOUS_DIR];
+/// Always protected, in addition to worlds and the user's globs. Includes the manager's own
+/// files in the server directory (config templates and profiles).
+pub const DEFAULT_PROTECTED_PATHS: &[&str] = &[
+    "server.properties",
+    "eula.txt",
+    "templates",
+    "templates/**",
+    "ops.json",
+    "whitelist.json",
+    "banned-*.json",
+    "usercache.json",
+    "backups",
+    "backups/**",
+];
+/// Metadata of the previous state inside `PREVIOUS_DIR`.
+const PREVIOUS_STATE_FILE: &str = "state.json";
+const PREVIOUS_FILES_DIR: &str = "files";
+
+/// What is needed to restore the manager settings on rollback.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviousState {
+    pub server_jar: String,
+    pub modpack: Option<ModpackConfig>,
+    pub created: chrono::DateTime<chrono::Utc>,
+}
+
+/// Paths of the server directory a modpack install must not change: worlds (any top-level
+/// directory with a `level.dat`, plus `level-name`), the player lists and user-configured
+/// globs. Paths are relative with `/` separators.
+pub struct Protection {
+    worlds: Vec<String>,
+    globs: GlobSet,
+}
+
+impl Protection {
+    pub fn for_server(server_dir: &Path, level_name: Option<&str>, user_globs: &[String]) -> Result<Self> {
+        let mut worlds: Vec<String> = Vec::new();
+        if let Some(level_name) = level_name {
+            worlds.extend([
+                level_name.to_string(),
+                format!("{}_nether", level_name),
+                format!("{}_the_end", level_name),
+            ]);
+        }
+        if let Ok(entries) = fs::read_dir(server_dir) {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if entry.path().join("level.dat").is_file() && !worlds.contains(&name) {
+                    worlds.push(name);
+                }
+            }
+        }
+
+        let mut builder = GlobSetBuilder::new();
+        for pattern in DEFAULT_PROTECTED_PATHS.iter().copied().chain(user_globs.iter().map(String::as_str)) {
+            let glob = GlobBuilder::new(pattern.trim_matches('/'))
+                .literal_separator(true)
+                .build()
+                .map_err(|e| AppError::ConfigError(format!("Invalid protected path '{}': {}", pattern, e)))?;
+            builder.add(glob);
+        }
+        let globs = builder
+            .build()
+            .map_err(|e| AppError::ConfigError(format!("Invalid protected paths: {}", e)))?;
+        Ok(Self { worlds, globs })
+    }
+
+    pub fn is_protected(&self, relative: &str) -> bool {
+        let top = relative.split('/').next().unwrap_or(relative);
+        self.worlds.iter().any(|world| world == top) || self.globs.is_match(relative)
+    }
+}
+
+/// Checks user globs without building a full `Protection`.
+pub fn validate_globs(globs: &[String]) -> Result<()> {
+    for pattern in globs {
+        GlobBuilder::new(pattern.trim_matches('/'))
+            .literal_separator(true)
+            .build()
+            .map_err(|e| AppError::ConfigError(format!("Invalid protected path '{}': {}", pattern, e)))?;
+    }
+    Ok(())
+}
+
+fn is_internal(relative: &str) -> bool {
+    let top = relative.split('/').next().unwrap_or(relative);
+    INTERNAL_DIRS.contains(&top)
+}
+
+/// Relative paths (with `/`) of all files below `root`, skipping the installer's directories
+/// and the pack base.
+pub(crate) fn relative_files(root: &Path) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(root).min_depth(1) {
+        let entry = entry.map_err(|e| AppError::IoError(io::Error::other(e.to_string())))?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(root) else { continue };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if !is_internal(&relative) && relative.split('/').next() != Some(BASE_DIR) {
+            files.push(relative);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+pub(crate) fn files_equal(a: &Path, b: &Path) -> Result<bool> {
+    if fs::metadata(a)?.len() != fs::metadata(b)?.len() {
+        return Ok(false);
+    }
+    let (mut file_a, mut file_b) = (File::open(a)?, File::open(b)?);
+    let (mut buffer_a, mut buffer_b) = ([0u8; 64 * 1024], [0u8; 64 * 1024]);
+    loop {
+        let read = file_a.read(&mut buffer_a)?;
+        if read == 0 {
+            return Ok(true);
+        }
+        file_b.read_exact(&mut buffer_b[..read])?;
+        if buffer_a[..read] != buffer_b[..read] {
+            return Ok(false);
+        }
+    }
+}
+
+/// Compares the staged pack with the live directory. Files in `keep` stay in place (see
+/// `swap`) and are not reported as removed.
+pub fn compute_changes(
+    staging: &Path,
+    live: &Path,
+    protection: &Protection,
+    keep: &[String],
+) -> Result<ModpackChangeSet> {
+    let mut changes = ModpackChangeSet::default();
+    let staged = relative_files(staging)?;
+    let current = relative_files(live)?;
+    let keep: BTreeSet<&str> = keep.iter().map(String::as_str).collect();
+
+    for path in &staged {
+        if protection.is_protected(path) {
+            continue;
+        }
+        let live_path = live.join(path);
+        if !live_path.is_file() {
+            changes.added.push(path.clone());
+        } else if files_equal(&staging.join(path), &live_path)? {
+            changes.unchanged += 1;
+        } else {
+            changes.modified.push(path.clone());
+        }
+    }
+    for path in &current {
+        if protection.is_protected(path) || keep.contains(path.as_str()) {
+            continue;
+        }
+        if !staging.join(path).is_file() {
+            changes.removed.push(path.clone());
+        }
+    }
+
+    // Report protected entries at the top level where possible (a world, not its region files)
+    let mut protected: Vec<String> = Vec::new();
+    for path in current {
+        if !protection.is_protected(&path) {
+            continue;
+        }
+        let top = path.split('/').next().unwrap_or(&path).to_string();
+        let entry = if protection.is_protected(&top) && live.join(&top).is_dir() { top } else { path };
+        if protected.last() != Some(&entry) {
+            protected.push(entry);
+        }
+    }
+    changes.protected = protected;
+    Ok(changes)
+}
+
+/// Replaces the live directory's content with the staged pack.
+///
+/// Every unprotected top-level entry is moved into `PREVIOUS_DIR` (replacing the state kept by
+/// the last install), then the staged entries are moved in. Protected top-level entries stay
+/// where they are; protected files inside moved directories (e.g. a protected
+/// `config/mymod.toml`) are copied back afterwards, and so are the files in `keep` (files the
+/// staged pack doesn't manage, see `merge_into_staging`), hard-linked where possible. Entries
+/// are moved with renames, so the swap is quick; if it fails halfway, the entries already
+/// moved are put back.
+pub fn swap(
+    staging: &Path,
+    live: &Path,
+    protection: &Protection,
+    keep: &[String],
+    state: &PreviousState,
+) -> Result<()> {
+    let previous = live.join(PREVIOUS_DIR);
+    fs_utils::remove_directory(&previous)?;
+    let previous_files = previous.join(PREVIOUS_FILES_DIR);
+    fs::create_dir_all(&previous_files)?;
+    let json = serde_json::to_string_pretty(state)
+        .map_err(|e| AppError::ConfigError(format!("Failed to serialize previous modpack state: {}", e)))?;
+    fs_utils::write_string_to_file_atomic(&previous.join(PREVIOUS_STATE_FILE), &json)?;
+
+    let mut moved_out: Vec<String> = Vec::new();
+    let mut moved_in: Vec<String> = Vec::new();
+    let result = (|| -> Result<()> {
+        for name in top_level_entries(live)? {
+            if is_internal(&name) || protection.is_protected(&name) {
+                continue;
+            }
+            fs::rename(live.join(&name), previous_files.join(&name))?;
+            moved_out.push(name);
+        }
+        for name in top_level_entries(staging)? {
+            if live.join(&name).exists() {
+                debug!("Keeping protected {} instead of the pack's copy", name);
+                continue;
+            }
+            fs::rename(staging.join(&name), live.join(&name))?;
+            moved_in.push(name);
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        error!("Modpack swap failed, restoring the previous files: {}", e);
+        for name in moved_in {
+            if let Err(e) = fs::rename(live.join(&name), staging.join(&name)) {
+                error!("Failed to move {} back to staging: {}", name, e);
+            }
+        }
+        for name in moved_out {
+            if let Err(e) = fs::rename(previous_files.join(&name), live.join(&name)) {
+                error!("Failed to restore {}: {}", name, e);
+            }
+        }
+        return Err(e);
+    }
+
+    for path in relative_files(&previous_files)? {
+        if protection.is_protected(&path) {
+            let target = live.join(&path);
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(previous_files.join(&path), &target)?;
+            debug!("Restored protected {}", path);
+        }
+    }
+    for path in keep {
+        let source = previous_files.join(path);
+        let target = live.join(path);
+        if !source.is_file() || target.exists() {
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // The copy in PREVIOUS_DIR stays for rollback, a hard link saves copying libraries
+        if fs::hard_link(&source, &target).is_err() {
+            fs::copy(&source, &target)?;
+        }
+    }
+    if !keep.is_empty() {
+        debug!("Kept {} files the pack doesn't manage", keep.len());
+    }
+    fs_utils::remove_directory(staging)?;
+    info!("Swapped in staged modpack; previous state kept in {}", previous.display());
+    Ok(())
+}
+
+/// Whether an install can be rolled back.
+pub fn has_previous(live: &Path) -> bool {
+    live.join(PREVIOUS_DIR).join(PREVIOUS_STATE_FILE).is_file()
+}
+
+/// Puts the state from before the last install back: unprotected entries are deleted and
+/// replaced by the ones kept in `PREVIOUS_DIR`. Returns the saved manager state so the caller
+/// can restore the server jar and modpack record.
+pub fn rollback(live: &Path, protection: &Protection) -> Result<PreviousState> {
+    let previous = live.join(PREVIOUS_DIR);
+    let content = fs_utils::read_file_to_string(&previous.join(PREVIOUS_STATE_FILE))
+        .map_err(|_| AppError::ModpackError("There is no previous modpack install to roll back to".to_string()))?;
+    let state: PreviousState = serde_json::from_str(&content)
+        .map_err(|e| AppError::ModpackError(format!("Previous modpack state is corrupt: {}", e)))?;
+    let previous_files = previous.join(PREVIOUS_FILES_DIR);
+
+    for name in top_level_entries(live)? {
+        if is_internal(&name) || protection.is_protected(&name) {
+            continue;
+        }
+        let path = live.join(&name);
+        if path.is_dir() {
+            fs_utils::remove_directory(&path)?;
+        } else {
+            fs_utils::remove_file(&path)?;
+        }
+    }
+    for name in top_level_entries(&previous_files)? {
+        if live.join(&name).exists() {
+            warn!("{} exists in the server directory, not restoring the previous copy", name);
+            continue;
+        }
+        fs::rename(previous_files.join(&name), live.join(&name))?;
+    }
+    fs_utils::remove_directory(&previous)?;
+    info!("Rolled back modpack install from {}", state.created);
+    Ok(state)
+}
+
+fn top_level_entries(dir: &Path) -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        names.push(entry?.file_name().to_string_lossy().to_string());
+    }
+    Ok(names)
+}
+
+/// Directory the staged pack is assembled in, emptied first.
+pub fn prepare_staging(live: &Path) -> Result<PathBuf> {
+    let staging = live.join(STAGING_DIR);
+    fs_utils::remove_directory(&staging)?;
+    fs::create_dir_all(&staging)?;
+    Ok(staging)
+}
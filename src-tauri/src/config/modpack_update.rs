@@ -0,0 +1,434 @@
+// src/config/modpack_update.rs
+
+use crate::config::modpack_staging::{self, Protection, BASE_DIR};
+use crate::error::{AppError, Result};
+use crate::models::config::{ConflictResolution, ModpackConflict, ModpackMergeSummary, ModpackMergeView};
+use crate::utils::fs_utils;
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use similar::{Algorithm, DiffTag, TextDiff};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Pack files larger than this are recorded by hash only; configs are far smaller.
+const MAX_BASE_COPY_SIZE: u64 = 1024 * 1024;
+/// Layout of `BASE_DIR`.
+const BASE_INDEX_FILE: &str = "index.json";
+const BASE_FILES_DIR: &str = "files";
+const CONFLICTS_FILE: &str = "conflicts.json";
+const CONFLICT_BASE_DIR: &str = "conflicts/base";
+const CONFLICT_INCOMING_DIR: &str = "conflicts/incoming";
+
+/// SHA-1 of every file of the installed pack, by relative path.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BaseIndex {
+    files: BTreeMap<String, String>,
+}
+
+/// The pack as it was installed, before the user changed anything: hashes of all its files
+/// plus copies of the small ones, which are the only ones worth merging.
+pub struct PackBase {
+    dir: PathBuf,
+    index: BaseIndex,
+}
+
+impl PackBase {
+    /// Records the freshly staged pack in `staging/BASE_DIR`, so it becomes the base of the
+    /// next update once swapped in.
+    pub fn snapshot(staging: &Path) -> Result<()> {
+        let dir = staging.join(BASE_DIR);
+        fs_utils::remove_directory(&dir)?;
+        let files_dir = dir.join(BASE_FILES_DIR);
+        let mut index = BaseIndex::default();
+        for path in modpack_staging::relative_files(staging)? {
+            let source = staging.join(&path);
+            index.files.insert(path.clone(), file_sha1(&source)?);
+            if !path.ends_with(".jar") && fs::metadata(&source)?.len() <= MAX_BASE_COPY_SIZE {
+                let target = files_dir.join(&path);
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::copy(&source, &target)?;
+            }
+        }
+        let json = serde_json::to_string_pretty(&index)
+            .map_err(|e| AppError::ModpackError(format!("Failed to serialize modpack base index: {}", e)))?;
+        fs_utils::ensure_directory(&dir)?;
+        fs_utils::write_string_to_file_atomic(&dir.join(BASE_INDEX_FILE), &json)?;
+        debug!("Recorded modpack base with {} files", index.files.len());
+        Ok(())
+    }
+
+    /// Loads the base of the installed pack. None for packs installed before bases were
+    /// recorded.
+    pub fn load(server_dir: &Path) -> Result<Option<Self>> {
+        let dir = server_dir.join(BASE_DIR);
+        let index_path = dir.join(BASE_INDEX_FILE);
+        if !index_path.is_file() {
+            return Ok(None);
+        }
+        let index = serde_json::from_str(&fs_utils::read_file_to_string(&index_path)?)
+            .map_err(|e| AppError::ModpackError(format!("Modpack base index is corrupt: {}", e)))?;
+        Ok(Some(Self { dir, index }))
+    }
+
+    fn hash(&self, path: &str) -> Option<&str> {
+        self.index.files.get(path).map(String::as_str)
+    }
+
+    /// Text of the file in the base, if it was small enough to be copied and is text.
+    fn text(&self, path: &str) -> Option<String> {
+        read_text(&self.dir.join(BASE_FILES_DIR).join(path))
+    }
+}
+
+fn file_sha1(path: &Path) -> Result<String> {
+    let mut hasher = Sha1::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn hash_if_exists(path: &Path) -> Result<Option<String>> {
+    if path.is_file() {
+        file_sha1(path).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// File content if it exists and is text.
+fn read_text(path: &Path) -> Option<String> {
+    let bytes = fs::read(path).ok()?;
+    String::from_utf8(bytes).ok().filter(|text| !text.contains('\0'))
+}
+
+fn is_mod_jar(path: &str) -> bool {
+    path.starts_with("mods/") && path.ends_with(".jar")
+}
+
+/// Result of `merge_into_staging`.
+pub struct MergeOutcome {
+    pub summary: ModpackMergeSummary,
+    /// Files of the server directory that neither pack version has, relative with `/`.
+    pub untracked: Vec<String>,
+}
+
+/// Merges the user's changes into the staged new pack version, so the staging directory
+/// holds what the server directory should look like after the update.
+///
+/// Per file, with the old pack (`base`), the new pack (staging) and the server directory
+/// (`live`): files the user never touched take the new version, files the pack didn't change
+/// keep the user's version, and text files both changed in different places are merged line
+/// by line. Everything else is a conflict: the user's version stays, and the base and
+/// incoming versions are kept in the new base for the merge view. Files neither pack version
+/// has (logs, generated configs, extra mods, loader libraries) are not copied into staging;
+/// they are returned as `untracked` for `swap` to keep in the server directory.
+pub fn merge_into_staging(
+    staging: &Path,
+    live: &Path,
+    base: &PackBase,
+    protection: &Protection,
+) -> Result<MergeOutcome> {
+    let mut summary = ModpackMergeSummary::default();
+    let mut untracked = Vec::new();
+    let staged = modpack_staging::relative_files(staging)?;
+    let current = modpack_staging::relative_files(live)?;
+    let paths: BTreeSet<&str> = base
+        .index
+        .files
+        .keys()
+        .map(String::as_str)
+        .chain(staged.iter().map(String::as_str))
+        .chain(current.iter().map(String::as_str))
+        .collect();
+    let new_base_dir = staging.join(BASE_DIR);
+
+    for path in paths {
+        if protection.is_protected(path) {
+            continue;
+        }
+        let base_hash = base.hash(path);
+        let incoming_path = staging.join(path);
+        if base_hash.is_none() && !incoming_path.is_file() {
+            untracked.push(path.to_string());
+            continue;
+        }
+        let current_path = live.join(path);
+        let incoming_hash = hash_if_exists(&incoming_path)?;
+        let current_hash = hash_if_exists(&current_path)?;
+
+        if is_mod_jar(path) {
+            match (base_hash.is_some(), incoming_hash.is_some()) {
+                (false, true) => summary.added_mods.push(path.to_string()),
+                (true, false) => summary.removed_mods.push(path.to_string()),
+                _ => {}
+            }
+        }
+
+        if current_hash == incoming_hash {
+            continue;
+        }
+        if current_hash.as_deref() == base_hash {
+            // Untouched by the user: the staged version (or its absence) wins
+            summary.auto_applied.push(path.to_string());
+            continue;
+        }
+        if incoming_hash.as_deref() == base_hash {
+            // Only the user changed it
+            keep_current(&current_path, &incoming_path)?;
+            summary.kept_user_changes.push(path.to_string());
+            continue;
+        }
+
+        let base_text = base.text(path);
+        let current_text = read_text(&current_path);
+        let incoming_text = read_text(&incoming_path);
+        if let (Some(base_text), Some(current_text), Some(incoming_text)) = (&base_text, &current_text, &incoming_text) {
+            if let Some(merged) = merge_text(base_text, current_text, incoming_text) {
+                fs::write(&incoming_path, merged)?;
+                summary.merged.push(path.to_string());
+                continue;
+            }
+        }
+
+        // A real conflict: keep the incoming (and base) version for the merge view, serve the
+        // user's version until it is resolved
+        if let Some(base_text) = &base_text {
+            write_conflict_copy(&new_base_dir.join(CONFLICT_BASE_DIR).join(path), base_text.as_bytes())?;
+        }
+        if incoming_hash.is_some() {
+            write_conflict_copy(&new_base_dir.join(CONFLICT_INCOMING_DIR).join(path), &fs::read(&incoming_path)?)?;
+        }
+        keep_current(&current_path, &incoming_path)?;
+        let exists = |hash: Option<&str>, text: &Option<String>| hash.is_none() || text.is_some();
+        summary.conflicts.push(ModpackConflict {
+            path: path.to_string(),
+            base_exists: base_hash.is_some(),
+            current_exists: current_hash.is_some(),
+            incoming_exists: incoming_hash.is_some(),
+            text: (base_hash.is_none() || base_text.is_some())
+                && exists(current_hash.as_deref(), &current_text)
+                && exists(incoming_hash.as_deref(), &incoming_text),
+        });
+    }
+
+    write_conflicts(&new_base_dir, &summary.conflicts)?;
+    info!(
+        "Merged modpack update: {} applied, {} user changes kept, {} merged, {} conflicts, {} untracked",
+        summary.auto_applied.len(),
+        summary.kept_user_changes.len(),
+        summary.merged.len(),
+        summary.conflicts.len(),
+        untracked.len()
+    );
+    Ok(MergeOutcome { summary, untracked })
+}
+
+/// Makes the staged file match the live one: copied if it exists, removed if the user
+/// deleted it.
+fn keep_current(current: &Path, staged: &Path) -> Result<()> {
+    if current.is_file() {
+        if let Some(parent) = staged.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(current, staged)?;
+    } else {
+        fs_utils::remove_file(staged)?;
+    }
+    Ok(())
+}
+
+fn write_conflict_copy(target: &Path, content: &[u8]) -> Result<()> {
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(target, content)?;
+    Ok(())
+}
+
+fn write_conflicts(base_dir: &Path, conflicts: &[ModpackConflict]) -> Result<()> {
+    let json = serde_json::to_string_pretty(conflicts)
+        .map_err(|e| AppError::ModpackError(format!("Failed to serialize modpack conflicts: {}", e)))?;
+    fs_utils::ensure_directory(base_dir)?;
+    fs_utils::write_string_to_file_atomic(&base_dir.join(CONFLICTS_FILE), &json)
+}
+
+/// Unresolved conflicts of the last update.
+pub fn list_conflicts(server_dir: &Path) -> Result<Vec<ModpackConflict>> {
+    let path = server_dir.join(BASE_DIR).join(CONFLICTS_FILE);
+    if !path.is_file() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&fs_utils::read_file_to_string(&path)?)
+        .map_err(|e| AppError::ModpackError(format!("Modpack conflict list is corrupt: {}", e)))
+}
+
+fn find_conflict(server_dir: &Path, path: &str) -> Result<ModpackConflict> {
+    list_conflicts(server_dir)?
+        .into_iter()
+        .find(|conflict| conflict.path == path)
+        .ok_or_else(|| AppError::ModpackError(format!("'{}' has no unresolved modpack conflict", path)))
+}
+
+/// The base, current and incoming version of a conflicting file, with diffs of both sides
+/// against the base.
+pub fn merge_view(server_dir: &Path, path: &str) -> Result<ModpackMergeView> {
+    let conflict = find_conflict(server_dir, path)?;
+    let base_dir = server_dir.join(BASE_DIR);
+    let base = read_text(&base_dir.join(CONFLICT_BASE_DIR).join(path));
+    let current = read_text(&server_dir.join(path));
+    let incoming = read_text(&base_dir.join(CONFLICT_INCOMING_DIR).join(path));
+    let diff = |side: &Option<String>, label: &str| {
+        TextDiff::from_lines(base.as_deref().unwrap_or(""), side.as_deref().unwrap_or(""))
+            .unified_diff()
+            .context_radius(3)
+            .header(&format!("base/{}", path), &format!("{}/{}", label, path))
+            .to_string()
+    };
+    let current_diff = diff(&current, "current");
+    let incoming_diff = diff(&incoming, "incoming");
+    Ok(ModpackMergeView { conflict, base, current, incoming, current_diff, incoming_diff })
+}
+
+/// Applies `resolution` to the server directory and removes the conflict from the list.
+pub fn resolve_conflict(server_dir: &Path, path: &str, resolution: ConflictResolution) -> Result<()> {
+    find_conflict(server_dir, path)?;
+    let base_dir = server_dir.join(BASE_DIR);
+    let target = server_dir.join(path);
+    let incoming = base_dir.join(CONFLICT_INCOMING_DIR).join(path);
+    match resolution {
+        ConflictResolution::KeepCurrent => {}
+        ConflictResolution::TakeIncoming => {
+            if incoming.is_file() {
+                fs_utils::write_bytes_to_file_atomic(&target, &fs::read(&incoming)?)?;
+            } else {
+                fs_utils::remove_file(&target)?;
+            }
+        }
+        ConflictResolution::Custom { content } => fs_utils::write_string_to_file_atomic(&target, &content)?,
+    }
+
+    fs_utils::remove_file(&incoming)?;
+    fs_utils::remove_file(&base_dir.join(CONFLICT_BASE_DIR).join(path))?;
+    let remaining: Vec<ModpackConflict> =
+        list_conflicts(server_dir)?.into_iter().filter(|conflict| conflict.path != path).collect();
+    write_conflicts(&base_dir, &remaining)?;
+    info!("Resolved modpack conflict in {} ({} left)", path, remaining.len());
+    Ok(())
+}
+
+/// A replacement of base lines `start..end` by `lines`.
+#[derive(Debug, PartialEq)]
+struct Edit<'a> {
+    start: usize,
+    end: usize,
+    lines: Vec<&'a str>,
+}
+
+/// Edits turning `base` into `other`, with adjacent ones combined.
+fn edits<'a>(base: &[&'a str], other: &[&'a str]) -> Vec<Edit<'a>> {
+    let mut edits: Vec<Edit<'a>> = Vec::new();
+    for op in similar::capture_diff_slices(Algorithm::Myers, base, other) {
+        let (tag, old, new) = op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            continue;
+        }
+        let lines = other[new].to_vec();
+        match edits.last_mut() {
+            Some(last) if last.end == old.start => {
+                last.end = old.end;
+                last.lines.extend(lines);
+            }
+            _ => edits.push(Edit { start: old.start, end: old.end, lines }),
+        }
+    }
+    edits
+}
+
+/// Line-based three-way merge. Returns None if both sides changed the same or adjacent
+/// lines differently.
+fn merge_text(base: &str, current: &str, incoming: &str) -> Option<String> {
+    let base_lines: Vec<&str> = base.split_inclusive('\n').collect();
+    let current_lines: Vec<&str> = current.split_inclusive('\n').collect();
+    let incoming_lines: Vec<&str> = incoming.split_inclusive('\n').collect();
+    let ours = edits(&base_lines, &current_lines);
+    let theirs = edits(&base_lines, &incoming_lines);
+
+    let mut merged = String::new();
+    let mut position = 0;
+    let (mut i, mut j) = (0, 0);
+    loop {
+        let next = match (ours.get(i), theirs.get(j)) {
+            (None, None) => break,
+            (Some(a), Some(b)) if a.start <= b.end && b.start <= a.end => {
+                if a != b {
+                    return None;
+                }
+                i += 1;
+                j += 1;
+                a
+            }
+            (Some(a), Some(b)) if b.start < a.start => {
+                j += 1;
+                b
+            }
+            (Some(a), _) => {
+                i += 1;
+                a
+            }
+            (None, Some(b)) => {
+                j += 1;
+                b
+            }
+        };
+        if next.start < position {
+            return None;
+        }
+        merged.extend(base_lines[position..next.start].iter().copied());
+        merged.extend(next.lines.iter().copied());
+        position = next.end;
+    }
+    merged.extend(base_lines[position..].iter().copied());
+    Some(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge_text;
+
+    const BASE: &str = "a\nb\nc\nd\ne\n";
+
+    #[test]
+    fn merges_disjoint_edits() {
+        let current = "a\nB\nc\nd\ne\n";
+        let incoming = "a\nb\nc\nD\ne\n";
+        assert_eq!(merge_text(BASE, current, incoming).as_deref(), Some("a\nB\nc\nD\ne\n"));
+    }
+
+    #[test]
+    fn applies_identical_edits_once() {
+        let both = "a\nB\nc\nd\ne\n";
+        assert_eq!(merge_text(BASE, both, both).as_deref(), Some(both));
+    }
+
+    #[test]
+    fn rejects_overlapping_edits() {
+        assert_eq!(merge_text(BASE, "a\nX\nc\nd\ne\n", "a\nY\nc\nd\ne\n"), None);
+        // Adjacent lines count as overlapping
+        assert_eq!(merge_text(BASE, "a\nB\nc\nd\ne\n", "a\nb\nC\nd\ne\n"), None);
+    }
+
+    #[test]
+    fn rejects_different_insertions_at_the_same_point() {
+        assert_eq!(merge_text(BASE, "a\nx\nb\nc\nd\ne\n", "a\ny\nb\nc\nd\ne\n"), None);
+    }
+
+    #[test]
+    fn merges_identical_insertions_at_the_same_point() {
+        let both = "a\nx\nb\nc\nd\ne\n";
+        assert_eq!(merge_text(BASE, both, both).as_deref(), Some(both));
+    }
+}
@@ -0,0 +1,173 @@
+// src/config/mrpack.rs
+
+use crate::error::{AppError, Result};
+use crate::models::config::ModpackLoader;
+use crate::models::server_software::{Checksum, ServerFlavor};
+use crate::utils::download::{self, DownloadJob};
+use log::info;
+use reqwest::blocking::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path};
+
+/// Index file at the root of a `.mrpack`.
+pub const INDEX_FILE: &str = "modrinth.index.json";
+/// Files applied over the downloads on every side, then server-only ones on top.
+pub const OVERRIDE_DIRECTORIES: &[&str] = &["overrides/", "server-overrides/"];
+/// Number of files downloaded at the same time.
+const DOWNLOAD_THREADS: usize = 6;
+/// Hosts the Modrinth format allows downloads from.
+const ALLOWED_DOWNLOAD_HOSTS: &[&str] = &["cdn.modrinth.com", "github.com", "raw.githubusercontent.com", "gitlab.com"];
+/// Loader keys of the `dependencies` block.
+const LOADER_DEPENDENCIES: &[(&str, ServerFlavor)] = &[
+    ("forge", ServerFlavor::Forge),
+    ("neoforge", ServerFlavor::NeoForge),
+    ("fabric-loader", ServerFlavor::Fabric),
+    ("quilt-loader", ServerFlavor::Quilt),
+];
+
+/// `modrinth.index.json` (format version 1).
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackIndex {
+    pub format_version: u32,
+    pub game: String,
+    pub version_id: String,
+    pub name: String,
+    #[serde(default)]
+    pub summary: Option<String>,
+    pub files: Vec<MrpackFile>,
+    /// "minecraft" plus at most one loader, mapped to their versions.
+    pub dependencies: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackFile {
+    /// Destination relative to the server directory.
+    pub path: String,
+    pub hashes: MrpackHashes,
+    /// Missing means the file is needed on both sides.
+    #[serde(default)]
+    pub env: Option<MrpackEnv>,
+    /// Mirrors, tried in order.
+    pub downloads: Vec<String>,
+    #[serde(default)]
+    pub file_size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MrpackHashes {
+    pub sha512: String,
+    #[serde(default)]
+    pub sha1: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MrpackEnv {
+    pub server: EnvSupport,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvSupport {
+    Required,
+    Optional,
+    Unsupported,
+}
+
+impl MrpackFile {
+    /// Client-only files (`env.server` is `unsupported`) are not installed on a server.
+    pub fn is_for_server(&self) -> bool {
+        self.env.as_ref().map(|env| env.server) != Some(EnvSupport::Unsupported)
+    }
+}
+
+impl MrpackIndex {
+    /// Minecraft version from the `dependencies` block.
+    pub fn minecraft_version(&self) -> Result<&str> {
+        self.dependencies
+            .get("minecraft")
+            .map(String::as_str)
+            .ok_or_else(|| AppError::ModpackError(format!("'{}' declares no minecraft dependency", self.name)))
+    }
+
+    /// Mod loader from the `dependencies` block; None for packs that run on vanilla.
+    pub fn loader(&self) -> Result<Option<ModpackLoader>> {
+        let minecraft_version = self.minecraft_version()?.to_string();
+        Ok(LOADER_DEPENDENCIES.iter().find_map(|(key, flavor)| {
+            self.dependencies.get(*key).map(|version| ModpackLoader {
+                flavor: *flavor,
+                minecraft_version: minecraft_version.clone(),
+                loader_version: Some(version.clone()),
+            })
+        }))
+    }
+}
+
+/// Whether the zip at `archive` is a Modrinth pack.
+pub fn is_mrpack(archive: &Path) -> bool {
+    File::open(archive)
+        .ok()
+        .and_then(|file| zip::ZipArchive::new(file).ok())
+        .is_some_and(|mut zip| zip.by_name(INDEX_FILE).is_ok())
+}
+
+/// Reads and validates the index of a `.mrpack`. Rejects packs for other games, unknown
+/// format versions, file paths that leave the server directory and downloads from hosts the
+/// format doesn't allow.
+pub fn read_index(archive: &Path) -> Result<MrpackIndex> {
+    let file = File::open(archive)?;
+    let mut zip = zip::ZipArchive::new(file)
+        .map_err(|e| AppError::ModpackError(format!("Failed to open zip archive: {}", e)))?;
+    let mut content = String::new();
+    zip.by_name(INDEX_FILE)
+        .map_err(|e| AppError::ModpackError(format!("{} not found in pack: {}", INDEX_FILE, e)))?
+        .read_to_string(&mut content)?;
+    let index: MrpackIndex = serde_json::from_str(&content)
+        .map_err(|e| AppError::ModpackError(format!("Invalid {}: {}", INDEX_FILE, e)))?;
+
+    if index.game != "minecraft" {
+        return Err(AppError::ModpackError(format!("Pack is for '{}', not minecraft", index.game)));
+    }
+    if index.format_version != 1 {
+        return Err(AppError::ModpackError(format!("Unsupported .mrpack format version {}", index.format_version)));
+    }
+    for file in &index.files {
+        if !Path::new(&file.path).components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(AppError::ModpackError(format!("Pack file path '{}' is not allowed", file.path)));
+        }
+        if file.downloads.is_empty() {
+            return Err(AppError::ModpackError(format!("Pack file '{}' has no downloads", file.path)));
+        }
+        for url in &file.downloads {
+            let allowed = reqwest::Url::parse(url)
+                .ok()
+                .filter(|u| u.scheme() == "https")
+                .and_then(|u| u.host_str().map(|host| ALLOWED_DOWNLOAD_HOSTS.contains(&host)))
+                .unwrap_or(false);
+            if !allowed {
+                return Err(AppError::ModpackError(format!("Download URL '{}' is not from an allowed host", url)));
+            }
+        }
+    }
+    info!("Read {} {} ({} files)", index.name, index.version_id, index.files.len());
+    Ok(index)
+}
+
+/// Downloads `files` into `target_dir` on several threads, verifying each against its
+/// SHA-512. Emits overall `Download` progress.
+pub fn download_files(client: &Client, files: &[&MrpackFile], target_dir: &Path) -> Result<()> {
+    let jobs: Vec<DownloadJob> = files
+        .iter()
+        .map(|file| DownloadJob {
+            urls: file.downloads.clone(),
+            checksum: Some(Checksum::Sha512(file.hashes.sha512.clone())),
+            destination: target_dir.join(&file.path),
+            label: file.path.clone(),
+        })
+        .collect();
+    download::download_all(client, &jobs, DOWNLOAD_THREADS, "Download")
+}
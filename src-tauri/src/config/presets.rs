@@ -0,0 +1,385 @@
+// src/config/presets.rs
+
+use crate::app_state::AppState;
+use crate::commands::gamerules::{self, GameruleService};
+use crate::commands::startup_queue::StartupCommandQueue;
+use crate::config::manager_config::ManagerConfigStore;
+use crate::config::{property_catalog, server_properties};
+use crate::error::{AppError, Result};
+use crate::models::preset::{JvmProfile, JvmSettings, PresetApplyResult, ServerPreset};
+use crate::utils::fs_utils;
+use lazy_static::lazy_static;
+use log::{debug, info, warn};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+lazy_static! {
+    // Pack ids as listed by `datapack list`: "vanilla", "fabric", "file/MyPack.zip"
+    static ref DATAPACK_ID_REGEX: Regex =
+        Regex::new(r"^(?:file/[A-Za-z0-9_.\-]+|[a-z0-9_.\-/]+)$").unwrap();
+}
+
+/// Properties that identify one server instance and are left out of saved presets.
+const INSTANCE_PROPERTIES: &[&str] = &[
+    "server-ip",
+    "server-port",
+    "query.port",
+    "rcon.port",
+    "rcon.password",
+    "level-name",
+];
+
+/// Aikar's G1GC flags for heaps below 12 GB (https://docs.papermc.io/paper/aikars-flags).
+const AIKAR_FLAGS: &[&str] = &[
+    "-XX:+UseG1GC",
+    "-XX:+ParallelRefProcEnabled",
+    "-XX:MaxGCPauseMillis=200",
+    "-XX:+UnlockExperimentalVMOptions",
+    "-XX:+DisableExplicitGC",
+    "-XX:+AlwaysPreTouch",
+    "-XX:G1NewSizePercent=30",
+    "-XX:G1MaxNewSizePercent=40",
+    "-XX:G1HeapRegionSize=8M",
+    "-XX:G1ReservePercent=20",
+    "-XX:G1HeapWastePercent=5",
+    "-XX:G1MixedGCCountTarget=4",
+    "-XX:InitiatingHeapOccupancyPercent=15",
+    "-XX:G1MixedGCLiveThresholdPercent=90",
+    "-XX:G1RSetUpdatingPauseTimePercent=5",
+    "-XX:SurvivorRatio=32",
+    "-XX:+PerfDisableSharedMem",
+    "-XX:MaxTenuringThreshold=1",
+    "-Dusing.aikars.flags=https://mcflags.emc.gs",
+    "-Daikars.new.flags=true",
+];
+
+/// Stores server presets (`presets/<name>.json` in the app data directory) and applies them.
+pub struct PresetManager {
+    state: Arc<AppState>,
+    config_store: Arc<ManagerConfigStore>,
+    startup_queue: Arc<StartupCommandQueue>,
+    gamerule_service: Arc<GameruleService>,
+    presets_dir: PathBuf,
+}
+
+impl PresetManager {
+    pub fn new(
+        state: Arc<AppState>,
+        config_store: Arc<ManagerConfigStore>,
+        startup_queue: Arc<StartupCommandQueue>,
+        gamerule_service: Arc<GameruleService>,
+        presets_dir: PathBuf,
+    ) -> Self {
+        if let Err(e) = fs_utils::ensure_directory(&presets_dir) {
+            warn!("Failed to create presets directory {}: {}", presets_dir.display(), e);
+        }
+        Self {
+            state,
+            config_store,
+            startup_queue,
+            gamerule_service,
+            presets_dir,
+        }
+    }
+
+    /// Lists all presets, sorted by name. Unreadable files are skipped with a warning.
+    pub fn list_presets(&self) -> Result<Vec<ServerPreset>> {
+        let mut presets = Vec::new();
+        for path in fs_utils::list_files_with_extension(&self.presets_dir, "json")? {
+            match read_preset_file(&path) {
+                Ok(preset) => presets.push(preset),
+                Err(e) => warn!("Skipping preset {}: {}", path.display(), e),
+            }
+        }
+        presets.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        Ok(presets)
+    }
+
+    /// Loads a preset by name.
+    pub fn get_preset(&self, name: &str) -> Result<ServerPreset> {
+        let path = self.preset_path(name)?;
+        if !path.exists() {
+            return Err(AppError::ConfigError(format!("Preset '{}' not found", name)));
+        }
+        read_preset_file(&path)
+    }
+
+    /// Validates and stores a preset, replacing one with the same name. Names that map to the
+    /// file of a different preset ("My Preset" and "my_preset") are rejected.
+    pub fn save_preset(&self, preset: &ServerPreset) -> Result<()> {
+        validate_preset(preset)?;
+        let path = self.preset_path(&preset.name)?;
+        if let Ok(existing) = read_preset_file(&path) {
+            if existing.name.trim() != preset.name.trim() {
+                return Err(AppError::ConfigError(format!(
+                    "Preset name '{}' is too similar to the existing preset '{}'; choose another name",
+                    preset.name.trim(),
+                    existing.name
+                )));
+            }
+        }
+        let json = serde_json::to_string_pretty(preset)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize preset: {}", e)))?;
+        fs_utils::write_string_to_file_atomic(&path, &json)?;
+        info!("Saved preset '{}'", preset.name);
+        Ok(())
+    }
+
+    pub fn delete_preset(&self, name: &str) -> Result<()> {
+        let path = self.preset_path(name)?;
+        if !path.exists() {
+            return Err(AppError::ConfigError(format!("Preset '{}' not found", name)));
+        }
+        fs_utils::remove_file(&path)?;
+        info!("Deleted preset '{}'", name);
+        Ok(())
+    }
+
+    /// Applies a preset: properties, world settings and JVM arguments are written through
+    /// `update_config_fully` and persisted; gamerules become desired values of the
+    /// gamerule service (set on every start) and datapacks are queued as console commands
+    /// for the next successful start.
+    pub fn apply_preset(&self, name: &str) -> Result<PresetApplyResult> {
+        let preset = self.get_preset(name)?;
+        validate_preset(&preset)?;
+        info!("Applying preset '{}'", preset.name);
+
+        let mut config = server_properties::read_config_fully(self.state.clone())?;
+        config.server_properties.extend(preset.server_properties.clone());
+        if let Some(world) = &preset.world {
+            let world_properties = [
+                ("level-type", &world.level_type),
+                ("level-seed", &world.seed),
+                ("generator-settings", &world.generator_settings),
+            ];
+            for (key, value) in world_properties {
+                if let Some(value) = value {
+                    config.server_properties.insert(key.to_string(), value.clone());
+                }
+            }
+        }
+        if !preset.datapacks.is_empty() {
+            // New worlds pick the packs up at creation (1.19.3+)
+            let mut packs = vec!["vanilla".to_string()];
+            packs.extend(preset.datapacks.iter().filter(|p| p.as_str() != "vanilla").cloned());
+            config.server_properties.insert("initial-enabled-packs".to_string(), packs.join(","));
+        }
+        if let Some(jvm) = &preset.jvm {
+            config.java_args = build_java_args(jvm);
+        }
+
+        server_properties::update_config_fully(config.clone(), self.state.clone())?;
+        let java_args = config.java_args.clone();
+        self.config_store.update(|manager_config| {
+            manager_config.server = config;
+            Ok(())
+        })?;
+
+        let desired_gamerules = preset
+            .gamerules
+            .iter()
+            .map(|(rule, value)| gamerules::parse_gamerule_value(rule, value).map(|v| (rule.clone(), v)))
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        self.gamerule_service.record_desired(&desired_gamerules)?;
+
+        // Existing worlds need the packs enabled explicitly
+        let commands: Vec<String> = preset
+            .datapacks
+            .iter()
+            .map(|pack| format!("datapack enable \"{}\"", pack))
+            .collect();
+        if !commands.is_empty() {
+            self.startup_queue.enqueue(commands.clone())?;
+        }
+
+        info!(
+            "Preset '{}' applied; {} gamerules enforced, {} commands queued for the next start.",
+            preset.name,
+            desired_gamerules.len(),
+            commands.len()
+        );
+        Ok(PresetApplyResult {
+            queued_commands: commands,
+            java_args,
+        })
+    }
+
+    /// Saves the current server setup as a new preset. Instance-specific properties
+    /// (ports, IP, RCON password, world name) are left out. Gamerules are taken from the
+    /// values the gamerule service enforces.
+    pub fn save_current_as_preset(&self, name: &str, description: &str) -> Result<ServerPreset> {
+        let config = server_properties::read_config_fully(self.state.clone())?;
+        let server_properties = config
+            .server_properties
+            .into_iter()
+            .filter(|(key, _)| !INSTANCE_PROPERTIES.contains(&key.as_str()))
+            .collect();
+
+        let preset = ServerPreset {
+            name: name.trim().to_string(),
+            description: description.to_string(),
+            server_properties,
+            gamerules: self
+                .gamerule_service
+                .get_desired()?
+                .into_iter()
+                .map(|(rule, value)| (rule, value.to_string()))
+                .collect(),
+            jvm: Some(parse_java_args(&config.java_args)),
+            world: None, // level-type/seed are part of the properties above
+            datapacks: Vec::new(),
+        };
+        if self.preset_path(&preset.name)?.exists() {
+            return Err(AppError::ConfigError(format!("A preset named '{}' already exists", preset.name)));
+        }
+        self.save_preset(&preset)?;
+        Ok(preset)
+    }
+
+    /// Writes a preset to a JSON file chosen by the user.
+    pub fn export_preset(&self, name: &str, destination: &Path) -> Result<()> {
+        let preset = self.get_preset(name)?;
+        let json = serde_json::to_string_pretty(&preset)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize preset: {}", e)))?;
+        fs_utils::write_string_to_file_atomic(destination, &json)?;
+        info!("Exported preset '{}' to {}", name, destination.display());
+        Ok(())
+    }
+
+    /// Imports a preset from a JSON file. A name clash gets a numeric suffix.
+    pub fn import_preset(&self, source: &Path) -> Result<ServerPreset> {
+        let mut preset = read_preset_file(source)?;
+        validate_preset(&preset)?;
+        let base_name = preset.name.clone();
+        let mut suffix = 2;
+        while self.preset_path(&preset.name)?.exists() {
+            preset.name = format!("{} ({})", base_name, suffix);
+            suffix += 1;
+        }
+        self.save_preset(&preset)?;
+        info!("Imported preset '{}' from {}", preset.name, source.display());
+        Ok(preset)
+    }
+
+    /// File path of a preset. Names are mapped to safe file names.
+    fn preset_path(&self, name: &str) -> Result<PathBuf> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::ConfigError("Preset name cannot be empty".to_string()));
+        }
+        let file_name: String = name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        Ok(self.presets_dir.join(format!("{}.json", file_name)))
+    }
+}
+
+fn read_preset_file(path: &Path) -> Result<ServerPreset> {
+    let content = fs_utils::read_file_to_string(path)?;
+    serde_json::from_str(&content).map_err(|e| {
+        AppError::ConfigError(format!(
+            "Invalid preset file {} (line {}, column {}): {}",
+            path.display(),
+            e.line(),
+            e.column(),
+            e
+        ))
+    })
+}
+
+/// Checks properties against the catalog and gamerules/JVM settings for obvious mistakes.
+fn validate_preset(preset: &ServerPreset) -> Result<()> {
+    let mut problems: Vec<String> = property_catalog::validate_properties(&preset.server_properties)
+        .into_iter()
+        .map(|e| format!("{}: {}", e.key, e.message))
+        .collect();
+
+    for (rule, value) in &preset.gamerules {
+        match gamerules::parse_gamerule_value(rule, value) {
+            Err(AppError::ConfigError(message)) => problems.push(message),
+            Err(e) => problems.push(e.to_string()),
+            Ok(_) => {}
+        }
+    }
+    if let Some(jvm) = &preset.jvm {
+        if jvm.min_memory_mb == 0 || jvm.min_memory_mb > jvm.max_memory_mb {
+            problems.push("jvm: minimum memory must be between 1 MiB and the maximum memory".to_string());
+        }
+    }
+    // IDs end up in `datapack enable "<id>"` on the console, so nothing but the pack-id grammar
+    for id in preset.datapacks.iter().filter(|id| !DATAPACK_ID_REGEX.is_match(id)) {
+        problems.push(format!("datapacks: invalid datapack ID {:?}", id));
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::ConfigError(format!(
+            "Invalid preset '{}': {}",
+            preset.name,
+            problems.join("; ")
+        )))
+    }
+}
+
+/// Generates Java arguments for the JVM settings.
+pub fn build_java_args(jvm: &JvmSettings) -> Vec<String> {
+    let mut args = vec![
+        format!("-Xms{}M", jvm.min_memory_mb),
+        format!("-Xmx{}M", jvm.max_memory_mb),
+    ];
+    if jvm.profile == JvmProfile::Aikar {
+        args.extend(AIKAR_FLAGS.iter().map(|flag| flag.to_string()));
+    }
+    args.extend(jvm.extra_args.iter().cloned());
+    args
+}
+
+/// Derives JVM settings from existing Java arguments (inverse of `build_java_args`).
+fn parse_java_args(args: &[String]) -> JvmSettings {
+    let parse_memory = |value: &str| -> Option<u32> {
+        let (number, unit) = value.split_at(value.find(|c: char| !c.is_ascii_digit())?);
+        let number: u32 = number.parse().ok()?;
+        match unit.to_ascii_uppercase().as_str() {
+            "G" => Some(number * 1024),
+            "M" => Some(number),
+            "K" => Some(number / 1024),
+            _ => None,
+        }
+    };
+
+    let mut min_memory_mb = None;
+    let mut max_memory_mb = None;
+    let mut extra_args = Vec::new();
+    for arg in args {
+        if let Some(mb) = arg.strip_prefix("-Xms").and_then(parse_memory) {
+            min_memory_mb = Some(mb);
+        } else if let Some(mb) = arg.strip_prefix("-Xmx").and_then(parse_memory) {
+            max_memory_mb = Some(mb);
+        } else if !AIKAR_FLAGS.contains(&arg.as_str()) && arg != "-jar" {
+            extra_args.push(arg.clone());
+        }
+    }
+    let uses_aikar = AIKAR_FLAGS.iter().all(|flag| args.iter().any(|a| a == flag));
+    if !uses_aikar {
+        // Partial flag sets are kept verbatim
+        extra_args = args
+            .iter()
+            .filter(|a| !a.starts_with("-Xms") && !a.starts_with("-Xmx") && a.as_str() != "-jar")
+            .cloned()
+            .collect();
+    }
+
+    let max_memory_mb = max_memory_mb.unwrap_or(2048);
+    debug!("Derived JVM settings from {} Java arguments", args.len());
+    JvmSettings {
+        profile: if uses_aikar { JvmProfile::Aikar } else { JvmProfile::Default },
+        min_memory_mb: min_memory_mb.unwrap_or(max_memory_mb).min(max_memory_mb),
+        max_memory_mb,
+        extra_args,
+    }
+}
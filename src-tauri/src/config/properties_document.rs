@@ -0,0 +1,360 @@
+// src/config/properties_document.rs
+
+use crate::error::Result;
+use crate::utils::fs_utils;
+use log::{debug, trace};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// Whitespace as defined by `java.util.Properties` (space, tab, form feed).
+const PROPERTIES_WHITESPACE: [char; 3] = [' ', '\t', '\x0c'];
+
+/// Character encoding the file was read with. Written back the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Utf8,
+    /// `Properties.load(InputStream)` default; used when the file isn't valid UTF-8.
+    Latin1,
+}
+
+/// One logical element of the file.
+#[derive(Debug, Clone)]
+enum Line {
+    /// Empty or whitespace-only line, kept verbatim.
+    Blank(String),
+    /// Line starting with `#` or `!`, kept verbatim.
+    Comment(String),
+    /// A key/value pair, possibly spanning several physical lines via `\` continuations.
+    Entry {
+        /// Original physical lines. Emitted unchanged unless the value is updated.
+        raw: Vec<String>,
+        /// Leading whitespace of the first physical line.
+        indent: String,
+        /// Escaped key plus separator exactly as written (e.g. `motd=` or `motd = `).
+        key_prefix: String,
+        key: String,
+        value: String,
+    },
+}
+
+/// Lossless model of a Java `.properties` file such as `server.properties`.
+///
+/// Comments, blank lines, key order, separators and the escaping of untouched entries are
+/// preserved. Updating a key rewrites only that entry, escaping the new value the way
+/// `Properties.store` does (`\:`, `\=`, `\uXXXX` for non-ASCII, ...), so the server reads
+/// back exactly the string that was set.
+#[derive(Debug, Clone)]
+pub struct PropertiesDocument {
+    lines: Vec<Line>,
+    line_ending: String,
+    trailing_newline: bool,
+    encoding: Encoding,
+}
+
+impl Default for PropertiesDocument {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PropertiesDocument {
+    /// Creates an empty document.
+    pub fn new() -> Self {
+        Self {
+            lines: Vec::new(),
+            line_ending: "\n".to_string(),
+            trailing_newline: true,
+            encoding: Encoding::Utf8,
+        }
+    }
+
+    /// Reads a properties file. Returns an empty document if the file doesn't exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            debug!("Properties file {} not found, starting with an empty document.", path.display());
+            return Ok(Self::new());
+        }
+        let bytes = std::fs::read(path).map_err(|e| {
+            std::io::Error::new(e.kind(), format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        Ok(Self::from_bytes(&bytes))
+    }
+
+    /// Writes the document atomically, in the encoding it was read with.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs_utils::write_bytes_to_file_atomic(path, &self.to_bytes())
+    }
+
+    /// Parses raw file contents. Invalid UTF-8 is decoded as ISO-8859-1, like Java does.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self::parse(text),
+            Err(_) => {
+                let text: String = bytes.iter().map(|&b| b as char).collect();
+                let mut document = Self::parse(&text);
+                document.encoding = Encoding::Latin1;
+                document
+            }
+        }
+    }
+
+    /// Parses properties text.
+    pub fn parse(text: &str) -> Self {
+        let line_ending = if text.contains("\r\n") { "\r\n" } else { "\n" };
+        let trailing_newline = text.is_empty() || text.ends_with('\n');
+        let mut physical: Vec<&str> = text.split('\n').map(|l| l.strip_suffix('\r').unwrap_or(l)).collect();
+        if trailing_newline {
+            physical.pop(); // Empty element after the final newline
+        }
+
+        let mut lines = Vec::new();
+        let mut i = 0;
+        while i < physical.len() {
+            let line = physical[i];
+            let content = line.trim_start_matches(PROPERTIES_WHITESPACE);
+            if content.is_empty() {
+                lines.push(Line::Blank(line.to_string()));
+            } else if content.starts_with('#') || content.starts_with('!') {
+                lines.push(Line::Comment(line.to_string()));
+            } else {
+                let indent = line[..line.len() - content.len()].to_string();
+                let mut raw = vec![line.to_string()];
+                let mut logical = content.to_string();
+                // An odd number of trailing backslashes continues the line
+                while ends_with_continuation(&logical) {
+                    logical.pop();
+                    if i + 1 >= physical.len() {
+                        break;
+                    }
+                    i += 1;
+                    raw.push(physical[i].to_string());
+                    logical.push_str(physical[i].trim_start_matches(PROPERTIES_WHITESPACE));
+                }
+                let (key_end, value_start) = split_key_value(&logical);
+                let key_prefix = if value_start == key_end {
+                    format!("{}=", &logical[..key_end]) // Bare key: add a separator for later updates
+                } else {
+                    logical[..value_start].to_string()
+                };
+                lines.push(Line::Entry {
+                    raw,
+                    indent,
+                    key_prefix,
+                    key: unescape(&logical[..key_end]),
+                    value: unescape(&logical[value_start..]),
+                });
+            }
+            i += 1;
+        }
+
+        trace!("Parsed properties document with {} lines", lines.len());
+        Self {
+            lines,
+            line_ending: line_ending.to_string(),
+            trailing_newline,
+            encoding: Encoding::Utf8,
+        }
+    }
+
+    /// Returns the value of a key. If a key appears more than once the last one wins, as in Java.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries().filter(|(k, _)| *k == key).last().map(|(_, v)| v)
+    }
+
+    /// Iterates over all entries in file order.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.lines.iter().filter_map(|line| match line {
+            Line::Entry { key, value, .. } => Some((key.as_str(), value.as_str())),
+            _ => None,
+        })
+    }
+
+    /// Returns all entries as a map.
+    pub fn to_map(&self) -> HashMap<String, String> {
+        self.entries().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    /// Sets a key. Existing entries are updated in place (keeping the key's spelling and
+    /// separator); new keys are appended. Returns true if the document changed.
+    pub fn set(&mut self, key: &str, new_value: &str) -> bool {
+        let existing = self
+            .lines
+            .iter()
+            .rposition(|line| matches!(line, Line::Entry { key: k, .. } if k == key));
+
+        if let Some(Line::Entry { raw, indent, key_prefix, value, .. }) = existing.map(|idx| &mut self.lines[idx]) {
+            if value == new_value {
+                return false;
+            }
+            *raw = vec![format!("{}{}{}", indent, key_prefix, escape(new_value, false))];
+            *value = new_value.to_string();
+            return true;
+        }
+
+        let key_prefix = format!("{}=", escape(key, true));
+        self.lines.push(Line::Entry {
+            raw: vec![format!("{}{}", key_prefix, escape(new_value, false))],
+            indent: String::new(),
+            key_prefix,
+            key: key.to_string(),
+            value: new_value.to_string(),
+        });
+        true
+    }
+
+    /// Removes all entries for a key. Returns true if any were removed.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let before = self.lines.len();
+        self.lines.retain(|line| !matches!(line, Line::Entry { key: k, .. } if k == key));
+        self.lines.len() != before
+    }
+
+    /// Appends a comment line (`#` is added).
+    pub fn push_comment(&mut self, text: &str) {
+        self.lines.push(Line::Comment(format!("#{}", text)));
+    }
+
+    /// Serializes the document in its original encoding.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let text = self.to_string();
+        match self.encoding {
+            Encoding::Utf8 => text.into_bytes(),
+            // Untouched lines came from Latin-1 and new values are ASCII-escaped, so this is lossless
+            Encoding::Latin1 => text.chars().map(|c| if (c as u32) <= 0xFF { c as u8 } else { b'?' }).collect(),
+        }
+    }
+}
+
+impl fmt::Display for PropertiesDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut physical = self.lines.iter().flat_map(|line| match line {
+            Line::Blank(raw) | Line::Comment(raw) => std::slice::from_ref(raw).iter(),
+            Line::Entry { raw, .. } => raw.iter(),
+        });
+        if let Some(first) = physical.next() {
+            f.write_str(first)?;
+            for line in physical {
+                f.write_str(&self.line_ending)?;
+                f.write_str(line)?;
+            }
+            if self.trailing_newline {
+                f.write_str(&self.line_ending)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// True if the line ends with an odd number of backslashes.
+fn ends_with_continuation(line: &str) -> bool {
+    line.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1
+}
+
+/// Returns the byte index where the key ends and where the value starts, following
+/// `Properties.load`: the key ends at the first unescaped `=`, `:` or whitespace; the separator
+/// is optional whitespace, at most one `=`/`:`, then optional whitespace.
+fn split_key_value(line: &str) -> (usize, usize) {
+    let mut key_end = line.len();
+    let mut escaped = false;
+    for (idx, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '=' || c == ':' || PROPERTIES_WHITESPACE.contains(&c) {
+            key_end = idx;
+            break;
+        }
+    }
+
+    let rest = &line[key_end..];
+    let mut value_start = key_end + (rest.len() - rest.trim_start_matches(PROPERTIES_WHITESPACE).len());
+    if line[value_start..].starts_with(['=', ':']) {
+        value_start += 1;
+    }
+    let rest = &line[value_start..];
+    value_start += rest.len() - rest.trim_start_matches(PROPERTIES_WHITESPACE).len();
+    (key_end, value_start)
+}
+
+/// Resolves `\t`, `\n`, `\r`, `\f`, `\uXXXX` (including surrogate pairs) and `\x` -> `x`.
+fn unescape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut utf16: Vec<u16> = Vec::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            flush_utf16(&mut utf16, &mut result);
+            result.push(c);
+            continue;
+        }
+        let Some(escaped) = chars.next() else { break }; // Lone trailing backslash is dropped
+        if escaped == 'u' {
+            let hex: String = chars.clone().take(4).collect();
+            if let (4, Ok(unit)) = (hex.len(), u16::from_str_radix(&hex, 16)) {
+                for _ in 0..4 {
+                    chars.next();
+                }
+                utf16.push(unit);
+                continue;
+            }
+            // Malformed escape: Java rejects the file, we keep the text as-is
+            flush_utf16(&mut utf16, &mut result);
+            result.push_str("\\u");
+            continue;
+        }
+        flush_utf16(&mut utf16, &mut result);
+        result.push(match escaped {
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            'f' => '\x0c',
+            other => other,
+        });
+    }
+    flush_utf16(&mut utf16, &mut result);
+    result
+}
+
+fn flush_utf16(buffer: &mut Vec<u16>, out: &mut String) {
+    if !buffer.is_empty() {
+        out.push_str(&String::from_utf16_lossy(buffer));
+        buffer.clear();
+    }
+}
+
+/// Escapes a value for a `.properties` file, e.g. when rendering templates.
+pub fn escape_value(value: &str) -> String {
+    escape(value, false)
+}
+
+/// Escapes a key or value like `Properties.store`: special characters get a backslash,
+/// control and non-ASCII characters become `\uXXXX`. Spaces are escaped everywhere in keys
+/// and only at the start of values.
+fn escape(text: &str, is_key: bool) -> String {
+    let mut result = String::with_capacity(text.len());
+    for (idx, c) in text.chars().enumerate() {
+        match c {
+            ' ' if is_key || idx == 0 => result.push_str("\\ "),
+            '\\' => result.push_str("\\\\"),
+            '\t' => result.push_str("\\t"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\x0c' => result.push_str("\\f"),
+            '=' | ':' | '#' | '!' => {
+                result.push('\\');
+                result.push(c);
+            }
+            c if c < ' ' || c > '~' => {
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    result.push_str(&format!("\\u{:04X}", unit));
+                }
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
@@ -0,0 +1,248 @@
+// src/config/property_catalog.rs
+
+use crate::models::config::ServerConfig;
+use crate::models::property::{PropertyError, PropertySpec, PropertyType, PropertyView, ServerConfigView};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+const DIFFICULTIES: &[&str] = &["peaceful", "easy", "normal", "hard"];
+const GAMEMODES: &[&str] = &["survival", "creative", "adventure", "spectator"];
+const REGION_COMPRESSIONS: &[&str] = &["deflate", "lz4", "none"];
+
+const PORT: PropertyType = PropertyType::Int { min: Some(1), max: Some(65535) };
+const NON_NEGATIVE: PropertyType = PropertyType::Int { min: Some(0), max: None };
+
+impl PropertySpec {
+    /// Key that has existed in every supported version and needs a restart.
+    const fn new(key: &'static str, property_type: PropertyType, default: &'static str, description: &'static str) -> Self {
+        Self {
+            key,
+            property_type,
+            default,
+            description,
+            since: None,
+            removed_in: None,
+            requires_restart: true,
+        }
+    }
+
+    const fn since(mut self, version: &'static str) -> Self {
+        self.since = Some(version);
+        self
+    }
+
+    const fn removed_in(mut self, version: &'static str) -> Self {
+        self.removed_in = Some(version);
+        self
+    }
+
+    /// Marks the key as applicable to a running server (see `live_command`).
+    const fn live(mut self) -> Self {
+        self.requires_restart = false;
+        self
+    }
+
+    /// Returns true if the given Minecraft version reads this key.
+    /// Versions that can't be compared (snapshots, custom names) count as supported.
+    pub fn is_available_in(&self, version: &str) -> bool {
+        let introduced = self
+            .since
+            .map_or(true, |since| compare_versions(version, since).map_or(true, |o| o != Ordering::Less));
+        let not_removed = self
+            .removed_in
+            .map_or(true, |removed| compare_versions(version, removed).map_or(true, |o| o == Ordering::Less));
+        introduced && not_removed
+    }
+
+    /// Checks a value against the key's type. Returns a message describing the problem.
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        match &self.property_type {
+            PropertyType::Bool => match value {
+                "true" | "false" => Ok(()),
+                _ => Err("must be 'true' or 'false'".to_string()),
+            },
+            PropertyType::Int { min, max } => {
+                let number: i64 = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| "must be a whole number".to_string())?;
+                match (min, max) {
+                    (Some(min), Some(max)) if number < *min || number > *max => {
+                        Err(format!("must be between {} and {}", min, max))
+                    }
+                    (Some(min), _) if number < *min => Err(format!("must be at least {}", min)),
+                    (_, Some(max)) if number > *max => Err(format!("must be at most {}", max)),
+                    _ => Ok(()),
+                }
+            }
+            PropertyType::Enum { values } => {
+                if values.contains(&value) {
+                    Ok(())
+                } else {
+                    Err(format!("must be one of: {}", values.join(", ")))
+                }
+            }
+            PropertyType::String => Ok(()),
+        }
+    }
+}
+
+/// Vanilla (Java Edition) `server.properties` keys. Servers only read the file at startup,
+/// so everything needs a restart unless the manager can apply it through the console.
+static CATALOG: &[PropertySpec] = &[
+    PropertySpec::new("accepts-transfers", PropertyType::Bool, "false", "Accept players transferred from another server with the /transfer packet.").since("1.20.5"),
+    PropertySpec::new("allow-flight", PropertyType::Bool, "false", "Allow flying in survival mode (e.g. with mods). If false, players airborne for 5 seconds are kicked."),
+    PropertySpec::new("allow-nether", PropertyType::Bool, "true", "Allow players to travel to the Nether."),
+    PropertySpec::new("broadcast-console-to-ops", PropertyType::Bool, "true", "Send console command output to all online operators."),
+    PropertySpec::new("broadcast-rcon-to-ops", PropertyType::Bool, "true", "Send RCON command output to all online operators."),
+    PropertySpec::new("bug-report-link", PropertyType::String, "", "URL shown in the disconnect screen for reporting server bugs.").since("1.21"),
+    PropertySpec::new("difficulty", PropertyType::Enum { values: DIFFICULTIES }, "easy", "Difficulty of the world.").live(),
+    PropertySpec::new("enable-command-block", PropertyType::Bool, "false", "Allow command blocks to run."),
+    PropertySpec::new("enable-jmx-monitoring", PropertyType::Bool, "false", "Expose tick time MBeans over JMX.").since("1.16"),
+    PropertySpec::new("enable-query", PropertyType::Bool, "false", "Enable the GameSpy4 query protocol."),
+    PropertySpec::new("enable-rcon", PropertyType::Bool, "false", "Enable remote console access."),
+    PropertySpec::new("enable-status", PropertyType::Bool, "true", "Show the server as online in the multiplayer server list.").since("1.16"),
+    PropertySpec::new("enforce-secure-profile", PropertyType::Bool, "true", "Require players to have a Mojang-signed public key to join.").since("1.19"),
+    PropertySpec::new("enforce-whitelist", PropertyType::Bool, "false", "Kick players that are not on the whitelist when it is reloaded."),
+    PropertySpec::new("entity-broadcast-range-percentage", PropertyType::Int { min: Some(10), max: Some(1000) }, "100", "Distance, in percent of the default, at which entities are sent to clients.").since("1.16"),
+    PropertySpec::new("force-gamemode", PropertyType::Bool, "false", "Put players into the default game mode every time they join."),
+    PropertySpec::new("function-permission-level", PropertyType::Int { min: Some(1), max: Some(4) }, "2", "Permission level of functions run by the server.").since("1.14.4"),
+    PropertySpec::new("gamemode", PropertyType::Enum { values: GAMEMODES }, "survival", "Game mode of new players.").live(),
+    PropertySpec::new("generate-structures", PropertyType::Bool, "true", "Generate villages, temples and other structures in new chunks."),
+    PropertySpec::new("generator-settings", PropertyType::String, "{}", "JSON settings for the flat and custom world generators."),
+    PropertySpec::new("hardcore", PropertyType::Bool, "false", "Players are set to spectator mode when they die."),
+    PropertySpec::new("hide-online-players", PropertyType::Bool, "false", "Don't send the player list in status responses.").since("1.18"),
+    PropertySpec::new("initial-disabled-packs", PropertyType::String, "", "Comma-separated datapacks not enabled when the world is created.").since("1.19.3"),
+    PropertySpec::new("initial-enabled-packs", PropertyType::String, "vanilla", "Comma-separated datapacks enabled when the world is created.").since("1.19.3"),
+    PropertySpec::new("level-name", PropertyType::String, "world", "Name of the world folder."),
+    PropertySpec::new("level-seed", PropertyType::String, "", "Seed of new worlds. Empty picks a random seed."),
+    PropertySpec::new("level-type", PropertyType::String, "minecraft:normal", "World preset of new worlds, e.g. minecraft:flat or minecraft:amplified."),
+    PropertySpec::new("log-ips", PropertyType::Bool, "true", "Include player IP addresses in the server log.").since("1.20.2"),
+    PropertySpec::new("max-build-height", PropertyType::Int { min: Some(1), max: Some(256) }, "256", "Maximum building height.").removed_in("1.17"),
+    PropertySpec::new("max-chained-neighbor-updates", PropertyType::Int { min: None, max: None }, "1000000", "Limit of consecutive neighbor updates before skipping. Negative disables the limit.").since("1.19"),
+    PropertySpec::new("max-players", PropertyType::Int { min: Some(0), max: Some(2147483647) }, "20", "Maximum number of players online at once."),
+    PropertySpec::new("max-tick-time", PropertyType::Int { min: Some(-1), max: None }, "60000", "Milliseconds a single tick may take before the watchdog stops the server. -1 disables it."),
+    PropertySpec::new("max-world-size", PropertyType::Int { min: Some(1), max: Some(29999984) }, "29999984", "Maximum radius of the world border in blocks."),
+    PropertySpec::new("motd", PropertyType::String, "A Minecraft Server", "Message shown in the server list."),
+    PropertySpec::new("network-compression-threshold", PropertyType::Int { min: Some(-1), max: None }, "256", "Packets larger than this many bytes are compressed. -1 disables compression."),
+    PropertySpec::new("online-mode", PropertyType::Bool, "true", "Verify player accounts with Mojang. Disable only behind a proxy that authenticates."),
+    PropertySpec::new("op-permission-level", PropertyType::Int { min: Some(0), max: Some(4) }, "4", "Default permission level of new operators."),
+    PropertySpec::new("pause-when-empty-seconds", PropertyType::Int { min: Some(0), max: None }, "60", "Seconds without players before the server pauses ticking. 0 disables pausing.").since("1.21.2"),
+    PropertySpec::new("player-idle-timeout", NON_NEGATIVE, "0", "Minutes before idle players are kicked. 0 disables it."),
+    PropertySpec::new("prevent-proxy-connections", PropertyType::Bool, "false", "Kick players whose IP differs from the one Mojang saw at login."),
+    PropertySpec::new("previews-chat", PropertyType::Bool, "false", "Enable chat preview.").since("1.19").removed_in("1.19.3"),
+    PropertySpec::new("pvp", PropertyType::Bool, "true", "Allow players to damage each other."),
+    PropertySpec::new("query.port", PORT, "25565", "UDP port of the query protocol."),
+    PropertySpec::new("rate-limit", NON_NEGATIVE, "0", "Packets per second a client may send before being kicked. 0 disables it."),
+    PropertySpec::new("rcon.password", PropertyType::String, "", "Password for remote console access."),
+    PropertySpec::new("rcon.port", PORT, "25575", "TCP port of the remote console."),
+    PropertySpec::new("region-file-compression", PropertyType::Enum { values: REGION_COMPRESSIONS }, "deflate", "Compression algorithm for region files.").since("1.20.5"),
+    PropertySpec::new("require-resource-pack", PropertyType::Bool, "false", "Kick players that decline the server resource pack.").since("1.17"),
+    PropertySpec::new("resource-pack", PropertyType::String, "", "URL of the server resource pack."),
+    PropertySpec::new("resource-pack-id", PropertyType::String, "", "UUID identifying the resource pack on clients.").since("1.20.3"),
+    PropertySpec::new("resource-pack-prompt", PropertyType::String, "", "Custom message shown when offering the resource pack.").since("1.17"),
+    PropertySpec::new("resource-pack-sha1", PropertyType::String, "", "SHA-1 of the resource pack, used to verify downloads."),
+    PropertySpec::new("server-ip", PropertyType::String, "", "Address to bind to. Empty binds to all interfaces."),
+    PropertySpec::new("server-port", PORT, "25565", "TCP port the server listens on."),
+    PropertySpec::new("simulation-distance", PropertyType::Int { min: Some(3), max: Some(32) }, "10", "Radius in chunks around players in which entities are ticked.").since("1.18"),
+    PropertySpec::new("snooper-enabled", PropertyType::Bool, "true", "Send usage statistics to Mojang.").removed_in("1.18"),
+    PropertySpec::new("spawn-animals", PropertyType::Bool, "true", "Spawn animals.").removed_in("1.21.2"),
+    PropertySpec::new("spawn-monsters", PropertyType::Bool, "true", "Spawn hostile mobs."),
+    PropertySpec::new("spawn-npcs", PropertyType::Bool, "true", "Spawn villagers.").removed_in("1.21.2"),
+    PropertySpec::new("spawn-protection", NON_NEGATIVE, "16", "Radius around spawn in which non-operators can't build. 0 disables it."),
+    PropertySpec::new("sync-chunk-writes", PropertyType::Bool, "true", "Write chunk files synchronously.").since("1.16"),
+    PropertySpec::new("text-filtering-config", PropertyType::String, "", "Configuration of the chat text filter.").since("1.17"),
+    PropertySpec::new("use-native-transport", PropertyType::Bool, "true", "Use optimized Linux packet handling (epoll)."),
+    PropertySpec::new("view-distance", PropertyType::Int { min: Some(3), max: Some(32) }, "10", "Radius in chunks the server sends to clients."),
+    PropertySpec::new("white-list", PropertyType::Bool, "false", "Only allow players on the whitelist to join.").live(),
+];
+
+/// Returns the whole catalog.
+pub fn catalog() -> &'static [PropertySpec] {
+    CATALOG
+}
+
+/// Looks up a key in the catalog.
+pub fn get_spec(key: &str) -> Option<&'static PropertySpec> {
+    CATALOG.iter().find(|spec| spec.key == key)
+}
+
+/// Validates all known keys of a properties map. Unknown keys are accepted as-is since
+/// plugins, mods and newer versions may add their own. Returns one error per invalid key.
+pub fn validate_properties(properties: &HashMap<String, String>) -> Vec<PropertyError> {
+    let mut errors: Vec<PropertyError> = properties
+        .iter()
+        .filter_map(|(key, value)| {
+            let spec = get_spec(key)?;
+            spec.validate(value).err().map(|message| PropertyError {
+                key: key.clone(),
+                value: value.clone(),
+                message,
+            })
+        })
+        .collect();
+    errors.sort_by(|a, b| a.key.cmp(&b.key));
+    errors
+}
+
+/// Returns the console command that applies a changed value to a running server,
+/// for keys that don't need a restart.
+pub fn live_command(key: &str, value: &str) -> Option<String> {
+    match key {
+        "difficulty" => Some(format!("difficulty {}", value)),
+        "gamemode" => Some(format!("defaultgamemode {}", value)),
+        "white-list" => Some(format!("whitelist {}", if value == "true" { "on" } else { "off" })),
+        _ => None,
+    }
+}
+
+/// Merges the configured values with the catalog. All catalog keys are listed (with defaults
+/// for unset ones) unless the version is known and doesn't read them.
+pub fn build_config_view(config: ServerConfig, minecraft_version: Option<String>) -> ServerConfigView {
+    let mut properties: Vec<PropertyView> = Vec::new();
+
+    for spec in CATALOG {
+        let available = minecraft_version.as_deref().map(|v| spec.is_available_in(v));
+        let current = config.server_properties.get(spec.key);
+        if available == Some(false) && current.is_none() {
+            continue; // Irrelevant for this version
+        }
+        properties.push(PropertyView {
+            key: spec.key.to_string(),
+            value: current.cloned().unwrap_or_else(|| spec.default.to_string()),
+            is_set: current.is_some(),
+            spec: Some(spec.clone()),
+            available,
+        });
+    }
+
+    for (key, value) in &config.server_properties {
+        if get_spec(key).is_none() {
+            properties.push(PropertyView {
+                key: key.clone(),
+                value: value.clone(),
+                is_set: true,
+                spec: None,
+                available: None,
+            });
+        }
+    }
+    properties.sort_by(|a, b| a.key.cmp(&b.key));
+
+    ServerConfigView {
+        config,
+        minecraft_version,
+        properties,
+    }
+}
+
+/// Compares release versions like "1.20" and "1.20.4" numerically ("1.20" == "1.20.0").
+/// Returns None if either isn't a plain dotted release version (snapshots, pre-releases).
+fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    let parse = |v: &str| -> Option<Vec<u32>> { v.trim().split('.').map(|part| part.parse().ok()).collect() };
+    let (mut a, mut b) = (parse(a)?, parse(b)?);
+    let len = a.len().max(b.len());
+    a.resize(len, 0);
+    b.resize(len, 0);
+    Some(a.cmp(&b))
+}
@@ -1,13 +1,16 @@
 ﻿// src/config/server_properties.rs
 
 use crate::app_state::AppState;
+use crate::commands::process_manager;
 use crate::error::{AppError, Result};
+use crate::config::properties_document::PropertiesDocument;
+use crate::config::property_catalog;
 use crate::models::config::ServerConfig; // Import the ServerConfig model
+use crate::models::server_status::ServerStatus;
 use log::{debug, error, info, warn};
 use std::collections::HashMap;
-use std::fs::{self, File};
-use std::io::{self, BufRead, BufReader, Write};
-use std::path::{Path, PathBuf};
+use std::io;
+use std::path::PathBuf;
 use std::sync::Arc;
 
 /// Returns the full path to the server.properties file.
@@ -15,80 +18,50 @@ fn get_properties_path(state: &AppState) -> PathBuf {
     state.server_directory.join("server.properties")
 }
 
-/// Reads the `server.properties` file into a HashMap.
-/// Skips empty lines and lines starting with '#'.
-/// Returns an empty HashMap if the file doesn't exist.
-pub fn read_properties_file(state: &Arc<AppState>) -> Result<HashMap<String, String>> {
+/// Returns the properties written to a new `server.properties` file.
+pub fn get_default_properties_map() -> HashMap<String, String> {
+    HashMap::from([
+        ("server-port".to_string(), "25565".to_string()),
+        ("gamemode".to_string(), "survival".to_string()),
+        ("difficulty".to_string(), "normal".to_string()),
+        ("motd".to_string(), "A Minecraft Server".to_string()), // Added motd
+        ("level-seed".to_string(), "".to_string()),
+        ("enable-command-block".to_string(), "false".to_string()),
+        ("max-players".to_string(), "20".to_string()),
+        ("spawn-protection".to_string(), "16".to_string()),
+        ("view-distance".to_string(), "10".to_string()),
+        ("simulation-distance".to_string(), "10".to_string()), // Added simulation-distance
+        ("spawn-npcs".to_string(), "true".to_string()),
+        ("spawn-animals".to_string(), "true".to_string()),
+        ("spawn-monsters".to_string(), "true".to_string()),
+        ("pvp".to_string(), "true".to_string()),
+        // Add other common defaults
+    ])
+}
+
+/// Reads `server.properties` as a lossless document (comments, order and escaping kept).
+/// Returns an empty document if the file doesn't exist.
+pub fn read_properties_document(state: &AppState) -> Result<PropertiesDocument> {
     let properties_path = get_properties_path(state);
     debug!("Reading properties file: {}", properties_path.display());
+    PropertiesDocument::load(&properties_path)
+}
 
-    match properties_path.try_exists() {
-        Ok(true) => {
-            let file = File::open(&properties_path).map_err(|e| {
-                AppError::IoError(io::Error::new(
-                    e.kind(),
-                    format!("Failed to open {}: {}", properties_path.display(), e),
-                ))
-            })?;
-            let reader = BufReader::new(file);
-            let mut properties = HashMap::new();
-
-            for (line_num, line_result) in reader.lines().enumerate() {
-                let line = line_result.map_err(|e| {
-                    AppError::IoError(io::Error::new(
-                        e.kind(),
-                        format!(
-                            "Failed to read line {} from {}: {}",
-                            line_num + 1,
-                            properties_path.display(),
-                            e
-                        ),
-                    ))
-                })?;
-                let line_trimmed = line.trim();
-
-                if line_trimmed.is_empty() || line_trimmed.starts_with('#') {
-                    continue;
-                }
-
-                // Split only on the first '=', allows '=' in values
-                if let Some((key, value)) = line_trimmed.split_once('=') {
-                    let key_trimmed = key.trim().to_string();
-                    let value_trimmed = value.trim().to_string();
-                    if !key_trimmed.is_empty() {
-                        debug!("Read property: '{}' = '{}'", key_trimmed, value_trimmed);
-                        properties.insert(key_trimmed, value_trimmed);
-                    } else {
-                        warn!("Skipping line {} due to empty key: '{}'", line_num + 1, line);
-                    }
-                } else {
-                    warn!(
-                        "Skipping malformed line {} (no '=' found): '{}'",
-                        line_num + 1,
-                        line
-                    );
-                }
-            }
-            info!(
-                "Successfully read {} properties from {}",
-                properties.len(),
-                properties_path.display()
-            );
-            Ok(properties)
-        }
-        Ok(false) => {
-            info!("Properties file not found: {}", properties_path.display());
-            Ok(HashMap::new()) // Return empty map if file doesn't exist
-        }
-        Err(e) => {
-            error!("Failed to check existence of {}: {}", properties_path.display(), e);
-            Err(AppError::IoError(io::Error::new(e.kind(), format!("Failed to check existence of {}: {}", properties_path.display(), e))))
-        }
-    }
+/// Reads the `server.properties` file into a HashMap of unescaped values.
+/// Returns an empty HashMap if the file doesn't exist.
+pub fn read_properties_file(state: &Arc<AppState>) -> Result<HashMap<String, String>> {
+    let properties = read_properties_document(state)?.to_map();
+    info!(
+        "Successfully read {} properties from {}",
+        properties.len(),
+        get_properties_path(state).display()
+    );
+    Ok(properties)
 }
 
-/// Writes a HashMap of properties to the `server.properties` file.
-/// Overwrites the file if it exists. Sorts keys alphabetically for consistency.
+/// Makes `server.properties` contain exactly the given properties.
+/// Only changed keys are rewritten in place; comments, blank lines and the order of existing
+/// keys are preserved. Keys missing from the map are removed, new keys are appended sorted.
 pub fn write_properties_file(
     properties: &HashMap<String, String>, // Borrow properties
     state: &Arc<AppState>,
@@ -100,29 +73,40 @@ pub fn write_properties_file(
         properties_path.display()
     );
 
-    let mut file = File::create(&properties_path).map_err(|e| {
-        AppError::IoError(io::Error::new(
-            e.kind(),
-            format!("Failed to create/open {}: {}", properties_path.display(), e),
-        ))
-    })?;
+    let mut document = read_properties_document(state)?;
+    let is_new = !properties_path.exists();
+    if is_new {
+        // Same header the server writes
+        document.push_comment("Minecraft server properties");
+        document.push_comment(&chrono::Local::now().to_rfc3339());
+    }
 
-    // Write header comments
-    writeln!(file, "# Minecraft server properties")?;
-    writeln!(file, "# Generated by Server Manager")?; // Your app name
-    writeln!(file, "# {}", chrono::Local::now().to_rfc3339())?; // Timestamp
+    let mut changed = is_new;
+    let stale_keys: Vec<String> = document
+        .entries()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| !properties.contains_key(key))
+        .collect();
+    for key in stale_keys {
+        debug!("Removing property: '{}'", key);
+        changed |= document.remove(&key);
+    }
 
-    // Sort keys for consistent output order
+    // Sort keys so newly appended properties have a stable order
     let mut sorted_keys: Vec<&String> = properties.keys().collect();
-    sorted_keys.sort_unstable(); // Faster sort if order stability isn't critical
-
+    sorted_keys.sort_unstable();
     for key in sorted_keys {
-        if let Some(value) = properties.get(key) {
-            writeln!(file, "{}={}", key, value)?;
+        if document.set(key, &properties[key]) {
+            debug!("Updated property: '{}' = '{}'", key, properties[key]);
+            changed = true;
         }
     }
 
-    file.flush()?; // Ensure data is written
+    if !changed {
+        info!("server.properties is already up to date.");
+        return Ok(());
+    }
+    document.save(&properties_path)?;
 
     info!(
         "Successfully wrote properties to {}",
@@ -143,26 +127,8 @@ pub fn create_default_properties_if_missing(state: &Arc<AppState>) -> Result<()>
         },
         Ok(false) => {
             info!("server.properties not found. Creating default file.");
-            // Define default properties
-            let default_properties = HashMap::from([
-                ("server-port".to_string(), "25565".to_string()),
-                ("gamemode".to_string(), "survival".to_string()),
-                ("difficulty".to_string(), "normal".to_string()),
-                ("motd".to_string(), "A Minecraft Server".to_string()), // Added motd
-                ("level-seed".to_string(), "".to_string()),
-                ("enable-command-block".to_string(), "false".to_string()),
-                ("max-players".to_string(), "20".to_string()),
-                ("spawn-protection".to_string(), "16".to_string()),
-                ("view-distance".to_string(), "10".to_string()),
-                ("simulation-distance".to_string(), "10".to_string()), // Added simulation-distance
-                ("spawn-npcs".to_string(), "true".to_string()),
-                ("spawn-animals".to_string(), "true".to_string()),
-                ("spawn-monsters".to_string(), "true".to_string()),
-                ("pvp".to_string(), "true".to_string()),
-                // Add other common defaults
-            ]);
             // Write the defaults to the file
-            write_properties_file(&default_properties, state)
+            write_properties_file(&get_default_properties_map(), state)
         }
         Err(e) => {
             error!("Failed to check existence of {}: {}", properties_path.display(), e);
@@ -193,8 +159,16 @@ pub fn read_config_fully(state: Arc<AppState>) -> Result<ServerConfig> {
 pub fn update_config_fully(config: ServerConfig, state: Arc<AppState>) -> Result<()> {
     info!("Updating full server configuration...");
 
+    let errors = property_catalog::validate_properties(&config.server_properties);
+    if !errors.is_empty() {
+        let details: Vec<String> = errors.iter().map(|e| format!("{}: {}", e.key, e.message)).collect();
+        return Err(AppError::ConfigError(format!("Invalid server properties: {}", details.join("; "))));
+    }
+
     // Write the server.properties part
+    let previous = read_properties_file(&state)?;
     write_properties_file(&config.server_properties, &state)?;
+    apply_live_properties(&previous, &config.server_properties, &state);
 
     // Update the Java args in AppState
     // This assumes Java args are *only* managed via this config update mechanism.
@@ -208,6 +182,24 @@ pub fn update_config_fully(config: ServerConfig, state: Arc<AppState>) -> Result
     Ok(())
 }
 
+/// Sends console commands for changed keys that a running server can apply without a restart.
+/// Failures are only logged; the new value is in the file and applies on the next start anyway.
+fn apply_live_properties(previous: &HashMap<String, String>, current: &HashMap<String, String>, state: &Arc<AppState>) {
+    if !matches!(state.get_status(), Ok(ServerStatus::Running)) {
+        return;
+    }
+    for (key, value) in current {
+        if previous.get(key) == Some(value) {
+            continue;
+        }
+        if let Some(command) = property_catalog::live_command(key, value) {
+            info!("Applying '{}' to the running server: {}", key, command);
+            if let Err(e) = process_manager::send_command_to_server(state.clone(), command) {
+                warn!("Failed to apply '{}' live, it will take effect after a restart: {}", key, e);
+            }
+        }
+    }
+}
 
 // --- Deprecated? ---
 // These functions might be less useful now with read_config_fully / update_config_fully
@@ -235,5 +227,5 @@ pub fn update_properties(
     }
 
     // Write the merged properties back to the file
-    write_properties_file(¤t_properties, &state)
+    write_properties_file(&current_properties, &state)
 }
\ No newline at end of file
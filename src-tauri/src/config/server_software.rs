@@ -0,0 +1,1153 @@
+// src/config/server_software.rs
+
+use crate::api::events::emit_progress;
+use crate::app_state::AppState;
+use crate::commands::process_manager;
+use crate::config::manager_config::ManagerConfigStore;
+use crate::error::{AppError, Result};
+use crate::models::server_software::{Checksum, InstalledSoftware, ServerFlavor, SoftwareBuild, SoftwareVersion};
+use crate::models::server_status::ServerStatus;
+use crate::utils::download::{self, get_json};
+use crate::utils::{fs_utils, java_detector};
+use chrono::{DateTime, TimeZone, Utc};
+use lazy_static::lazy_static;
+use log::{debug, error, info, warn};
+use regex::Regex;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, RwLock};
+
+/// Progress task name for server software downloads and installs.
+const PROGRESS_TASK: &str = "ServerSoftware";
+
+lazy_static! {
+    static ref MAVEN_VERSION_REGEX: Regex = Regex::new(r"<version>([^<]+)</version>").unwrap();
+    // "java @user_jvm_args.txt @libraries/net/minecraftforge/forge/1.20.1-47.2.0/unix_args.txt "$@""
+    static ref ARGS_FILE_REGEX: Regex = Regex::new(r#"@(libraries[/\\][^\s"]+_args\.txt)"#).unwrap();
+    // Weekly snapshots: "24w14a"
+    static ref SNAPSHOT_ID_REGEX: Regex = Regex::new(r"^\d{2}w\d{2}[a-z]$").unwrap();
+}
+
+/// Base URLs of the software providers (`software-sources.json` in the app data directory).
+/// Point them at a local mirror to install without internet access to the upstream APIs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoftwareSources {
+    /// Mojang's `version_manifest_v2.json`.
+    pub vanilla_manifest_url: String,
+    /// PaperMC API v2 root (serves Paper and its forks).
+    pub paper_api_url: String,
+    /// Purpur API v2 root.
+    pub purpur_api_url: String,
+    /// Fabric meta root.
+    pub fabric_meta_url: String,
+    /// Fabric maven root (`net/fabricmc/fabric-installer` lives below it).
+    #[serde(default = "default_fabric_maven_url")]
+    pub fabric_maven_url: String,
+    /// Quilt meta root.
+    pub quilt_meta_url: String,
+    /// Quilt maven root (`org/quiltmc/quilt-installer` lives below it).
+    #[serde(default = "default_quilt_maven_url")]
+    pub quilt_maven_url: String,
+    /// Forge maven root (`net/minecraftforge/forge` lives below it).
+    #[serde(default = "default_forge_maven_url")]
+    pub forge_maven_url: String,
+    /// NeoForge maven root (`net/neoforged/neoforge` lives below it).
+    #[serde(default = "default_neoforge_maven_url")]
+    pub neoforge_maven_url: String,
+}
+
+fn default_fabric_maven_url() -> String {
+    "https://maven.fabricmc.net".to_string()
+}
+
+fn default_quilt_maven_url() -> String {
+    "https://maven.quiltmc.org/repository/release".to_string()
+}
+
+fn default_forge_maven_url() -> String {
+    "https://maven.minecraftforge.net".to_string()
+}
+
+fn default_neoforge_maven_url() -> String {
+    "https://maven.neoforged.net/releases".to_string()
+}
+
+impl Default for SoftwareSources {
+    fn default() -> Self {
+        Self {
+            vanilla_manifest_url: "https://piston-meta.mojang.com/mc/game/version_manifest_v2.json".to_string(),
+            paper_api_url: "https://api.papermc.io/v2".to_string(),
+            purpur_api_url: "https://api.purpurmc.org/v2".to_string(),
+            fabric_meta_url: "https://meta.fabricmc.net".to_string(),
+            fabric_maven_url: default_fabric_maven_url(),
+            quilt_meta_url: "https://meta.quiltmc.org".to_string(),
+            quilt_maven_url: default_quilt_maven_url(),
+            forge_maven_url: default_forge_maven_url(),
+            neoforge_maven_url: default_neoforge_maven_url(),
+        }
+    }
+}
+
+impl SoftwareSources {
+    fn validate(&self) -> Result<()> {
+        for url in [
+            &self.vanilla_manifest_url,
+            &self.paper_api_url,
+            &self.purpur_api_url,
+            &self.fabric_meta_url,
+            &self.fabric_maven_url,
+            &self.quilt_meta_url,
+            &self.quilt_maven_url,
+            &self.forge_maven_url,
+            &self.neoforge_maven_url,
+        ] {
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return Err(AppError::ConfigError(format!("Invalid source URL '{}': must be http(s)", url)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lists and installs one kind of server software.
+///
+/// Versions and builds are returned newest first. `install` places the server jar in the
+/// server directory and returns its file name; the default downloads `build.download_url`.
+pub trait ServerSoftwareProvider: Send + Sync {
+    fn flavor(&self) -> ServerFlavor;
+
+    fn list_versions(&self) -> Result<Vec<SoftwareVersion>>;
+
+    fn list_builds(&self, minecraft_version: &str) -> Result<Vec<SoftwareBuild>>;
+
+    fn install(&self, _minecraft_version: &str, build: &SoftwareBuild, state: &AppState) -> Result<ProviderInstall> {
+        let destination = state.server_directory.join(&build.file_name);
+        download::download_verified(
+            self.client(),
+            &build.download_url,
+            build.checksum.as_ref(),
+            &destination,
+            PROGRESS_TASK,
+        )?;
+        Ok(ProviderInstall { server_jar: build.file_name.clone(), verified: build.checksum.is_some() })
+    }
+
+    fn client(&self) -> &Client;
+}
+
+/// What a provider installed.
+pub struct ProviderInstall {
+    /// Jar (or `@` argument file) the server is now started with.
+    pub server_jar: String,
+    /// Whether the downloaded jar or installer was checked against a published checksum.
+    pub verified: bool,
+}
+
+/// Snapshots, pre-releases and release candidates, for providers that only list version ids.
+fn is_snapshot_id(version: &str) -> bool {
+    version.contains("-pre")
+        || version.contains("-rc")
+        || version.contains("snapshot")
+        || SNAPSHOT_ID_REGEX.is_match(version)
+}
+
+/// Orders build and loader versions by their components, numerically where both are numbers:
+/// "1.20.1-47.2.0" > "1.9.4-14.23.5", "47.2.10" > "47.2.9". Text parts compare as text, and a
+/// version sorts above the same version with a suffix ("47.2.0" > "47.2.0-beta").
+pub(crate) fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |v: &str| -> Vec<String> {
+        v.split(['.', '-', '+', '_']).filter(|part| !part.is_empty()).map(str::to_string).collect()
+    };
+    let (a, b) = (split(a), split(b));
+    for i in 0..a.len().max(b.len()) {
+        let ordering = match (a.get(i), b.get(i)) {
+            (Some(x), Some(y)) => match (x.parse::<u64>(), y.parse::<u64>()) {
+                (Ok(x), Ok(y)) => x.cmp(&y),
+                (Ok(_), Err(_)) => Ordering::Greater,
+                (Err(_), Ok(_)) => Ordering::Less,
+                (Err(_), Err(_)) => x.cmp(y),
+            },
+            // A further number is a later version, a further text part a pre-release
+            (Some(x), None) => if x.parse::<u64>().is_ok() { Ordering::Greater } else { Ordering::Less },
+            (None, Some(y)) => if y.parse::<u64>().is_ok() { Ordering::Less } else { Ordering::Greater },
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+fn trim_url(url: &str) -> String {
+    url.trim_end_matches('/').to_string()
+}
+
+/// Maven publishes a .sha1 next to every artifact; None if it's missing, so the artifact
+/// is installed unverified and the install result carries a warning.
+fn maven_sha1(client: &Client, artifact_url: &str) -> Option<Checksum> {
+    download::get_text(client, &format!("{}.sha1", artifact_url))
+        .ok()
+        .and_then(|text| text.split_whitespace().next().map(str::to_string))
+        .map(Checksum::Sha1)
+}
+
+/// Versions listed in a `maven-metadata.xml`, newest first.
+fn maven_versions(client: &Client, metadata_url: &str) -> Result<Vec<String>> {
+    let metadata = download::get_text(client, metadata_url)?;
+    let mut versions: Vec<String> =
+        MAVEN_VERSION_REGEX.captures_iter(&metadata).map(|caps| caps[1].to_string()).collect();
+    // Maven lists versions in deployment order, which interleaves Minecraft versions when
+    // older branches get backports
+    versions.sort_by(|a, b| compare_versions(b, a));
+    versions.dedup();
+    Ok(versions)
+}
+
+// --- Vanilla ---
+
+#[derive(Deserialize)]
+struct VersionManifest {
+    versions: Vec<ManifestVersion>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ManifestVersion {
+    id: String,
+    #[serde(rename = "type")]
+    version_type: String,
+    url: String,
+    release_time: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct VersionDetails {
+    downloads: VersionDownloads,
+}
+
+#[derive(Deserialize)]
+struct VersionDownloads {
+    server: Option<ManifestDownload>,
+}
+
+#[derive(Deserialize)]
+struct ManifestDownload {
+    sha1: String,
+    url: String,
+}
+
+/// Mojang's official server from the launcher version manifest. Each version has exactly
+/// one build, identified by the version id.
+pub struct VanillaProvider {
+    client: Client,
+    manifest_url: String,
+}
+
+impl VanillaProvider {
+    fn manifest(&self) -> Result<VersionManifest> {
+        get_json(&self.client, &self.manifest_url)
+    }
+}
+
+impl ServerSoftwareProvider for VanillaProvider {
+    fn flavor(&self) -> ServerFlavor {
+        ServerFlavor::Vanilla
+    }
+
+    fn list_versions(&self) -> Result<Vec<SoftwareVersion>> {
+        Ok(self
+            .manifest()?
+            .versions
+            .into_iter()
+            // old_alpha/old_beta have no (working) server downloads
+            .filter(|v| v.version_type == "release" || v.version_type == "snapshot")
+            .map(|v| SoftwareVersion {
+                snapshot: v.version_type == "snapshot",
+                id: v.id,
+                release_time: v.release_time,
+            })
+            .collect())
+    }
+
+    fn list_builds(&self, minecraft_version: &str) -> Result<Vec<SoftwareBuild>> {
+        let version = self
+            .manifest()?
+            .versions
+            .into_iter()
+            .find(|v| v.id == minecraft_version)
+            .ok_or_else(|| AppError::DownloadError(format!("Unknown Minecraft version '{}'", minecraft_version)))?;
+        let details: VersionDetails = get_json(&self.client, &version.url)?;
+        let server = details.downloads.server.ok_or_else(|| {
+            AppError::DownloadError(format!("Minecraft {} has no server download", minecraft_version))
+        })?;
+        Ok(vec![SoftwareBuild {
+            id: version.id.clone(),
+            experimental: version.version_type != "release",
+            file_name: format!("minecraft_server.{}.jar", version.id),
+            download_url: server.url,
+            checksum: Some(Checksum::Sha1(server.sha1)),
+            time: version.release_time,
+            changelog: Vec::new(),
+        }])
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+// --- Paper ---
+
+#[derive(Deserialize)]
+struct PaperProject {
+    versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct PaperBuilds {
+    builds: Vec<PaperBuild>,
+}
+
+#[derive(Deserialize)]
+struct PaperBuild {
+    build: u32,
+    time: Option<DateTime<Utc>>,
+    channel: String,
+    downloads: PaperDownloads,
+    #[serde(default)]
+    changes: Vec<PaperChange>,
+}
+
+#[derive(Deserialize)]
+struct PaperDownloads {
+    application: PaperDownload,
+}
+
+#[derive(Deserialize)]
+struct PaperDownload {
+    name: String,
+    sha256: String,
+}
+
+#[derive(Deserialize)]
+struct PaperChange {
+    summary: String,
+}
+
+/// Paper through the PaperMC downloads API.
+pub struct PaperProvider {
+    client: Client,
+    api_url: String,
+}
+
+impl ServerSoftwareProvider for PaperProvider {
+    fn flavor(&self) -> ServerFlavor {
+        ServerFlavor::Paper
+    }
+
+    fn list_versions(&self) -> Result<Vec<SoftwareVersion>> {
+        let project: PaperProject = get_json(&self.client, &format!("{}/projects/paper", self.api_url))?;
+        Ok(project
+            .versions
+            .into_iter()
+            .rev()
+            .map(|id| SoftwareVersion { snapshot: is_snapshot_id(&id), id, release_time: None })
+            .collect())
+    }
+
+    fn list_builds(&self, minecraft_version: &str) -> Result<Vec<SoftwareBuild>> {
+        let url = format!("{}/projects/paper/versions/{}/builds", self.api_url, minecraft_version);
+        let builds: PaperBuilds = get_json(&self.client, &url)?;
+        Ok(builds
+            .builds
+            .into_iter()
+            .rev()
+            .map(|build| SoftwareBuild {
+                id: build.build.to_string(),
+                experimental: build.channel != "default",
+                download_url: format!(
+                    "{}/projects/paper/versions/{}/builds/{}/downloads/{}",
+                    self.api_url, minecraft_version, build.build, build.downloads.application.name
+                ),
+                file_name: build.downloads.application.name,
+                checksum: Some(Checksum::Sha256(build.downloads.application.sha256)),
+                time: build.time,
+                changelog: build.changes.into_iter().map(|change| change.summary).collect(),
+            })
+            .collect())
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+// --- Purpur ---
+
+#[derive(Deserialize)]
+struct PurpurProject {
+    versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct PurpurVersion {
+    builds: PurpurBuildList,
+}
+
+#[derive(Deserialize)]
+struct PurpurBuildList {
+    all: Vec<PurpurBuild>,
+}
+
+#[derive(Deserialize)]
+struct PurpurBuild {
+    build: String,
+    result: String,
+    /// Milliseconds since the epoch.
+    timestamp: Option<i64>,
+    md5: Option<String>,
+    #[serde(default)]
+    commits: Vec<PurpurCommit>,
+}
+
+#[derive(Deserialize)]
+struct PurpurCommit {
+    description: String,
+}
+
+/// Purpur through its downloads API. Failed CI builds are skipped.
+pub struct PurpurProvider {
+    client: Client,
+    api_url: String,
+}
+
+impl ServerSoftwareProvider for PurpurProvider {
+    fn flavor(&self) -> ServerFlavor {
+        ServerFlavor::Purpur
+    }
+
+    fn list_versions(&self) -> Result<Vec<SoftwareVersion>> {
+        let project: PurpurProject = get_json(&self.client, &format!("{}/purpur", self.api_url))?;
+        Ok(project
+            .versions
+            .into_iter()
+            .rev()
+            .map(|id| SoftwareVersion { snapshot: is_snapshot_id(&id), id, release_time: None })
+            .collect())
+    }
+
+    fn list_builds(&self, minecraft_version: &str) -> Result<Vec<SoftwareBuild>> {
+        let url = format!("{}/purpur/{}?detailed=true", self.api_url, minecraft_version);
+        let version: PurpurVersion = get_json(&self.client, &url)?;
+        Ok(version
+            .builds
+            .all
+            .into_iter()
+            .rev()
+            .filter(|build| build.result == "SUCCESS")
+            .map(|build| SoftwareBuild {
+                download_url: format!("{}/purpur/{}/{}/download", self.api_url, minecraft_version, build.build),
+                file_name: format!("purpur-{}-{}.jar", minecraft_version, build.build),
+                id: build.build,
+                experimental: false,
+                checksum: build.md5.map(Checksum::Md5),
+                time: build.timestamp.and_then(|ms| Utc.timestamp_millis_opt(ms).single()),
+                changelog: build.commits.into_iter().map(|commit| commit.description).collect(),
+            })
+            .collect())
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+// --- Fabric and Quilt ---
+
+#[derive(Deserialize)]
+struct MetaGameVersion {
+    version: String,
+    stable: bool,
+}
+
+#[derive(Deserialize)]
+struct MetaLoaderEntry {
+    loader: MetaLoader,
+}
+
+#[derive(Deserialize)]
+struct MetaLoader {
+    version: String,
+    /// Fabric only; Quilt marks unstable loaders with a pre-release suffix instead.
+    #[serde(default)]
+    stable: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct MetaInstaller {
+    version: String,
+    #[serde(default)]
+    stable: Option<bool>,
+}
+
+/// Vanilla jar the Fabric and Quilt launchers load (their default `serverJar`).
+const VANILLA_SERVER_JAR: &str = "server.jar";
+
+/// Downloads the vanilla server jar the Fabric/Quilt launcher wraps from the configured
+/// manifest, instead of letting the installer fetch it from Mojang.
+fn install_vanilla_server_jar(vanilla: &VanillaProvider, minecraft_version: &str, state: &AppState) -> Result<()> {
+    let build = vanilla
+        .list_builds(minecraft_version)?
+        .into_iter()
+        .next()
+        .ok_or_else(|| AppError::DownloadError(format!("Minecraft {} has no server download", minecraft_version)))?;
+    download::download_verified(
+        vanilla.client(),
+        &build.download_url,
+        build.checksum.as_ref(),
+        &state.server_directory.join(VANILLA_SERVER_JAR),
+        PROGRESS_TASK,
+    )
+}
+
+fn meta_game_versions(client: &Client, url: &str) -> Result<Vec<SoftwareVersion>> {
+    let versions: Vec<MetaGameVersion> = get_json(client, url)?;
+    Ok(versions
+        .into_iter()
+        .map(|v| SoftwareVersion { id: v.version, snapshot: !v.stable, release_time: None })
+        .collect())
+}
+
+/// Fabric through Fabric meta. The launcher jar meta generates comes without a checksum, so
+/// the build's download is the Fabric installer from the Fabric maven (verified against its
+/// `.sha1`), which is run with `server ...` next to the vanilla jar from the configured
+/// manifest. Builds are loader versions.
+pub struct FabricProvider {
+    client: Client,
+    meta_url: String,
+    maven_url: String,
+    vanilla: VanillaProvider,
+}
+
+/// Launcher jar written by the Fabric installer.
+const FABRIC_SERVER_JAR: &str = "fabric-server-launch.jar";
+
+impl ServerSoftwareProvider for FabricProvider {
+    fn flavor(&self) -> ServerFlavor {
+        ServerFlavor::Fabric
+    }
+
+    fn list_versions(&self) -> Result<Vec<SoftwareVersion>> {
+        meta_game_versions(&self.client, &format!("{}/v2/versions/game", self.meta_url))
+    }
+
+    fn list_builds(&self, minecraft_version: &str) -> Result<Vec<SoftwareBuild>> {
+        let installers: Vec<MetaInstaller> =
+            get_json(&self.client, &format!("{}/v2/versions/installer", self.meta_url))?;
+        let installer = installers
+            .iter()
+            .find(|i| i.stable.unwrap_or(true))
+            .or(installers.first())
+            .ok_or_else(|| AppError::DownloadError("Fabric meta lists no installer versions".to_string()))?;
+        let installer_url = format!(
+            "{0}/net/fabricmc/fabric-installer/{1}/fabric-installer-{1}.jar",
+            self.maven_url, installer.version
+        );
+        let checksum = maven_sha1(&self.client, &installer_url);
+
+        let loaders: Vec<MetaLoaderEntry> =
+            get_json(&self.client, &format!("{}/v2/versions/loader/{}", self.meta_url, minecraft_version))?;
+        Ok(loaders
+            .into_iter()
+            .map(|entry| SoftwareBuild {
+                experimental: !entry.loader.stable.unwrap_or(true),
+                file_name: format!("fabric-installer-{}.jar", installer.version),
+                download_url: installer_url.clone(),
+                id: entry.loader.version,
+                checksum: checksum.clone(),
+                time: None,
+                changelog: Vec::new(),
+            })
+            .collect())
+    }
+
+    fn install(&self, minecraft_version: &str, build: &SoftwareBuild, state: &AppState) -> Result<ProviderInstall> {
+        let installer_path = state.server_directory.join(".temp_download").join(&build.file_name);
+        download::download_verified(
+            &self.client,
+            &build.download_url,
+            build.checksum.as_ref(),
+            &installer_path,
+            PROGRESS_TASK,
+        )?;
+
+        let result = install_vanilla_server_jar(&self.vanilla, minecraft_version, state).and_then(|_| {
+            emit_progress(PROGRESS_TASK, -1.0, "Running Fabric installer...");
+            let java_path = process_manager::select_java(state, java_detector::required_java_major(minecraft_version));
+            let install_dir = state.server_directory.display().to_string();
+            run_installer(
+                &java_path,
+                &installer_path,
+                &["server", "-dir", &install_dir, "-mcversion", minecraft_version, "-loader", &build.id],
+                &state.server_directory,
+                PROGRESS_TASK,
+            )
+        });
+        if let Err(e) = fs_utils::remove_file(&installer_path) {
+            debug!("Could not remove Fabric installer: {}", e);
+        }
+        result?;
+
+        if !state.server_directory.join(FABRIC_SERVER_JAR).exists() {
+            return Err(AppError::DownloadError(format!(
+                "Fabric installer finished but {} was not created",
+                FABRIC_SERVER_JAR
+            )));
+        }
+        Ok(ProviderInstall { server_jar: FABRIC_SERVER_JAR.to_string(), verified: build.checksum.is_some() })
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+/// Quilt through Quilt meta. Quilt has no prebuilt server jar, so the build's download is
+/// the Quilt installer from the Quilt maven, which is run with `install server ...` next to
+/// the vanilla jar from the configured manifest.
+pub struct QuiltProvider {
+    client: Client,
+    meta_url: String,
+    maven_url: String,
+    vanilla: VanillaProvider,
+}
+
+/// Launcher jar written by the Quilt installer.
+const QUILT_SERVER_JAR: &str = "quilt-server-launch.jar";
+
+impl ServerSoftwareProvider for QuiltProvider {
+    fn flavor(&self) -> ServerFlavor {
+        ServerFlavor::Quilt
+    }
+
+    fn list_versions(&self) -> Result<Vec<SoftwareVersion>> {
+        meta_game_versions(&self.client, &format!("{}/v3/versions/game", self.meta_url))
+    }
+
+    fn list_builds(&self, minecraft_version: &str) -> Result<Vec<SoftwareBuild>> {
+        let installers: Vec<MetaInstaller> =
+            get_json(&self.client, &format!("{}/v3/versions/installer", self.meta_url))?;
+        let installer = installers
+            .first()
+            .ok_or_else(|| AppError::DownloadError("Quilt meta lists no installer versions".to_string()))?;
+        let installer_url = format!(
+            "{0}/org/quiltmc/quilt-installer/{1}/quilt-installer-{1}.jar",
+            self.maven_url, installer.version
+        );
+        let checksum = maven_sha1(&self.client, &installer_url);
+
+        let loaders: Vec<MetaLoaderEntry> =
+            get_json(&self.client, &format!("{}/v3/versions/loader/{}", self.meta_url, minecraft_version))?;
+        Ok(loaders
+            .into_iter()
+            .map(|entry| SoftwareBuild {
+                experimental: entry.loader.version.contains('-'),
+                file_name: format!("quilt-installer-{}.jar", installer.version),
+                download_url: installer_url.clone(),
+                id: entry.loader.version,
+                checksum: checksum.clone(),
+                time: None,
+                changelog: Vec::new(),
+            })
+            .collect())
+    }
+
+    fn install(&self, minecraft_version: &str, build: &SoftwareBuild, state: &AppState) -> Result<ProviderInstall> {
+        let temp_dir = state.server_directory.join(".temp_download");
+        let installer_path = temp_dir.join(&build.file_name);
+        download::download_verified(
+            &self.client,
+            &build.download_url,
+            build.checksum.as_ref(),
+            &installer_path,
+            PROGRESS_TASK,
+        )?;
+
+        let result = install_vanilla_server_jar(&self.vanilla, minecraft_version, state).and_then(|_| {
+            emit_progress(PROGRESS_TASK, -1.0, "Running Quilt installer...");
+            let java_path = process_manager::select_java(state, java_detector::required_java_major(minecraft_version));
+            let install_dir = format!("--install-dir={}", state.server_directory.display());
+            run_installer(
+                &java_path,
+                &installer_path,
+                &["install", "server", minecraft_version, &build.id, &install_dir],
+                &state.server_directory,
+                PROGRESS_TASK,
+            )
+        });
+        if let Err(e) = fs_utils::remove_file(&installer_path) {
+            debug!("Could not remove Quilt installer: {}", e);
+        }
+        result?;
+
+        if !state.server_directory.join(QUILT_SERVER_JAR).exists() {
+            return Err(AppError::DownloadError(format!(
+                "Quilt installer finished but {} was not created",
+                QUILT_SERVER_JAR
+            )));
+        }
+        Ok(ProviderInstall { server_jar: QUILT_SERVER_JAR.to_string(), verified: build.checksum.is_some() })
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+// --- Forge / NeoForge ---
+
+/// Forge through its maven. A build's download is the Forge installer, which is run with
+/// `--installServer`. Builds are Forge versions (`47.2.0` for `1.20.1-47.2.0`).
+pub struct ForgeProvider {
+    client: Client,
+    maven_url: String,
+}
+
+impl ForgeProvider {
+    fn artifact_versions(&self) -> Result<Vec<String>> {
+        maven_versions(
+            &self.client,
+            &format!("{}/net/minecraftforge/forge/maven-metadata.xml", self.maven_url),
+        )
+    }
+}
+
+impl ServerSoftwareProvider for ForgeProvider {
+    fn flavor(&self) -> ServerFlavor {
+        ServerFlavor::Forge
+    }
+
+    fn list_versions(&self) -> Result<Vec<SoftwareVersion>> {
+        let mut versions: Vec<SoftwareVersion> = Vec::new();
+        for artifact in self.artifact_versions()? {
+            let Some((minecraft, _)) = artifact.split_once('-') else { continue };
+            if !versions.iter().any(|v| v.id == minecraft) {
+                versions.push(SoftwareVersion {
+                    id: minecraft.to_string(),
+                    snapshot: is_snapshot_id(minecraft),
+                    release_time: None,
+                });
+            }
+        }
+        Ok(versions)
+    }
+
+    fn list_builds(&self, minecraft_version: &str) -> Result<Vec<SoftwareBuild>> {
+        let prefix = format!("{}-", minecraft_version);
+        Ok(self
+            .artifact_versions()?
+            .into_iter()
+            .filter_map(|artifact| {
+                let id = artifact.strip_prefix(&prefix)?.to_string();
+                Some(SoftwareBuild {
+                    experimental: id.contains("-beta"),
+                    file_name: format!("forge-{}-installer.jar", artifact),
+                    download_url: format!(
+                        "{0}/net/minecraftforge/forge/{1}/forge-{1}-installer.jar",
+                        self.maven_url, artifact
+                    ),
+                    id,
+                    // Looked up from the maven .sha1 at install time, one request per build is too many
+                    checksum: None,
+                    time: None,
+                    changelog: Vec::new(),
+                })
+            })
+            .collect())
+    }
+
+    fn install(&self, minecraft_version: &str, build: &SoftwareBuild, state: &AppState) -> Result<ProviderInstall> {
+        install_with_forge_installer(&self.client, minecraft_version, build, state)
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+/// NeoForge through its maven. Builds are NeoForge versions (`21.1.57`, which encodes
+/// Minecraft 1.21.1); 1.20.1 is served by the older `net/neoforged/forge` artifact.
+pub struct NeoForgeProvider {
+    client: Client,
+    maven_url: String,
+}
+
+/// The only Minecraft version NeoForge published under the Forge artifact.
+const NEOFORGE_LEGACY_VERSION: &str = "1.20.1";
+
+/// Minecraft version a NeoForge version belongs to: `21.1.57` is 1.21.1, `21.0.167` is 1.21.
+/// None for the snapshot builds (`0.25w14craftmine.3-beta`).
+fn neoforge_minecraft_version(version: &str) -> Option<String> {
+    let mut parts = version.split('.');
+    let minor = parts.next().filter(|minor| *minor != "0")?;
+    Some(match parts.next()? {
+        "0" => format!("1.{}", minor),
+        patch => format!("1.{}.{}", minor, patch),
+    })
+}
+
+impl NeoForgeProvider {
+    fn artifact_versions(&self, artifact: &str) -> Result<Vec<String>> {
+        maven_versions(
+            &self.client,
+            &format!("{}/net/neoforged/{}/maven-metadata.xml", self.maven_url, artifact),
+        )
+    }
+}
+
+impl ServerSoftwareProvider for NeoForgeProvider {
+    fn flavor(&self) -> ServerFlavor {
+        ServerFlavor::NeoForge
+    }
+
+    fn list_versions(&self) -> Result<Vec<SoftwareVersion>> {
+        let mut versions: Vec<SoftwareVersion> = Vec::new();
+        for minecraft in self
+            .artifact_versions("neoforge")?
+            .iter()
+            .filter_map(|v| neoforge_minecraft_version(v))
+            .chain(std::iter::once(NEOFORGE_LEGACY_VERSION.to_string()))
+        {
+            if !versions.iter().any(|v| v.id == minecraft) {
+                versions.push(SoftwareVersion { id: minecraft, snapshot: false, release_time: None });
+            }
+        }
+        Ok(versions)
+    }
+
+    fn list_builds(&self, minecraft_version: &str) -> Result<Vec<SoftwareBuild>> {
+        let (artifact, builds): (&str, Vec<(String, String)>) = if minecraft_version == NEOFORGE_LEGACY_VERSION {
+            let prefix = format!("{}-", NEOFORGE_LEGACY_VERSION);
+            let builds = self
+                .artifact_versions("forge")?
+                .into_iter()
+                .filter_map(|v| Some((v.strip_prefix(&prefix)?.to_string(), v)))
+                .collect();
+            ("forge", builds)
+        } else {
+            let builds = self
+                .artifact_versions("neoforge")?
+                .into_iter()
+                .filter(|v| neoforge_minecraft_version(v).as_deref() == Some(minecraft_version))
+                .map(|v| (v.clone(), v))
+                .collect();
+            ("neoforge", builds)
+        };
+        Ok(builds
+            .into_iter()
+            .map(|(id, coordinate)| SoftwareBuild {
+                experimental: id.contains("-beta") || id.contains("-alpha"),
+                file_name: format!("{}-{}-installer.jar", artifact, coordinate),
+                download_url: format!(
+                    "{0}/net/neoforged/{1}/{2}/{1}-{2}-installer.jar",
+                    self.maven_url, artifact, coordinate
+                ),
+                id,
+                checksum: None,
+                time: None,
+                changelog: Vec::new(),
+            })
+            .collect())
+    }
+
+    fn install(&self, minecraft_version: &str, build: &SoftwareBuild, state: &AppState) -> Result<ProviderInstall> {
+        install_with_forge_installer(&self.client, minecraft_version, build, state)
+    }
+
+    fn client(&self) -> &Client {
+        &self.client
+    }
+}
+
+/// Downloads and runs a Forge/NeoForge installer with a Java suitable for the Minecraft
+/// version, then returns what the server is launched with: the `@libraries/.../unix_args.txt`
+/// argument file from the generated `run.sh`/`run.bat` (1.17+), or the server jar.
+fn install_with_forge_installer(
+    client: &Client,
+    minecraft_version: &str,
+    build: &SoftwareBuild,
+    state: &AppState,
+) -> Result<ProviderInstall> {
+    let installer_path = state.server_directory.join(".temp_download").join(&build.file_name);
+    let checksum = build.checksum.clone().or_else(|| maven_sha1(client, &build.download_url));
+    download::download_verified(client, &build.download_url, checksum.as_ref(), &installer_path, PROGRESS_TASK)?;
+
+    let java_path = process_manager::select_java(state, java_detector::required_java_major(minecraft_version));
+    emit_progress(PROGRESS_TASK, -1.0, &format!("Running {}...", build.file_name));
+    let install_dir = state.server_directory.display().to_string();
+    let result = run_installer(
+        &java_path,
+        &installer_path,
+        &["--installServer", &install_dir],
+        &state.server_directory,
+        PROGRESS_TASK,
+    );
+    if let Err(e) = fs_utils::remove_file(&installer_path) {
+        debug!("Could not remove installer: {}", e);
+    }
+    result?;
+
+    let server_jar = forge_launch_target(&state.server_directory, &build.file_name)?;
+    Ok(ProviderInstall { server_jar, verified: checksum.is_some() })
+}
+
+/// Finds how to start a freshly installed Forge/NeoForge server. `installer_name` is
+/// `<artifact>-<coordinate>-installer.jar`.
+fn forge_launch_target(server_dir: &Path, installer_name: &str) -> Result<String> {
+    let script = if cfg!(windows) { "run.bat" } else { "run.sh" };
+    if let Ok(content) = std::fs::read_to_string(server_dir.join(script)) {
+        if let Some(caps) = ARGS_FILE_REGEX.captures(&content) {
+            let args_file = caps[1].replace('\\', "/");
+            if server_dir.join(&args_file).is_file() {
+                info!("Launching through {} (from {})", args_file, script);
+                return Ok(format!("@{}", args_file));
+            }
+        }
+    }
+
+    // Before 1.17 the installer leaves a runnable jar; 1.20.4+ additionally writes a shim jar
+    let base = installer_name.trim_end_matches("-installer.jar");
+    ["-shim.jar", ".jar", "-universal.jar"]
+        .iter()
+        .map(|suffix| format!("{}{}", base, suffix))
+        .find(|jar| server_dir.join(jar).is_file())
+        .ok_or_else(|| {
+            AppError::DownloadError(format!(
+                "Installer finished but neither {} nor a {} server jar was found",
+                script, base
+            ))
+        })
+}
+
+/// Runs `java -jar <installer> <args>` and forwards its output as progress messages of `task`.
+pub(crate) fn run_installer(
+    java_path: &Path,
+    installer: &Path,
+    args: &[&str],
+    working_dir: &Path,
+    task: &str,
+) -> Result<()> {
+    info!("Running installer {} {:?}", installer.display(), args);
+    let mut child = Command::new(java_path)
+        .arg("-jar")
+        .arg(installer)
+        .args(args)
+        .current_dir(working_dir)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::ProcessError(format!("Failed to start installer: {}", e)))?;
+
+    // Drain stderr on its own thread so a chatty installer can't block on a full pipe
+    let stderr = child.stderr.take();
+    let stderr_thread = std::thread::spawn(move || {
+        let mut lines = Vec::new();
+        if let Some(stderr) = stderr {
+            lines.extend(BufReader::new(stderr).lines().map_while(|line| line.ok()));
+        }
+        lines
+    });
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines().map_while(|line| line.ok()) {
+            debug!("[installer] {}", line);
+            emit_progress(task, -1.0, &line);
+        }
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| AppError::ProcessError(format!("Failed to wait for installer: {}", e)))?;
+    let stderr_lines = stderr_thread.join().unwrap_or_default();
+    if !status.success() {
+        let tail = stderr_lines.iter().rev().take(5).rev().cloned().collect::<Vec<_>>().join("\n");
+        return Err(AppError::ProcessError(format!("Installer exited with {}: {}", status, tail)));
+    }
+    Ok(())
+}
+
+// --- Manager ---
+
+/// Lists and installs server software from the configured sources.
+pub struct ServerSoftwareManager {
+    state: Arc<AppState>,
+    config_store: Arc<ManagerConfigStore>,
+    sources: RwLock<SoftwareSources>,
+    storage_path: PathBuf,
+    client: Client,
+}
+
+impl ServerSoftwareManager {
+    pub fn new(state: Arc<AppState>, config_store: Arc<ManagerConfigStore>, storage_path: PathBuf) -> Self {
+        let sources = match fs_utils::read_file_to_string(&storage_path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                error!("Software sources {} are corrupt, using defaults: {}", storage_path.display(), e);
+                SoftwareSources::default()
+            }),
+            Err(_) => SoftwareSources::default(),
+        };
+        Self {
+            state,
+            config_store,
+            sources: RwLock::new(sources),
+            storage_path,
+            client: download::http_client(),
+        }
+    }
+
+    pub fn get_sources(&self) -> Result<SoftwareSources> {
+        self.sources
+            .read()
+            .map(|guard| guard.clone())
+            .map_err(|e| AppError::LockError(format!("Failed to lock software sources: {}", e)))
+    }
+
+    /// Validates and persists new base URLs.
+    pub fn set_sources(&self, sources: SoftwareSources) -> Result<()> {
+        sources.validate()?;
+        let mut guard = self
+            .sources
+            .write()
+            .map_err(|e| AppError::LockError(format!("Failed to lock software sources for writing: {}", e)))?;
+        let json = serde_json::to_string_pretty(&sources)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize software sources: {}", e)))?;
+        fs_utils::write_string_to_file_atomic(&self.storage_path, &json)?;
+        *guard = sources;
+        info!("Server software sources updated.");
+        Ok(())
+    }
+
+    /// Builds the provider for a flavor from the current sources.
+    pub fn provider(&self, flavor: ServerFlavor) -> Result<Box<dyn ServerSoftwareProvider>> {
+        let sources = self.get_sources()?;
+        let client = self.client.clone();
+        // Fabric and Quilt wrap the vanilla jar, which comes from the same manifest
+        let vanilla = VanillaProvider { client: client.clone(), manifest_url: sources.vanilla_manifest_url.clone() };
+        Ok(match flavor {
+            ServerFlavor::Vanilla => Box::new(vanilla),
+            ServerFlavor::Paper => Box::new(PaperProvider { client, api_url: trim_url(&sources.paper_api_url) }),
+            ServerFlavor::Purpur => Box::new(PurpurProvider { client, api_url: trim_url(&sources.purpur_api_url) }),
+            ServerFlavor::Fabric => Box::new(FabricProvider {
+                client,
+                meta_url: trim_url(&sources.fabric_meta_url),
+                maven_url: trim_url(&sources.fabric_maven_url),
+                vanilla,
+            }),
+            ServerFlavor::Quilt => Box::new(QuiltProvider {
+                client,
+                meta_url: trim_url(&sources.quilt_meta_url),
+                maven_url: trim_url(&sources.quilt_maven_url),
+                vanilla,
+            }),
+            ServerFlavor::Forge => Box::new(ForgeProvider { client, maven_url: trim_url(&sources.forge_maven_url) }),
+            ServerFlavor::NeoForge => {
+                Box::new(NeoForgeProvider { client, maven_url: trim_url(&sources.neoforge_maven_url) })
+            }
+            ServerFlavor::Spigot | ServerFlavor::Unknown => {
+                return Err(AppError::NotImplemented(format!("Downloading {} server software", flavor)))
+            }
+        })
+    }
+
+    /// Switches the jar the server starts with and persists the choice in the manager config.
+    pub fn set_server_jar(&self, jar: &str) -> Result<()> {
+        self.state.set_server_jar(jar.to_string())?;
+        info!("Server jar set to {}", jar);
+        self.config_store.update(|config| {
+            config.server_jar = jar.to_string();
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    pub fn list_versions(&self, flavor: ServerFlavor, include_snapshots: bool) -> Result<Vec<SoftwareVersion>> {
+        let mut versions = self.provider(flavor)?.list_versions()?;
+        if !include_snapshots {
+            versions.retain(|v| !v.snapshot);
+        }
+        Ok(versions)
+    }
+
+    pub fn list_builds(
+        &self,
+        flavor: ServerFlavor,
+        minecraft_version: &str,
+        include_experimental: bool,
+    ) -> Result<Vec<SoftwareBuild>> {
+        let mut builds = self.provider(flavor)?.list_builds(minecraft_version)?;
+        if !include_experimental {
+            builds.retain(|b| !b.experimental);
+        }
+        Ok(builds)
+    }
+
+    /// Installs a build (the newest stable one if `build` is None) and makes it the server jar.
+    /// The previous jar is left in the server directory. The server must be stopped.
+    pub fn install(
+        &self,
+        flavor: ServerFlavor,
+        minecraft_version: &str,
+        build: Option<&str>,
+    ) -> Result<InstalledSoftware> {
+        match self.state.get_status()? {
+            ServerStatus::Stopped | ServerStatus::Error(_) => {}
+            status => {
+                return Err(AppError::ServerError(format!(
+                    "Stop the server before installing server software (current state: {})",
+                    status
+                )))
+            }
+        }
+
+        let provider = self.provider(flavor)?;
+        emit_progress(PROGRESS_TASK, 0.0, &format!("Looking up {} {} builds...", flavor, minecraft_version));
+        let builds = provider.list_builds(minecraft_version)?;
+        let selected = match build {
+            Some(id) => builds.iter().find(|b| b.id == id).ok_or_else(|| {
+                AppError::DownloadError(format!("{} {} has no build '{}'", flavor, minecraft_version, id))
+            })?,
+            None => builds
+                .iter()
+                .find(|b| !b.experimental)
+                .or(builds.first())
+                .ok_or_else(|| AppError::DownloadError(format!("No {} builds for {}", flavor, minecraft_version)))?,
+        };
+
+        info!("Installing {} {} build {}", flavor, minecraft_version, selected.id);
+        let installed = provider.install(minecraft_version, selected, &self.state)?;
+        let jar = installed.server_jar;
+        self.set_server_jar(&jar)?;
+        emit_progress(PROGRESS_TASK, 100.0, &format!("Installed {} {} ({})", flavor, minecraft_version, jar));
+
+        let mut warnings = Vec::new();
+        if !installed.verified {
+            let warning = format!(
+                "{} {} build {} was installed without checksum verification: {} publishes no checksum for it",
+                flavor, minecraft_version, selected.id, selected.download_url
+            );
+            warn!("{}", warning);
+            warnings.push(warning);
+        }
+        Ok(InstalledSoftware {
+            flavor,
+            minecraft_version: minecraft_version.to_string(),
+            build: selected.id.clone(),
+            server_jar: jar,
+            warnings,
+        })
+    }
+}
@@ -0,0 +1,313 @@
+// src/config/software_detector.rs
+
+use crate::config::server_software;
+use crate::error::{AppError, Result};
+use crate::models::server_software::{DetectedSoftware, ServerFlavor};
+use crate::utils::java_detector;
+use lazy_static::lazy_static;
+use log::{debug, info};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+lazy_static! {
+    // "1.20.1", "1.21", "24w14a", "1.20.5-pre1" inside a file name
+    static ref MC_VERSION_REGEX: Regex = Regex::new(r"(1\.\d+(?:\.\d+)?(?:-(?:pre|rc)\d+)?|\d{2}w\d{2}[a-z])").unwrap();
+    // paper-1.21.1-120.jar, purpur-1.21.1-2329.jar
+    static ref BUILD_JAR_REGEX: Regex = Regex::new(r"^(paper|purpur)-(.+)-(\d+)\.jar$").unwrap();
+    // @libraries/net/minecraftforge/forge/1.20.1-47.2.0/unix_args.txt
+    static ref FORGE_ARGS_REGEX: Regex =
+        Regex::new(r"libraries[/\\]net[/\\](minecraftforge|neoforged)[/\\](forge|neoforge)[/\\]([^/\\]+)[/\\]").unwrap();
+    // forge-1.16.5-36.2.39.jar, forge-1.12.2-14.23.5.2859-universal.jar, forge-1.20.4-49.0.3-shim.jar
+    static ref FORGE_JAR_REGEX: Regex =
+        Regex::new(r"^forge-(1\.[\d.]+)-([\d.]+)(?:-universal|-shim)?\.jar$").unwrap();
+    // libraries/org/quiltmc/quilt-loader/0.26.0/ in the launcher's Class-Path
+    static ref QUILT_LOADER_REGEX: Regex = Regex::new(r"quilt-loader/([^/\s]+)/").unwrap();
+}
+
+/// The part of a vanilla `version.json` the detector needs.
+#[derive(Deserialize)]
+struct VersionJson {
+    id: String,
+    #[serde(default)]
+    java_version: Option<u32>,
+}
+
+/// Read access to the entries of a jar that matter for detection.
+struct JarContents {
+    archive: zip::ZipArchive<File>,
+}
+
+impl JarContents {
+    fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path)?;
+        let archive = zip::ZipArchive::new(file)
+            .map_err(|e| AppError::ConfigError(format!("{} is not a valid jar: {}", path.display(), e)))?;
+        Ok(Self { archive })
+    }
+
+    fn read_text(&mut self, name: &str) -> Option<String> {
+        let mut entry = self.archive.by_name(name).ok()?;
+        let mut content = String::new();
+        entry.read_to_string(&mut content).ok()?;
+        Some(content)
+    }
+
+    fn has(&mut self, name: &str) -> bool {
+        self.archive.by_name(name).is_ok()
+    }
+}
+
+/// Parses `key=value` (properties) or `Key: Value` (manifest) lines.
+fn parse_key_values(content: &str, separator: char) -> HashMap<String, String> {
+    content
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .filter_map(|line| line.split_once(separator))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect()
+}
+
+/// Joins manifest continuation lines (lines starting with a space) so long values such as
+/// `Class-Path` come back in one piece.
+fn unwrap_manifest(content: &str) -> String {
+    content.replace("\r\n", "\n").replace("\n ", "")
+}
+
+/// Inspects the server directory and its jar to find out which server software it runs.
+///
+/// Looks at, in order: a Forge `@…_args.txt` launch or Forge jar name, the jar's
+/// `version.json` (vanilla and bundler jars), Paperclip's `META-INF/versions.list` and
+/// `patch.properties`, Fabric's `install.properties`, the Quilt launcher manifest, then the
+/// Forge/NeoForge launch scripts and `libraries/` if the jar wasn't identified, and finally
+/// the jar's file name. A jar that can't be opened still yields a result from the other clues.
+pub fn detect(server_dir: &Path, server_jar: &str) -> Result<DetectedSoftware> {
+    let mut detected = DetectedSoftware {
+        flavor: ServerFlavor::Unknown,
+        minecraft_version: None,
+        loader_version: None,
+        required_java: None,
+        server_jar: server_jar.to_string(),
+        evidence: Vec::new(),
+    };
+
+    if detect_forge_launch(server_jar, &mut detected) {
+        finish(&mut detected);
+        return Ok(detected);
+    }
+
+    let jar_path = server_dir.join(server_jar);
+    if !jar_path.is_file() {
+        // `@…` launches and removed jars: the installer's leftovers are all there is
+        if detect_forge_files(server_dir, &mut detected) {
+            finish(&mut detected);
+            return Ok(detected);
+        }
+        return Err(AppError::ServerJarNotFound(jar_path));
+    }
+    match JarContents::open(&jar_path) {
+        Ok(mut jar) => inspect_jar(server_dir, server_jar, &mut jar, &mut detected),
+        Err(e) => debug!("{}", e),
+    }
+    // Scripts and libraries may be left over from an earlier Forge install, so they only
+    // count when the configured jar itself says nothing
+    if detected.flavor == ServerFlavor::Unknown && detect_forge_files(server_dir, &mut detected) {
+        finish(&mut detected);
+        return Ok(detected);
+    }
+
+    // File name as the last resort for the version
+    if detected.minecraft_version.is_none() {
+        if let Some(caps) = MC_VERSION_REGEX.captures(server_jar) {
+            detected.minecraft_version = Some(caps[1].to_string());
+            detected.evidence.push(format!("{} (file name)", server_jar));
+        }
+    }
+    finish(&mut detected);
+    Ok(detected)
+}
+
+fn finish(detected: &mut DetectedSoftware) {
+    if detected.required_java.is_none() {
+        detected.required_java = detected.minecraft_version.as_deref().and_then(java_detector::required_java_major);
+    }
+    info!(
+        "Detected {} {} (loader {}, Java {}+)",
+        detected.flavor,
+        detected.minecraft_version.as_deref().unwrap_or("unknown version"),
+        detected.loader_version.as_deref().unwrap_or("-"),
+        detected.required_java.map_or("?".to_string(), |v| v.to_string())
+    );
+}
+
+fn inspect_jar(server_dir: &Path, server_jar: &str, jar: &mut JarContents, detected: &mut DetectedSoftware) {
+    // Vanilla 1.14+ and bundler/paperclip jars carry Mojang's version.json
+    if let Some(version) = jar.read_text("version.json").and_then(|text| serde_json::from_str::<VersionJson>(&text).ok()) {
+        detected.flavor = ServerFlavor::Vanilla;
+        detected.minecraft_version = Some(version.id);
+        detected.required_java = version.java_version;
+        detected.evidence.push(format!("{}!/version.json", server_jar));
+    }
+
+    // Paperclip (Paper, Purpur) and Spigot bundler jars list the bundled server jar
+    if let Some(list) = jar.read_text("META-INF/versions.list") {
+        let bundled = list.lines().filter_map(|line| line.split('\t').nth(2)).collect::<Vec<_>>();
+        let flavor = bundled.iter().find_map(|path| {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            if name.starts_with("paper-") {
+                Some(ServerFlavor::Paper)
+            } else if name.starts_with("purpur-") {
+                Some(ServerFlavor::Purpur)
+            } else if name.starts_with("spigot-") {
+                Some(ServerFlavor::Spigot)
+            } else {
+                None
+            }
+        });
+        if let Some(flavor) = flavor {
+            detected.flavor = flavor;
+            detected.evidence.push(format!("{}!/META-INF/versions.list", server_jar));
+        }
+    }
+
+    // Legacy Paperclip (before 1.18)
+    if let Some(text) = jar.read_text("patch.properties") {
+        let properties = parse_key_values(&text, '=');
+        if detected.flavor == ServerFlavor::Unknown || detected.flavor == ServerFlavor::Vanilla {
+            detected.flavor = if server_jar.starts_with("purpur") { ServerFlavor::Purpur } else { ServerFlavor::Paper };
+        }
+        if let Some(version) = properties.get("version") {
+            detected.minecraft_version = Some(version.clone());
+        }
+        detected.evidence.push(format!("{}!/patch.properties", server_jar));
+    }
+
+    let manifest = jar
+        .read_text("META-INF/MANIFEST.MF")
+        .map(|text| parse_key_values(&unwrap_manifest(&text), ':'))
+        .unwrap_or_default();
+    let main_class = manifest.get("Main-Class").map(String::as_str).unwrap_or("");
+    if main_class.contains("paperclip") && detected.flavor != ServerFlavor::Purpur {
+        if detected.flavor != ServerFlavor::Paper {
+            detected.evidence.push(format!("{}!/META-INF/MANIFEST.MF", server_jar));
+        }
+        detected.flavor = if server_jar.starts_with("purpur") { ServerFlavor::Purpur } else { ServerFlavor::Paper };
+    }
+    if let Some(caps) = BUILD_JAR_REGEX.captures(server_jar) {
+        detected.loader_version = Some(caps[3].to_string());
+        if detected.minecraft_version.is_none() {
+            detected.minecraft_version = Some(caps[2].to_string());
+        }
+    }
+
+    // Fabric server launcher from Fabric meta
+    if let Some(text) = jar.read_text("install.properties") {
+        let properties = parse_key_values(&text, '=');
+        detected.flavor = ServerFlavor::Fabric;
+        detected.minecraft_version = properties.get("game-version").cloned().or(detected.minecraft_version.take());
+        detected.loader_version = properties.get("fabric-loader-version").cloned();
+        detected.evidence.push(format!("{}!/install.properties", server_jar));
+    } else if jar.has("fabric-server-launch.properties") || main_class.contains("net.fabricmc") {
+        // Installer-generated fabric-server-launch.jar: the game is in a separate server.jar
+        detected.flavor = ServerFlavor::Fabric;
+        detected.evidence.push(format!("{}!/fabric-server-launch.properties", server_jar));
+        let server_jar_name = jar
+            .read_text("fabric-server-launch.properties")
+            .and_then(|text| parse_key_values(&text, '=').get("serverJar").cloned())
+            .unwrap_or_else(|| "server.jar".to_string());
+        read_vanilla_version(server_dir, &server_jar_name, detected);
+    }
+
+    // Quilt server launcher written by the Quilt installer
+    if main_class.contains("org.quiltmc") {
+        detected.flavor = ServerFlavor::Quilt;
+        detected.evidence.push(format!("{}!/META-INF/MANIFEST.MF", server_jar));
+        detected.loader_version = manifest
+            .get("Class-Path")
+            .and_then(|class_path| QUILT_LOADER_REGEX.captures(class_path))
+            .map(|caps| caps[1].to_string());
+        read_vanilla_version(server_dir, "server.jar", detected);
+    }
+}
+
+/// Reads the version of the vanilla jar a loader launches.
+fn read_vanilla_version(server_dir: &Path, jar_name: &str, detected: &mut DetectedSoftware) {
+    let Ok(mut jar) = JarContents::open(&server_dir.join(jar_name)) else { return };
+    if let Some(version) = jar.read_text("version.json").and_then(|text| serde_json::from_str::<VersionJson>(&text).ok()) {
+        detected.minecraft_version = Some(version.id);
+        detected.required_java = version.java_version;
+        detected.evidence.push(format!("{}!/version.json", jar_name));
+    }
+}
+
+/// Forge and NeoForge: 1.17+ installs start through `run.sh`/`run.bat` with an args file
+/// under `libraries/`; older installs have a `forge-<mc>-<forge>.jar`.
+fn detect_forge_launch(server_jar: &str, detected: &mut DetectedSoftware) -> bool {
+    // Argument file launch configured after running the installer
+    if let Some(caps) = FORGE_ARGS_REGEX.captures(server_jar) {
+        apply_forge_coordinates(&caps[1], &caps[2], &caps[3], detected);
+        detected.evidence.push(format!("{} (launch argument file)", server_jar));
+        return true;
+    }
+    if let Some(caps) = FORGE_JAR_REGEX.captures(server_jar) {
+        detected.flavor = ServerFlavor::Forge;
+        detected.minecraft_version = Some(caps[1].to_string());
+        detected.loader_version = Some(caps[2].to_string());
+        detected.evidence.push(format!("{} (file name)", server_jar));
+        return true;
+    }
+    false
+}
+
+/// Launch scripts and `libraries/` written by the Forge/NeoForge installer.
+fn detect_forge_files(server_dir: &Path, detected: &mut DetectedSoftware) -> bool {
+    for script in ["run.sh", "run.bat", "user_jvm_args.txt"] {
+        let Ok(content) = fs::read_to_string(server_dir.join(script)) else { continue };
+        if let Some(caps) = FORGE_ARGS_REGEX.captures(&content) {
+            apply_forge_coordinates(&caps[1], &caps[2], &caps[3], detected);
+            detected.evidence.push(script.to_string());
+            return true;
+        }
+    }
+
+    for (group, artifact) in [("minecraftforge", "forge"), ("neoforged", "neoforge"), ("neoforged", "forge")] {
+        let dir = server_dir.join("libraries").join("net").join(group).join(artifact);
+        let Ok(entries) = fs::read_dir(&dir) else { continue };
+        let newest = entries
+            .flatten()
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            // Numerically: 1.20.1-47.2.10 is newer than 1.20.1-47.2.9
+            .max_by(|a, b| server_software::compare_versions(a, b));
+        if let Some(version) = newest {
+            apply_forge_coordinates(group, artifact, &version, detected);
+            detected.evidence.push(format!("libraries/net/{}/{}/{}", group, artifact, version));
+            return true;
+        }
+    }
+    false
+}
+
+/// Interprets a Forge/NeoForge maven version. Forge and the 1.20.1 NeoForge fork use
+/// `<mc>-<loader>`; NeoForge proper uses `<mc minor>.<mc patch>.<build>` (21.1.57 is 1.21.1).
+fn apply_forge_coordinates(group: &str, artifact: &str, version: &str, detected: &mut DetectedSoftware) {
+    detected.flavor = if group == "neoforged" { ServerFlavor::NeoForge } else { ServerFlavor::Forge };
+    if artifact == "neoforge" {
+        let parts: Vec<&str> = version.split('.').collect();
+        if parts.len() >= 2 {
+            detected.minecraft_version = Some(match parts[1] {
+                "0" => format!("1.{}", parts[0]),
+                patch => format!("1.{}.{}", parts[0], patch),
+            });
+        }
+        detected.loader_version = Some(version.to_string());
+    } else if let Some((minecraft, loader)) = version.split_once('-') {
+        detected.minecraft_version = Some(minecraft.to_string());
+        detected.loader_version = Some(loader.to_string());
+    } else {
+        detected.loader_version = Some(version.to_string());
+    }
+}
@@ -0,0 +1,281 @@
+// src/config/software_upgrade.rs
+
+use crate::api::events::emit_progress;
+use crate::app_state::AppState;
+use crate::commands::process_manager;
+use crate::config::backup;
+use crate::config::server_software::{self, ServerSoftwareManager};
+use crate::config::software_detector;
+use crate::error::{AppError, Result};
+use crate::models::server_software::{ServerFlavor, UpgradeCheck, UpgradeRequest, UpgradeResult};
+use crate::models::server_status::ServerStatus;
+use crate::utils::fs_utils;
+use log::{error, info, warn};
+use std::fs;
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use walkdir::WalkDir;
+
+/// Progress task name for upgrades.
+const PROGRESS_TASK: &str = "Upgrade";
+/// How long the first start after an upgrade may take to reach `Running`.
+const FIRST_START_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// Same with `--forceUpgrade`, which converts every chunk before the server is ready.
+const FORCE_UPGRADE_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+/// Added to the stop timeout (after which the process is killed) when waiting for `Stopped`.
+const STOP_WAIT_MARGIN: Duration = Duration::from_secs(30);
+/// Directory inside the backup holding the launch files of the installed software.
+const LAUNCH_FILES_DIR: &str = ".launch-files";
+/// Files some flavors install in place and overwrite on upgrade: the vanilla jar Quilt and
+/// Fabric load, their launchers and Forge's start scripts.
+const LAUNCH_FILES: &[&str] = &[
+    "server.jar",
+    "quilt-server-launch.jar",
+    "fabric-server-launch.jar",
+    "run.sh",
+    "run.bat",
+    "user_jvm_args.txt",
+];
+
+/// Upgrades the installed server software to a newer build or Minecraft version.
+///
+/// The world, config files and launch files are copied to `backups/pre-upgrade-<time>`
+/// first. After the new jar is installed, the server is started once; if it doesn't reach
+/// `Running`, the previous jar is selected again and the backup is restored.
+pub struct SoftwareUpgrader {
+    state: Arc<AppState>,
+    software: Arc<ServerSoftwareManager>,
+}
+
+impl SoftwareUpgrader {
+    pub fn new(state: Arc<AppState>, software: Arc<ServerSoftwareManager>) -> Self {
+        Self { state, software }
+    }
+
+    /// Compares the installed software against the provider. If the installed build is
+    /// unknown (e.g. a jar renamed to `server.jar`), the newest build is offered; if the
+    /// provider no longer lists it, the builds with a higher version are.
+    pub fn check(&self) -> Result<UpgradeCheck> {
+        let installed = software_detector::detect(&self.state.server_directory, &self.state.get_server_jar()?)?;
+        let version = installed
+            .minecraft_version
+            .clone()
+            .ok_or_else(|| AppError::ConfigError("Could not determine the installed Minecraft version".to_string()))?;
+        let provider = self.software.provider(installed.flavor)?;
+
+        // Vanilla has one build per version, identified by the version itself
+        let current_build = match installed.flavor {
+            ServerFlavor::Vanilla => Some(version.clone()),
+            _ => installed.loader_version.clone(),
+        };
+        let builds = provider.list_builds(&version)?;
+        let newer_builds = match &current_build {
+            Some(current) if builds.iter().any(|build| &build.id == current) => {
+                builds.into_iter().take_while(|build| &build.id != current).collect()
+            }
+            Some(current) => builds
+                .into_iter()
+                .filter(|build| server_software::compare_versions(&build.id, current) == Ordering::Greater)
+                .collect(),
+            None => builds.into_iter().take(1).collect(),
+        };
+
+        let versions = provider.list_versions()?;
+        let installed_is_snapshot = versions.iter().any(|v| v.id == version && v.snapshot);
+        let newer_versions = versions
+            .into_iter()
+            .take_while(|v| v.id != version)
+            .filter(|v| installed_is_snapshot || !v.snapshot)
+            .collect();
+
+        Ok(UpgradeCheck { installed, newer_builds, newer_versions })
+    }
+
+    /// Backs up, installs the requested build and starts the server once to verify it.
+    /// A failed first start is rolled back and reported in the result, not as an error;
+    /// errors mean nothing was changed (or the rollback itself failed).
+    pub fn upgrade(&self, request: UpgradeRequest) -> Result<UpgradeResult> {
+        match self.state.get_status()? {
+            ServerStatus::Stopped | ServerStatus::Error(_) => {}
+            status => {
+                return Err(AppError::ServerError(format!(
+                    "Stop the server before upgrading (current state: {})",
+                    status
+                )))
+            }
+        }
+
+        let old_jar = self.state.get_server_jar()?;
+        let installed = software_detector::detect(&self.state.server_directory, &old_jar)?;
+        let version = request
+            .minecraft_version
+            .clone()
+            .or(installed.minecraft_version)
+            .ok_or_else(|| AppError::ConfigError("Could not determine the Minecraft version to upgrade to".to_string()))?;
+
+        emit_progress(PROGRESS_TASK, 0.0, "Backing up world and config...");
+        let backup_dir = self.create_backup()?;
+        let backup_path = backup_dir.display().to_string();
+
+        emit_progress(PROGRESS_TASK, 20.0, &format!("Installing {} {}...", installed.flavor, version));
+        let new_software = self.software.install(installed.flavor, &version, request.build.as_deref())?;
+
+        if request.force_upgrade {
+            self.state.set_next_start_args(vec!["--forceUpgrade".to_string()])?;
+        }
+        let timeout = if request.force_upgrade { FORCE_UPGRADE_TIMEOUT } else { FIRST_START_TIMEOUT };
+        emit_progress(PROGRESS_TASK, 60.0, "Starting the server with the new version...");
+
+        match self.first_start(timeout) {
+            Ok(()) => {
+                emit_progress(PROGRESS_TASK, 100.0, "Upgrade complete.");
+                info!("Upgraded to {} {} build {}", new_software.flavor, version, new_software.build);
+                Ok(UpgradeResult {
+                    installed: Some(new_software),
+                    backup_path,
+                    started: true,
+                    rolled_back: false,
+                    error: None,
+                })
+            }
+            Err(e) => {
+                error!("First start after upgrade failed, rolling back: {}", e);
+                emit_progress(PROGRESS_TASK, 80.0, "First start failed, rolling back...");
+                // The JVM may still be saving chunks; restoring the world under it corrupts it
+                self.wait_until_stopped().map_err(|wait_error| {
+                    AppError::ServerError(format!(
+                        "{} (after: {}); nothing was restored, the backup is in {}",
+                        wait_error, e, backup_path
+                    ))
+                })?;
+                self.rollback(&old_jar, &backup_dir)?;
+                emit_progress(PROGRESS_TASK, 100.0, "Upgrade rolled back.");
+                Ok(UpgradeResult {
+                    installed: Some(new_software),
+                    backup_path,
+                    started: false,
+                    rolled_back: true,
+                    error: Some(e.to_string()),
+                })
+            }
+        }
+    }
+
+    // --- Internals ---
+
+    /// Starts the server and waits until it is `Running`. On timeout the server is stopped.
+    fn first_start(&self, timeout: Duration) -> Result<()> {
+        process_manager::start_server(self.state.clone())?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.state.get_status()? {
+                ServerStatus::Running => return Ok(()),
+                ServerStatus::Starting => {}
+                status => return Err(AppError::ServerError(format!("Server did not start (state: {})", status))),
+            }
+            if Instant::now() >= deadline {
+                warn!("Server did not reach Running within {:?}, stopping it.", timeout);
+                if let Err(e) = process_manager::stop_server(self.state.clone()) {
+                    error!("Failed to stop server after upgrade timeout: {}", e);
+                }
+                return Err(AppError::ServerError(format!(
+                    "Server did not finish starting within {} minutes",
+                    timeout.as_secs() / 60
+                )));
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+    }
+
+    /// Waits until the server process has exited. A server still `Starting` or `Running` is
+    /// stopped; `stop_server` kills it once the stop timeout is over.
+    fn wait_until_stopped(&self) -> Result<()> {
+        let deadline = Instant::now() + self.state.get_stop_timeout() + STOP_WAIT_MARGIN;
+        loop {
+            match self.state.get_status()? {
+                ServerStatus::Stopped | ServerStatus::Error(_) => return Ok(()),
+                ServerStatus::Stopping => {}
+                _ => process_manager::stop_server(self.state.clone())?,
+            }
+            if Instant::now() >= deadline {
+                return Err(AppError::ServerError("The server process did not exit".to_string()));
+            }
+            thread::sleep(Duration::from_millis(500));
+        }
+    }
+
+    /// Copies the world dimensions and config files into a timestamped backup directory.
+    fn create_backup(&self) -> Result<PathBuf> {
+        backup::with_backup_events(|| self.copy_to_backup())
+    }
+
+    fn copy_to_backup(&self) -> Result<(PathBuf, u64)> {
+        let backup_dir = backup::new_backup_dir(&self.state, "pre-upgrade")?;
+        let mut size = backup::copy_world_and_configs(&self.state, &backup_dir)?;
+        size += self.copy_launch_files(&backup_dir.join(LAUNCH_FILES_DIR))?;
+        info!("Pre-upgrade backup written to {} ({} bytes)", backup_dir.display(), size);
+        Ok((backup_dir, size))
+    }
+
+    /// Copies the configured jar (or Forge's argument file) and the files in `LAUNCH_FILES`,
+    /// keeping their paths relative to the server directory.
+    fn copy_launch_files(&self, target_dir: &Path) -> Result<u64> {
+        let server_dir = &self.state.server_directory;
+        let jar = self.state.get_server_jar()?;
+        let mut names: Vec<&str> = LAUNCH_FILES.to_vec();
+        names.push(jar.trim_start_matches('@'));
+        let mut size = 0;
+        for name in names {
+            let source = server_dir.join(name);
+            let target = target_dir.join(name);
+            if !source.is_file() || target.exists() {
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                fs_utils::ensure_directory(parent)?;
+            }
+            size += fs::copy(&source, &target)?;
+        }
+        Ok(size)
+    }
+
+    /// Re-selects the previous jar and restores everything in the backup directory.
+    fn rollback(&self, old_jar: &str, backup_dir: &Path) -> Result<()> {
+        self.state.set_next_start_args(Vec::new())?;
+        self.software.set_server_jar(old_jar)?;
+
+        let server_dir = &self.state.server_directory;
+        for entry in fs::read_dir(backup_dir)? {
+            let source = entry?.path();
+            let Some(name) = source.file_name() else { continue };
+            if name == LAUNCH_FILES_DIR {
+                continue;
+            }
+            let target = server_dir.join(name);
+            if source.is_dir() {
+                fs_utils::remove_directory(&target)?;
+                fs_utils::copy_directory(&source, &target)?;
+            } else {
+                fs::copy(&source, &target)?;
+            }
+        }
+        // Restored file by file: the argument file lives deep inside `libraries/`
+        let launch_dir = backup_dir.join(LAUNCH_FILES_DIR);
+        for entry in WalkDir::new(&launch_dir).min_depth(1) {
+            let entry = entry.map_err(|e| AppError::IoError(std::io::Error::other(e.to_string())))?;
+            let Ok(relative) = entry.path().strip_prefix(&launch_dir) else { continue };
+            if entry.file_type().is_file() {
+                let target = server_dir.join(relative);
+                if let Some(parent) = target.parent() {
+                    fs_utils::ensure_directory(parent)?;
+                }
+                fs::copy(entry.path(), &target)?;
+            }
+        }
+        info!("Rolled back to {} and restored {}", old_jar, backup_dir.display());
+        Ok(())
+    }
+}
@@ -16,6 +16,7 @@ pub enum AppError {
     NotImplemented(String), // Placeholder for features not yet implemented
     ModpackError(String), // Specific errors during modpack installation
     BackupError(String), // Specific errors during backup
+    DownloadError(String), // Failed or unverifiable downloads (server software, mods)
     // Add other specific error types as needed
 }
 
@@ -33,6 +34,7 @@ impl fmt::Display for AppError {
             AppError::NotImplemented(feature) => write!(f, "Feature not implemented yet: {}", feature),
             AppError::ModpackError(msg) => write!(f, "Modpack installation failed: {}", msg),
             AppError::BackupError(msg) => write!(f, "Backup operation failed: {}", msg),
+            AppError::DownloadError(msg) => write!(f, "Download failed: {}", msg),
         }
     }
 }
@@ -12,14 +12,27 @@ pub mod utils;
 
 // --- Imports ---
 use crate::api::events::{self, Event, TAURI_BACKEND_EVENT};
+use crate::api::email::EmailNotifier;
+use crate::api::mqtt::MqttPublisher;
+use crate::api::prometheus::PrometheusExporter;
+use crate::api::webhooks::WebhookDispatcher;
 use crate::app_state::AppState;
-use crate::config::{eula_manager, server_properties}; // Import specific config modules
+use crate::commands::gamerules::GameruleService;
+use crate::commands::player_access::PlayerAccessManager;
+use crate::commands::startup_queue::StartupCommandQueue;
+use crate::config::manager_config::{ManagerConfigStore, MANAGER_CONFIG_FILE};
+use crate::config::modpack_installer::ModpackInstaller;
+use crate::config::presets::PresetManager;
+use crate::config::server_software::ServerSoftwareManager;
+use crate::config::software_upgrade::SoftwareUpgrader;
+use crate::config::{config_templates, eula_manager, server_properties}; // Import specific config modules
 use crate::error::{AppError, Result};
 // Import monitoring components
 use crate::monitoring::{
     alert_manager::AlertManager, metrics_collector::MetricsCollector, resource_monitor,
 };
 use crate::utils::java_detector;
+use crate::utils::uuid_resolver::{CachingResolver, MojangResolver, DEFAULT_MOJANG_API_URL};
 use log::{debug, error, info, warn}; // Use log crate
 use std::{
     fs, // Filesystem operations
@@ -73,12 +86,7 @@ fn initialize_app(app: &mut tauri::App) -> Result<()> {
     info!("Initializing application backend...");
     let app_handle = app.handle();
 
-    // --- 1. Detect Java ---
-    let java_path =
-        java_detector::find_java_path().map_err(|_| AppError::JavaNotFound)?; // Convert error if needed
-    info!("Java found at: {:?}", java_path);
-
-    // --- 2. Determine Server Directory & Log Directory ---
+    // --- 1. Determine Server Directory & Log Directory ---
     let app_data_dir = app_handle
         .path_resolver()
         .app_data_dir()
@@ -91,7 +99,7 @@ fn initialize_app(app: &mut tauri::App) -> Result<()> {
         .app_log_dir() // Use dedicated log dir from Tauri
         .ok_or_else(|| AppError::ConfigError("Could not determine app log directory".to_string()))?;
 
-    // --- 3. Ensure Directories Exist ---
+    // --- 2. Ensure Directories Exist ---
     for dir in [&server_dir, &log_dir] {
         if !dir.exists() {
             info!("Creating directory: {}", dir.display());
@@ -106,36 +114,111 @@ fn initialize_app(app: &mut tauri::App) -> Result<()> {
         }
     }
 
-    // --- 4. Create Event Channel & Set Global Sender ---
+    // --- 3. Load Manager Config ---
+    // A corrupt file aborts initialization with a clear message instead of losing settings
+    let config_store = Arc::new(ManagerConfigStore::load(app_data_dir.join(MANAGER_CONFIG_FILE))?);
+    let manager_config = config_store.get()?;
+    app.manage(config_store.clone());
+
+    // --- 4. Detect Java (unless overridden in the manager config) ---
+    let java_path = match &manager_config.java_path {
+        Some(path) if path.exists() => {
+            info!("Using Java override from manager config: {:?}", path);
+            path.clone()
+        }
+        Some(path) => {
+            warn!("Configured Java path {:?} does not exist, falling back to detection.", path);
+            java_detector::find_java_path().map_err(|_| AppError::JavaNotFound)?
+        }
+        None => java_detector::find_java_path().map_err(|_| AppError::JavaNotFound)?, // Convert error if needed
+    };
+    info!("Java found at: {:?}", java_path);
+
+    // --- 5. Create Event Channel & Set Global Sender ---
     let (event_sender, event_receiver) = events::create_event_channel();
     events::set_event_sender(event_sender);
 
-    // --- 5. Create and Manage AppState ---
-    // TODO: Load server_jar name and potentially java_args from a persistent config file
-    let server_jar_name = "server.jar".to_string(); // Default, maybe loaded from config
-    let app_state = AppState::new(
-        server_dir.to_string_lossy().into_owned(),
-        java_path.to_string_lossy().into_owned(),
-        server_jar_name,
-    )?; // Propagate error from AppState::new if any
+    // --- 6. Create and Manage AppState ---
+    let app_state = AppState::from_config(server_dir.clone(), java_path, &manager_config)?;
     app.manage(app_state.clone()); // Make AppState available via app.state()
-    info!("AppState initialized and managed.");
+    info!("AppState initialized from manager config and managed.");
 
-    // --- 6. Create Monitoring Components ---
+    // --- 7. Create Monitoring Components ---
     // MetricsCollector needs the log directory path
     let metrics_collector = Arc::new(MetricsCollector::new(log_dir.clone()));
-    // AlertManager uses default thresholds initially (should be configurable later)
-    let alert_manager = Arc::new(AlertManager::new());
+    // AlertManager takes its rules from the manager config, alert state lives in alerts.json
+    let alert_manager = Arc::new(AlertManager::new(config_store.clone(), app_data_dir.join("alerts.json")));
     // Store these Arcs in AppState if other parts of the app need to access them directly?
-    // For now, we only pass them to the monitoring thread.
-    // app.manage(metrics_collector.clone()); // Optional: If needed via Tauri state
-    // app.manage(alert_manager.clone());    // Optional: If needed via Tauri state
+    app.manage(metrics_collector.clone()); // Needed by get_metrics_range
+    app.manage(alert_manager.clone()); // Needed by the alert rule commands
+
+    // Prometheus exporter: counts events from now on, listener stays off unless enabled
+    let prometheus_exporter = PrometheusExporter::new(app_state.clone(), alert_manager.clone());
+    if let Err(e) = prometheus_exporter.apply_config(manager_config.exporter.clone()) {
+        error!("Failed to apply Prometheus exporter config: {}", e);
+    }
+    app.manage(prometheus_exporter);
 
-    // --- 7. Start Event Bridge ---
+    // Webhooks: deliver events to configured endpoints, failed deliveries go to the log dir
+    let webhook_dispatcher = WebhookDispatcher::new(
+        app_data_dir.join("webhooks.json"),
+        log_dir.join("webhook-dead-letters.jsonl"),
+    );
+    app.manage(webhook_dispatcher);
+
+    // Email notifications: batches alerts and crashes into digests
+    let email_notifier = EmailNotifier::new(app_data_dir.join("email.json"));
+    app.manage(email_notifier);
+
+    // MQTT publisher: connects only if enabled in mqtt.json
+    let mqtt_publisher = MqttPublisher::new(app_state.clone(), app_data_dir.join("mqtt.json"));
+    if let Err(e) = mqtt_publisher.apply_config() {
+        error!("Failed to start MQTT publisher: {}", e);
+    }
+    app.manage(mqtt_publisher);
+
+    // Console commands (preset datapacks) sent on the next successful start
+    let startup_queue = StartupCommandQueue::new(app_state.clone(), app_data_dir.join("startup-commands.json"));
+    app.manage(startup_queue.clone());
+    // Gamerules: queried through the console, desired values re-applied on every start
+    let gamerule_service = GameruleService::new(app_state.clone(), app_data_dir.join("gamerules.json"));
+    app.manage(gamerule_service.clone());
+    let preset_manager = Arc::new(PresetManager::new(
+        app_state.clone(),
+        config_store.clone(),
+        startup_queue,
+        gamerule_service,
+        app_data_dir.join("presets"),
+    ));
+    app.manage(preset_manager);
+
+    // Whitelist, ops and bans: files while stopped, console commands while running
+    let uuid_resolver = Arc::new(CachingResolver::new(
+        Box::new(MojangResolver::new(DEFAULT_MOJANG_API_URL)),
+        app_data_dir.join("uuid-cache.json"),
+    ));
+    app.manage(Arc::new(PlayerAccessManager::new(app_state.clone(), uuid_resolver)));
+
+    // Server software downloads (Vanilla, Paper, Purpur, Fabric, Quilt)
+    let software_manager = Arc::new(ServerSoftwareManager::new(
+        app_state.clone(),
+        config_store.clone(),
+        app_data_dir.join("software-sources.json"),
+    ));
+    app.manage(software_manager.clone());
+    app.manage(Arc::new(SoftwareUpgrader::new(app_state.clone(), software_manager.clone())));
+    app.manage(Arc::new(ModpackInstaller::new(
+        app_state.clone(),
+        software_manager,
+        config_store.clone(),
+        app_data_dir.join("curseforge.json"),
+    )));
+
+    // --- 8. Start Event Bridge ---
     // Needs to run after event sender is set and potentially after other components are ready
     setup_event_bridge(app_handle.clone(), event_receiver);
 
-    // --- 8. Start Background Tasks ---
+    // --- 9. Start Background Tasks ---
     info!("Starting background monitoring task...");
     // Clone Arcs needed for the monitoring task
     let monitoring_state = app_state.clone();
@@ -151,7 +234,7 @@ fn initialize_app(app: &mut tauri::App) -> Result<()> {
         warn!("Resource monitoring task finished unexpectedly!");
     });
 
-    // --- 9. Perform Initial Config/State Checks ---
+    // --- 10. Perform Initial Config/State Checks ---
     info!("Performing initial configuration checks...");
     // Ensure default server.properties exists if needed
     if let Err(e) = server_properties::create_default_properties_if_missing(&app_state) {
@@ -160,6 +243,11 @@ fn initialize_app(app: &mut tauri::App) -> Result<()> {
         events::emit_app_error(&e);
     }
 
+    // Install the default templates and profiles (never overwrites user edits)
+    if let Err(e) = config_templates::install_default_templates(&app_state) {
+        warn!("Failed to install default config templates: {}", e);
+    }
+
     // Check EULA status and emit initial event
     let eula_check_state = app_state.clone();
     tokio::spawn(async move {
@@ -176,7 +264,6 @@ fn initialize_app(app: &mut tauri::App) -> Result<()> {
     });
 
     // TODO: Check if a modpack is installed and load its info into ServerConfig/AppState?
-    // TODO: Load persisted ServerConfig (including java_args, thresholds) if it exists.
 
     info!("Backend initialization complete.");
     Ok(())
@@ -230,6 +317,7 @@ pub fn run() {
             greet, // Keep example command?
             api::rest::get_server_status,
             api::rest::get_server_metrics,
+            api::rest::get_metrics_range,
             api::rest::start_server,
             api::rest::stop_server,
             api::rest::restart_server,
@@ -239,8 +327,82 @@ pub fn run() {
             api::rest::accept_eula,
             api::rest::is_eula_accepted,
             api::rest::install_modpack,
+            api::rest::install_modpack_from_path,
+            api::rest::update_modpack,
+            api::rest::update_modpack_from_path,
+            api::rest::get_modpack_conflicts,
+            api::rest::get_modpack_merge_view,
+            api::rest::resolve_modpack_conflict,
+            api::rest::rollback_modpack_install,
+            api::rest::get_modpack_protected_paths,
+            api::rest::set_modpack_protected_paths,
+            api::rest::get_curseforge_settings,
+            api::rest::set_curseforge_settings,
             api::rest::create_backup,
-            // TODO: Add commands for get/set alert thresholds
+            api::rest::get_exporter_config,
+            api::rest::set_exporter_config,
+            api::rest::get_alert_rules,
+            api::rest::set_alert_rules,
+            api::rest::upsert_alert_rule,
+            api::rest::delete_alert_rule,
+            api::rest::get_active_alerts,
+            api::rest::get_alert_history,
+            api::rest::dismiss_alert,
+            api::rest::get_webhook_config,
+            api::rest::set_webhook_config,
+            api::rest::test_webhook,
+            api::rest::get_webhook_dead_letters,
+            api::rest::clear_webhook_dead_letters,
+            api::rest::get_email_config,
+            api::rest::set_email_config,
+            api::rest::send_test_email,
+            api::rest::get_mqtt_config,
+            api::rest::set_mqtt_config,
+            api::rest::get_mqtt_status,
+            api::rest::get_manager_config,
+            api::rest::update_manager_settings,
+            api::rest::list_config_files,
+            api::rest::get_config_file,
+            api::rest::get_config_value,
+            api::rest::set_config_value,
+            api::rest::validate_config_file,
+            api::rest::save_config_file,
+            api::rest::list_template_profiles,
+            api::rest::save_template_profile,
+            api::rest::delete_template_profile,
+            api::rest::preview_template_profile,
+            api::rest::apply_template_profile,
+            api::rest::list_presets,
+            api::rest::save_preset,
+            api::rest::delete_preset,
+            api::rest::apply_preset,
+            api::rest::save_current_as_preset,
+            api::rest::export_preset,
+            api::rest::import_preset,
+            api::rest::get_startup_commands,
+            api::rest::clear_startup_commands,
+            api::rest::get_gamerules,
+            api::rest::set_gamerule,
+            api::rest::clear_desired_gamerule,
+            api::rest::get_player_access_lists,
+            api::rest::resolve_player,
+            api::rest::whitelist_add,
+            api::rest::whitelist_remove,
+            api::rest::op_add,
+            api::rest::op_remove,
+            api::rest::ban_player,
+            api::rest::pardon_player,
+            api::rest::ban_ip,
+            api::rest::pardon_ip,
+            api::rest::import_player_access_csv,
+            api::rest::get_software_sources,
+            api::rest::set_software_sources,
+            api::rest::list_server_versions,
+            api::rest::list_server_builds,
+            api::rest::install_server_software,
+            api::rest::detect_server_software,
+            api::rest::check_server_upgrade,
+            api::rest::upgrade_server_software,
         ])
         .build(tauri::generate_context!()); // Use build() before run()
 
@@ -0,0 +1,198 @@
+use crate::models::metrics::MetricsData;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A metric field of `MetricsData` that an alert rule can watch.
+/// Percentages are computed here so rules don't need to know about totals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricField {
+    /// CPU usage of the server process tree (0-100).
+    CpuUsage,
+    /// Resident memory of the server process tree (bytes).
+    MemoryUsageBytes,
+    /// Resident memory as a percentage of host memory (0-100).
+    MemoryUsagePercent,
+    /// Players online.
+    PlayerCount,
+    /// Players online as a percentage of max-players (0-100).
+    PlayerPercent,
+    /// Ticks per second.
+    Tps,
+    /// Server uptime (seconds).
+    Uptime,
+    /// Threads in the server process tree.
+    ThreadCount,
+    /// Open file descriptors of the server process tree.
+    OpenFiles,
+    /// Disk read throughput (bytes/s).
+    DiskReadBytesPerSec,
+    /// Disk write throughput (bytes/s).
+    DiskWriteBytesPerSec,
+    /// Host load average over 1 minute.
+    LoadAverage1m,
+    /// Receive throughput of all interfaces in the server's network namespace, i.e. the
+    /// whole host unless the server is containerised (bytes/s, Linux only).
+    #[serde(alias = "network_rx_bytes_per_sec")]
+    HostNetworkRxBytesPerSec,
+    /// Transmit throughput of the server's network namespace (bytes/s, Linux only).
+    #[serde(alias = "network_tx_bytes_per_sec")]
+    HostNetworkTxBytesPerSec,
+    /// JVM heap in use as a percentage of the maximum heap (0-100).
+    HeapUsedPercent,
+    /// JVM heap after the last full GC as a percentage of the maximum heap (0-100).
+    HeapAfterFullGcPercent,
+    /// JVM old generation occupancy (0-100).
+    OldGenOccupancyPercent,
+    /// Duration of the most recent GC pause (ms).
+    LastGcPauseMs,
+    /// JVM allocation rate (bytes/s).
+    AllocationRateBytesPerSec,
+}
+
+impl MetricField {
+    /// Extracts the field's value from a metrics sample.
+    /// Returns None if the value is not available (e.g., no TPS, no JVM metrics, unknown totals).
+    pub fn extract(&self, m: &MetricsData) -> Option<f64> {
+        let percent = |part: f64, total: f64| if total > 0.0 { Some(part / total * 100.0) } else { None };
+        match self {
+            MetricField::CpuUsage => Some(m.cpu_usage as f64),
+            MetricField::MemoryUsageBytes => Some(m.memory_usage as f64),
+            MetricField::MemoryUsagePercent => percent(m.memory_usage as f64, m.system_memory_total as f64),
+            MetricField::PlayerCount => Some(m.player_count as f64),
+            MetricField::PlayerPercent => percent(m.player_count as f64, m.max_players as f64),
+            MetricField::Tps => m.tps.map(|tps| tps as f64),
+            MetricField::Uptime => Some(m.uptime as f64),
+            MetricField::ThreadCount => m.thread_count.map(|v| v as f64),
+            MetricField::OpenFiles => m.open_files.map(|v| v as f64),
+            MetricField::DiskReadBytesPerSec => Some(m.disk_read_bytes_per_sec as f64),
+            MetricField::DiskWriteBytesPerSec => Some(m.disk_write_bytes_per_sec as f64),
+            MetricField::LoadAverage1m => Some(m.load_average[0]),
+            MetricField::HostNetworkRxBytesPerSec => m.host_network_rx_bytes_per_sec.map(|v| v as f64),
+            MetricField::HostNetworkTxBytesPerSec => m.host_network_tx_bytes_per_sec.map(|v| v as f64),
+            MetricField::HeapUsedPercent => m.jvm.as_ref().and_then(|jvm| {
+                jvm.heap_max_bytes.and_then(|max| percent(jvm.heap_used_bytes as f64, max as f64))
+            }),
+            MetricField::HeapAfterFullGcPercent => m.jvm.as_ref().and_then(|jvm| jvm.heap_after_full_gc_percent.map(|v| v as f64)),
+            MetricField::OldGenOccupancyPercent => m.jvm.as_ref().and_then(|jvm| jvm.old_gen_occupancy_percent.map(|v| v as f64)),
+            MetricField::LastGcPauseMs => m.jvm.as_ref().and_then(|jvm| jvm.last_gc_pause_ms),
+            MetricField::AllocationRateBytesPerSec => m.jvm.as_ref().map(|jvm| jvm.allocation_rate_bytes_per_sec as f64),
+        }
+    }
+}
+
+/// How a metric value is compared against a rule threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Comparison {
+    GreaterThan,
+    GreaterOrEqual,
+    LessThan,
+    LessOrEqual,
+}
+
+impl Comparison {
+    /// Returns true if `value` satisfies the comparison against `threshold`.
+    pub fn matches(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Comparison::GreaterThan => value > threshold,
+            Comparison::GreaterOrEqual => value >= threshold,
+            Comparison::LessThan => value < threshold,
+            Comparison::LessOrEqual => value <= threshold,
+        }
+    }
+
+    /// Symbol used in alert messages.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Comparison::GreaterThan => ">",
+            Comparison::GreaterOrEqual => ">=",
+            Comparison::LessThan => "<",
+            Comparison::LessOrEqual => "<=",
+        }
+    }
+}
+
+/// Severity of an alert, used for UI styling and notification routing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl fmt::Display for AlertSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlertSeverity::Info => write!(f, "info"),
+            AlertSeverity::Warning => write!(f, "warning"),
+            AlertSeverity::Critical => write!(f, "critical"),
+        }
+    }
+}
+
+/// A user-editable alert rule.
+///
+/// The rule fires once `metric <comparison> threshold` has held continuously for `for_secs`.
+/// It resolves when the value no longer satisfies the comparison against `recovery_threshold`
+/// (hysteresis), which defaults to `threshold`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    /// Unique identifier. Generated from the name if left empty when saving.
+    pub id: String,
+    /// Human-readable name shown in alerts (e.g., "High CPU").
+    pub name: String,
+    /// Metric field to watch.
+    pub metric: MetricField,
+    pub comparison: Comparison,
+    pub threshold: f64,
+    /// How long (in seconds) the condition must hold before the alert fires. 0 fires immediately.
+    #[serde(default)]
+    pub for_secs: u64,
+    /// Threshold the value must cross back over to resolve. Defaults to `threshold`.
+    #[serde(default)]
+    pub recovery_threshold: Option<f64>,
+    pub severity: AlertSeverity,
+    /// Disabled rules are kept but never evaluated.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Lifecycle state of an alert instance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertState {
+    Firing,
+    Resolved,
+}
+
+/// An alert instance produced by a rule. Sent to the frontend via `Event::Alert`
+/// on every state transition (firing, resolved).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    /// Unique identifier of this alert instance.
+    pub id: String,
+    /// Rule that produced the alert.
+    pub rule_id: String,
+    pub rule_name: String,
+    pub metric: MetricField,
+    pub severity: AlertSeverity,
+    pub state: AlertState,
+    pub comparison: Comparison,
+    pub threshold: f64,
+    /// Value that triggered the alert.
+    pub value: f64,
+    /// Latest value seen while firing, or the value at resolution.
+    pub last_value: f64,
+    /// UNIX timestamp when the alert started firing.
+    pub started_at: u64,
+    /// UNIX timestamp when the alert resolved, if it has.
+    pub resolved_at: Option<u64>,
+    /// Human-readable summary.
+    pub message: String,
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use crate::config::server_properties; // Import for default properties logic
+use crate::models::server_software::ServerFlavor;
 
 /// Represents the complete server configuration managed by the application.
 /// This structure can be serialized/deserialized to/from a persistent format (e.g., JSON).
@@ -26,13 +27,138 @@ pub struct ModpackConfig {
     pub version: String,
     /// URL the modpack was originally downloaded from (for reference or updates).
     pub source_url: Option<String>, // Changed from installer_url for generality
-    /// Required Forge version, if applicable.
+    /// Required Forge version, if applicable. Also holds the NeoForge version.
     pub forge_version: Option<String>,
-    /// Required Fabric version, if applicable.
+    /// Required Fabric version, if applicable. Also holds the Quilt loader version.
     pub fabric_version: Option<String>,
+    /// Minecraft version the pack targets, if known.
+    #[serde(default)]
+    pub minecraft_version: Option<String>,
+    /// Mod loader installed for the pack, if any.
+    #[serde(default)]
+    pub loader: Option<ServerFlavor>,
     // Add other relevant metadata, e.g., manifest ID, author
 }
 
+/// Mod loader to install after extracting a modpack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackLoader {
+    /// Forge, NeoForge, Fabric or Quilt.
+    pub flavor: ServerFlavor,
+    pub minecraft_version: String,
+    /// Loader version; the newest stable one if None.
+    #[serde(default)]
+    pub loader_version: Option<String>,
+}
+
+/// Outcome of a modpack install.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackInstallResult {
+    pub modpack: ModpackConfig,
+    /// Files the pack lists that were not installed: client-only files and optional ones.
+    #[serde(default)]
+    pub skipped_files: Vec<String>,
+    /// Files whose authors don't allow third-party downloads. The install went ahead without
+    /// them; they have to be downloaded by hand.
+    #[serde(default)]
+    pub manual_downloads: Vec<ManualDownload>,
+    /// What the install changed in the server directory.
+    #[serde(default)]
+    pub changes: ModpackChangeSet,
+}
+
+/// Difference between a staged modpack and the live server directory, as relative paths.
+/// Protected paths are never touched and don't appear in the other lists.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModpackChangeSet {
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub removed: Vec<String>,
+    /// Live files and directories kept as they are (worlds, ops.json, ...).
+    pub protected: Vec<String>,
+    pub unchanged: usize,
+}
+
+/// Outcome of a modpack update: the install itself plus how the new pack was merged with
+/// the files the user changed since the last install.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackUpdateResult {
+    /// The pack that was installed before the update.
+    pub previous: Option<ModpackConfig>,
+    pub install: ModpackInstallResult,
+    pub merge: ModpackMergeSummary,
+}
+
+/// How each file of a modpack update was handled, as relative paths.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModpackMergeSummary {
+    /// Files the user never touched, replaced (or removed) by the new pack's version.
+    pub auto_applied: Vec<String>,
+    /// Files only the user changed; the user's version was kept.
+    pub kept_user_changes: Vec<String>,
+    /// Text files both sides changed without overlapping, merged line by line.
+    pub merged: Vec<String>,
+    /// Files both sides changed in the same place. The user's version was kept until the
+    /// conflict is resolved.
+    pub conflicts: Vec<ModpackConflict>,
+    /// Mod jars the new pack version adds.
+    pub added_mods: Vec<String>,
+    /// Mod jars the new pack version no longer ships.
+    pub removed_mods: Vec<String>,
+}
+
+/// A file the user and the new pack version both changed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModpackConflict {
+    pub path: String,
+    /// Whether the previous pack version had the file.
+    pub base_exists: bool,
+    /// Whether the file exists in the server directory.
+    pub current_exists: bool,
+    /// Whether the new pack version has the file.
+    pub incoming_exists: bool,
+    /// Whether all existing versions are text and can be shown in the merge view.
+    pub text: bool,
+}
+
+/// The three versions of a conflicting file, for the merge view. Text is None for missing
+/// or binary versions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModpackMergeView {
+    pub conflict: ModpackConflict,
+    pub base: Option<String>,
+    pub current: Option<String>,
+    pub incoming: Option<String>,
+    /// Unified diff from the previous pack version to the user's file.
+    pub current_diff: String,
+    /// Unified diff from the previous pack version to the new one.
+    pub incoming_diff: String,
+}
+
+/// How to resolve a modpack update conflict.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConflictResolution {
+    /// Keep the file in the server directory as it is.
+    KeepCurrent,
+    /// Use the new pack version's file (or delete it if the new version dropped it).
+    TakeIncoming,
+    /// Write the given content, e.g. a hand-merged file.
+    Custom { content: String },
+}
+
+/// A pack file that has to be downloaded by hand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManualDownload {
+    /// Project name.
+    pub name: String,
+    pub file_name: String,
+    /// Page the file can be downloaded from.
+    pub url: String,
+    /// Where to put the file, relative to the server directory.
+    pub destination: String,
+}
+
 impl Default for ServerConfig {
     /// Provides a default configuration, useful for initializing or resetting.
     fn default() -> Self {
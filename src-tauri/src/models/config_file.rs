@@ -0,0 +1,56 @@
+// src/models/config_file.rs
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Syntax of an editable config file, derived from its extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigFormat {
+    Yaml,
+    Toml,
+    Json,
+    /// JSON with comments, trailing commas etc. (Fabric/Quilt mod configs).
+    Json5,
+}
+
+impl ConfigFormat {
+    /// Returns the format for a file extension, or None if the file isn't editable.
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_ascii_lowercase().as_str() {
+            "yml" | "yaml" => Some(ConfigFormat::Yaml),
+            "toml" => Some(ConfigFormat::Toml),
+            "json" => Some(ConfigFormat::Json),
+            "json5" => Some(ConfigFormat::Json5),
+            _ => None,
+        }
+    }
+}
+
+/// A config file found in the server directory.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigFileInfo {
+    /// Path relative to the server directory, with `/` separators.
+    pub path: String,
+    pub format: ConfigFormat,
+    pub size_bytes: u64,
+    /// What the file configures, for well-known files.
+    pub description: Option<String>,
+}
+
+/// A config file's raw text and its parsed tree.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigFileContent {
+    pub path: String,
+    pub format: ConfigFormat,
+    pub text: String,
+    /// The document as JSON (mappings become objects, sequences arrays).
+    pub tree: Value,
+}
+
+/// Outcome of a write to a config file.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigEditResult {
+    /// False if the file had to be re-serialized and its comments were dropped.
+    pub comments_preserved: bool,
+}
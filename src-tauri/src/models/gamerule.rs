@@ -0,0 +1,70 @@
+// src/models/gamerule.rs
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Value type of a gamerule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameruleType {
+    Bool,
+    Int,
+}
+
+/// A gamerule value. Serialized as a plain JSON boolean or number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GameruleValue {
+    Bool(bool),
+    Int(i64),
+}
+
+impl GameruleValue {
+    /// Parses a value as printed by the server ("true", "3").
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.trim() {
+            "true" => Some(GameruleValue::Bool(true)),
+            "false" => Some(GameruleValue::Bool(false)),
+            other => other.parse().ok().map(GameruleValue::Int),
+        }
+    }
+
+    pub fn value_type(&self) -> GameruleType {
+        match self {
+            GameruleValue::Bool(_) => GameruleType::Bool,
+            GameruleValue::Int(_) => GameruleType::Int,
+        }
+    }
+}
+
+impl fmt::Display for GameruleValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameruleValue::Bool(b) => write!(f, "{}", b),
+            GameruleValue::Int(i) => write!(f, "{}", i),
+        }
+    }
+}
+
+/// Catalog entry for a vanilla gamerule.
+#[derive(Debug, Clone, Serialize)]
+pub struct GameruleInfo {
+    pub name: &'static str,
+    pub rule_type: GameruleType,
+    pub default: GameruleValue,
+    /// Smallest accepted value for integer rules.
+    pub min: Option<i64>,
+}
+
+/// Gamerules as reported by the running server plus the values the manager enforces.
+#[derive(Debug, Clone, Serialize)]
+pub struct GameruleReport {
+    /// True if `values` was read from the running server just now.
+    pub running: bool,
+    /// Current values, by rule name. Empty while the server is stopped.
+    pub values: BTreeMap<String, GameruleValue>,
+    /// Values re-applied on every start (survive world resets).
+    pub desired: BTreeMap<String, GameruleValue>,
+    pub catalog: Vec<GameruleInfo>,
+}
@@ -7,9 +7,9 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 pub struct MetricsData {
     /// UNIX timestamp (seconds since epoch) when the metrics were collected.
     pub timestamp: u64,
-    /// CPU usage of the server process (percentage). How this is calculated depends on the monitoring implementation.
+    /// CPU usage of the server process tree (percentage, 0-100), normalized to the number of host cores.
     pub cpu_usage: f32,
-    /// Memory currently used by the server process (in bytes).
+    /// Resident memory (RSS) used by the server process tree (in bytes).
     pub memory_usage: u64,
     /// Total physical memory available on the system (in bytes). Added for context.
     pub system_memory_total: u64, // Renamed from memory_total for clarity
@@ -21,6 +21,66 @@ pub struct MetricsData {
     pub tps: Option<f32>,
     /// Server process uptime in seconds.
     pub uptime: u64,
+    /// Number of processes in the server process tree (wrapper scripts, the JVM and any children).
+    pub process_count: u32,
+    /// Total number of threads across the server process tree, if the platform reports it.
+    pub thread_count: Option<u32>,
+    /// Total number of open file descriptors across the server process tree, if the platform reports it.
+    pub open_files: Option<u32>,
+    /// Disk bytes read per second by the server process tree.
+    pub disk_read_bytes_per_sec: u64,
+    /// Disk bytes written per second by the server process tree.
+    pub disk_write_bytes_per_sec: u64,
+    /// Host load average over the last 1, 5 and 15 minutes.
+    pub load_average: [f64; 3],
+    /// Bytes received per second on all interfaces of the server process' network namespace
+    /// (Linux only). Unless the server runs in its own namespace (e.g. a container), this is
+    /// the whole host's traffic, not just the server's.
+    #[serde(alias = "network_rx_bytes_per_sec")]
+    pub host_network_rx_bytes_per_sec: Option<u64>,
+    /// Bytes sent per second in the same namespace (Linux only).
+    #[serde(alias = "network_tx_bytes_per_sec")]
+    pub host_network_tx_bytes_per_sec: Option<u64>,
+    /// JVM heap and garbage collection metrics, if a GC log or `jstat` was available.
+    pub jvm: Option<JvmMetrics>,
+}
+
+/// Where the JVM metrics in a sample were read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JvmMetricsSource {
+    /// Parsed from the `-Xlog:gc*` file enabled by the launcher.
+    GcLog,
+    /// Polled with `jstat -gc` from the detected JDK.
+    Jstat,
+}
+
+/// JVM-level memory and garbage collection metrics of the server process.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JvmMetrics {
+    /// Source the values were read from. None only for the default instance.
+    pub source: Option<JvmMetricsSource>,
+    /// Heap currently in use (in bytes). For GC logs this is the value after the last collection.
+    pub heap_used_bytes: u64,
+    /// Heap currently committed by the JVM (in bytes).
+    pub heap_committed_bytes: u64,
+    /// Maximum heap size (-Xmx) in bytes, if known.
+    pub heap_max_bytes: Option<u64>,
+    /// Old generation usage (in bytes), if the collector reports it.
+    pub old_gen_used_bytes: Option<u64>,
+    /// Old generation usage as a percentage of the maximum heap, if known.
+    pub old_gen_occupancy_percent: Option<f32>,
+    /// Heap usage after the most recent full GC as a percentage of the maximum heap.
+    pub heap_after_full_gc_percent: Option<f32>,
+    /// Total number of young (and mixed) collections since the JVM started.
+    pub young_gc_count: u64,
+    /// Total number of full collections since the JVM started.
+    pub full_gc_count: u64,
+    /// Total time spent in GC pauses since the JVM started (in milliseconds).
+    pub gc_pause_total_ms: f64,
+    /// Duration of the most recent GC pause (in milliseconds), if known.
+    pub last_gc_pause_ms: Option<f64>,
+    /// Estimated allocation rate (bytes per second).
+    pub allocation_rate_bytes_per_sec: u64,
 }
 
 impl Default for MetricsData {
@@ -40,6 +100,15 @@ impl Default for MetricsData {
             max_players: 0, // Should be updated from config by monitor
             tps: None,
             uptime: 0,
+            process_count: 0,
+            thread_count: None,
+            open_files: None,
+            disk_read_bytes_per_sec: 0,
+            disk_write_bytes_per_sec: 0,
+            load_average: [0.0; 3],
+            host_network_rx_bytes_per_sec: None,
+            host_network_tx_bytes_per_sec: None,
+            jvm: None,
         }
     }
 }
\ No newline at end of file
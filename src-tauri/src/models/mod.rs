@@ -1,4 +1,12 @@
 ﻿pub mod server_status;
 pub mod metrics;
 pub mod config;
-pub mod log_entry;
\ No newline at end of file
+pub mod log_entry;
+pub mod alert;
+pub mod property;
+pub mod config_file;
+pub mod template;
+pub mod preset;
+pub mod gamerule;
+pub mod player_access;
+pub mod server_software;
\ No newline at end of file
@@ -0,0 +1,100 @@
+// src/models/player_access.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// The access list files kept in the server directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessList {
+    Whitelist,
+    Ops,
+    BannedPlayers,
+    BannedIps,
+}
+
+impl AccessList {
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            AccessList::Whitelist => "whitelist.json",
+            AccessList::Ops => "ops.json",
+            AccessList::BannedPlayers => "banned-players.json",
+            AccessList::BannedIps => "banned-ips.json",
+        }
+    }
+}
+
+/// A player name with its UUID (dashed form).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerProfile {
+    pub uuid: String,
+    pub name: String,
+}
+
+/// Entry of `whitelist.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WhitelistEntry {
+    pub uuid: String,
+    pub name: String,
+}
+
+/// Entry of `ops.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpEntry {
+    pub uuid: String,
+    pub name: String,
+    /// Permission level 1-4.
+    pub level: u8,
+    #[serde(default)]
+    pub bypasses_player_limit: bool,
+}
+
+/// Entry of `banned-players.json`. Dates use the server's format
+/// ("2024-05-01 18:30:00 +0000"); `expires` is "forever" for permanent bans.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BanEntry {
+    pub uuid: String,
+    pub name: String,
+    pub created: String,
+    pub source: String,
+    pub expires: String,
+    pub reason: String,
+}
+
+/// Entry of `banned-ips.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IpBanEntry {
+    pub ip: String,
+    pub created: String,
+    pub source: String,
+    pub expires: String,
+    pub reason: String,
+}
+
+/// Contents of all four access lists.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlayerAccessLists {
+    pub whitelist: Vec<WhitelistEntry>,
+    pub ops: Vec<OpEntry>,
+    pub banned_players: Vec<BanEntry>,
+    pub banned_ips: Vec<IpBanEntry>,
+}
+
+/// Ban parameters shared by player and IP bans.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct BanRequest {
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// None for a permanent ban.
+    #[serde(default)]
+    pub expires: Option<DateTime<Utc>>,
+}
+
+/// Outcome of a CSV import.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CsvImportResult {
+    pub imported: usize,
+    /// One message per rejected line, prefixed with the line number.
+    pub errors: Vec<String>,
+}
@@ -0,0 +1,73 @@
+// src/models/preset.rs
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Garbage collector tuning applied on top of the memory settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JvmProfile {
+    /// Only heap sizes, JVM defaults otherwise.
+    Default,
+    /// Aikar's G1GC flags, the common recommendation for Paper servers.
+    Aikar,
+}
+
+/// JVM settings of a preset. Replaces the server's Java arguments when applied.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JvmSettings {
+    pub profile: JvmProfile,
+    /// Initial heap (-Xms) in MiB.
+    pub min_memory_mb: u32,
+    /// Maximum heap (-Xmx) in MiB.
+    pub max_memory_mb: u32,
+    /// Additional arguments appended after the generated ones.
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+/// World generation settings. Only affect worlds created after the preset is applied.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorldSettings {
+    /// `level-type`, e.g. "minecraft:flat".
+    #[serde(default)]
+    pub level_type: Option<String>,
+    #[serde(default)]
+    pub seed: Option<String>,
+    /// `generator-settings` JSON for flat/custom worlds.
+    #[serde(default)]
+    pub generator_settings: Option<String>,
+}
+
+/// A reusable server setup: properties, gamerules, JVM, world and datapacks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerPreset {
+    /// Unique name, also used as the file name.
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// `server.properties` values merged over the current ones.
+    #[serde(default)]
+    pub server_properties: HashMap<String, String>,
+    /// Gamerules enforced on every start (e.g. "keepInventory" -> "true").
+    #[serde(default)]
+    pub gamerules: BTreeMap<String, String>,
+    #[serde(default)]
+    pub jvm: Option<JvmSettings>,
+    #[serde(default)]
+    pub world: Option<WorldSettings>,
+    /// Datapack IDs to enable, e.g. "file/my_pack.zip" or feature packs like "bundle".
+    /// They are enabled for new worlds and on the next start for existing ones; the pack
+    /// files themselves must already be in the world's `datapacks` folder.
+    #[serde(default)]
+    pub datapacks: Vec<String>,
+}
+
+/// What applying a preset did.
+#[derive(Debug, Clone, Serialize)]
+pub struct PresetApplyResult {
+    /// Console commands queued for the next successful start.
+    pub queued_commands: Vec<String>,
+    /// Java arguments now configured.
+    pub java_args: Vec<String>,
+}
@@ -0,0 +1,68 @@
+// src/models/property.rs
+
+use crate::models::config::ServerConfig;
+use serde::Serialize;
+
+/// Value type of a `server.properties` key, used for validation and to pick a UI control.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PropertyType {
+    /// `true` or `false`.
+    Bool,
+    /// Integer within an optional inclusive range.
+    Int { min: Option<i64>, max: Option<i64> },
+    /// One of a fixed set of lowercase names.
+    Enum { values: &'static [&'static str] },
+    /// Free text.
+    String,
+}
+
+/// Catalog entry describing one vanilla `server.properties` key.
+#[derive(Debug, Clone, Serialize)]
+pub struct PropertySpec {
+    pub key: &'static str,
+    #[serde(flatten)]
+    pub property_type: PropertyType,
+    /// Value the server uses when the key is missing.
+    pub default: &'static str,
+    pub description: &'static str,
+    /// First Minecraft version that reads the key. None if it has always existed.
+    pub since: Option<&'static str>,
+    /// First Minecraft version that no longer reads the key.
+    pub removed_in: Option<&'static str>,
+    /// False if the manager can apply a change to a running server with a console command.
+    pub requires_restart: bool,
+}
+
+/// A validation failure for a single key.
+#[derive(Debug, Clone, Serialize)]
+pub struct PropertyError {
+    pub key: String,
+    pub value: String,
+    pub message: String,
+}
+
+/// A property value merged with its catalog metadata, for rendering the settings form.
+#[derive(Debug, Clone, Serialize)]
+pub struct PropertyView {
+    pub key: String,
+    /// Value from the file, or the catalog default if the key isn't set.
+    pub value: String,
+    /// True if the key is present in `server.properties`.
+    pub is_set: bool,
+    /// None for keys unknown to the catalog (plugins, mods, newer versions).
+    pub spec: Option<PropertySpec>,
+    /// Whether the key is read by the given Minecraft version. None if the version is unknown.
+    pub available: Option<bool>,
+}
+
+/// `ServerConfig` plus per-key metadata, returned by `get_server_config`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerConfigView {
+    #[serde(flatten)]
+    pub config: ServerConfig,
+    /// Version the metadata was resolved for, if known.
+    pub minecraft_version: Option<String>,
+    /// All catalog keys plus any unknown keys found in the file, sorted by key.
+    pub properties: Vec<PropertyView>,
+}
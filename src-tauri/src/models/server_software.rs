@@ -0,0 +1,143 @@
+// src/models/server_software.rs
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Kind of server software. All but Spigot and Unknown can be installed through a provider;
+/// those two are only recognized when detecting what is installed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerFlavor {
+    Vanilla,
+    Paper,
+    Purpur,
+    Fabric,
+    Quilt,
+    Spigot,
+    Forge,
+    NeoForge,
+    Unknown,
+}
+
+impl fmt::Display for ServerFlavor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerFlavor::Vanilla => write!(f, "Vanilla"),
+            ServerFlavor::Paper => write!(f, "Paper"),
+            ServerFlavor::Purpur => write!(f, "Purpur"),
+            ServerFlavor::Fabric => write!(f, "Fabric"),
+            ServerFlavor::Quilt => write!(f, "Quilt"),
+            ServerFlavor::Spigot => write!(f, "Spigot"),
+            ServerFlavor::Forge => write!(f, "Forge"),
+            ServerFlavor::NeoForge => write!(f, "NeoForge"),
+            ServerFlavor::Unknown => write!(f, "Unknown"),
+        }
+    }
+}
+
+/// A Minecraft version offered by a provider.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoftwareVersion {
+    pub id: String,
+    /// Snapshot, pre-release or release candidate.
+    pub snapshot: bool,
+    #[serde(default)]
+    pub release_time: Option<DateTime<Utc>>,
+}
+
+/// Expected hash of a download.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", content = "hex", rename_all = "snake_case")]
+pub enum Checksum {
+    Sha1(String),
+    Sha256(String),
+    Sha512(String),
+    Md5(String),
+}
+
+/// A downloadable build for one Minecraft version. For Fabric and Quilt a build is a
+/// loader version.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SoftwareBuild {
+    pub id: String,
+    /// Experimental channel or unstable loader.
+    pub experimental: bool,
+    /// File name the jar is saved as inside the server directory.
+    pub file_name: String,
+    pub download_url: String,
+    #[serde(default)]
+    pub checksum: Option<Checksum>,
+    #[serde(default)]
+    pub time: Option<DateTime<Utc>>,
+    /// Change summaries, newest first. Empty if the provider doesn't publish them.
+    #[serde(default)]
+    pub changelog: Vec<String>,
+}
+
+/// Result of installing server software.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstalledSoftware {
+    pub flavor: ServerFlavor,
+    pub minecraft_version: String,
+    pub build: String,
+    /// Jar now used to start the server.
+    pub server_jar: String,
+    /// Problems worth showing that didn't stop the install, e.g. a download that could not be
+    /// verified because its source publishes no checksum.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// What the detector found in the server directory.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectedSoftware {
+    pub flavor: ServerFlavor,
+    pub minecraft_version: Option<String>,
+    /// Loader version for Fabric, Quilt, Forge and NeoForge; build number for Paper and Purpur.
+    pub loader_version: Option<String>,
+    /// Minimum Java major version the server needs, if known.
+    pub required_java: Option<u32>,
+    /// Jar that was inspected.
+    pub server_jar: String,
+    /// Files the result is based on, for display and troubleshooting.
+    pub evidence: Vec<String>,
+}
+
+/// Newer software available for what is installed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpgradeCheck {
+    pub installed: DetectedSoftware,
+    /// Builds of the installed Minecraft version newer than the installed one, newest first,
+    /// with their changelogs.
+    pub newer_builds: Vec<SoftwareBuild>,
+    /// Newer Minecraft versions offered for the same flavor, newest first.
+    pub newer_versions: Vec<SoftwareVersion>,
+}
+
+/// Parameters of an upgrade. Missing values default to the installed Minecraft version and
+/// its newest stable build.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct UpgradeRequest {
+    #[serde(default)]
+    pub minecraft_version: Option<String>,
+    #[serde(default)]
+    pub build: Option<String>,
+    /// Start with `--forceUpgrade` so the world is converted to the new version right away.
+    #[serde(default)]
+    pub force_upgrade: bool,
+}
+
+/// Outcome of an upgrade.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpgradeResult {
+    pub installed: Option<InstalledSoftware>,
+    /// Directory holding the pre-upgrade copy of the world and config files.
+    pub backup_path: String,
+    /// The first start reached `Running`.
+    pub started: bool,
+    /// The upgrade failed and the previous jar, world and config were restored.
+    pub rolled_back: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+}
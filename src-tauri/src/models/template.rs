@@ -0,0 +1,47 @@
+// src/models/template.rs
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// One file rendered by a profile.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileFile {
+    /// Template name inside the `templates` directory (e.g. "server.properties.tmpl").
+    pub template: String,
+    /// Output path relative to the server directory (e.g. "server.properties").
+    pub output: String,
+}
+
+/// A named set of templates rendered together (`templates/profiles/<name>.json`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateProfile {
+    /// Identifier and file name: lowercase letters, digits, `-` and `_`.
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Default variables. Values passed when rendering override them.
+    #[serde(default)]
+    pub variables: Map<String, Value>,
+    pub files: Vec<ProfileFile>,
+    /// Fail on variables that are used but not defined (instead of rendering them empty).
+    #[serde(default = "default_strict")]
+    pub strict: bool,
+}
+
+fn default_strict() -> bool {
+    true
+}
+
+/// Result of rendering one profile file, with a diff against the current file.
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedFile {
+    pub template: String,
+    pub output: String,
+    pub content: String,
+    /// True if the output file already exists.
+    pub exists: bool,
+    /// True if the rendered content differs from the current file.
+    pub changed: bool,
+    /// Unified diff from the current file to the rendered content (empty if unchanged).
+    pub diff: String,
+}
@@ -1,199 +1,524 @@
 ﻿use crate::api::events::{self, emit_event, emit_log}; // Use helpers
-use crate::app_state::AppState;
-use crate::error::Result; // For potential future use
+use crate::config::manager_config::ManagerConfigStore;
+use crate::error::{AppError, Result};
+use crate::models::alert::{Alert, AlertRule, AlertSeverity, AlertState, Comparison, MetricField};
 use crate::models::log_entry::LogLevel; // Use our LogLevel
 use crate::models::metrics::MetricsData;
-use log::{debug, info, warn};
-use serde::{Deserialize, Serialize}; // For config persistence
-use std::sync::{Arc, Mutex, RwLock}; // Use RwLock for thresholds if needed
-use std::time::Duration; // For alert cooldown
+use crate::utils::fs_utils;
+use log::{debug, error, info};
+use serde::{Deserialize, Serialize}; // For rule/alert persistence
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
 
-// Configuration for alert thresholds. Could be loaded from a file.
+/// Current version of the persisted alerts file.
+const ALERTS_FILE_VERSION: u32 = 2;
+/// Number of resolved alerts kept in the persisted history.
+const MAX_ALERT_HISTORY: usize = 200;
+
+/// On-disk representation of alert state (`alerts.json` in the app data directory).
+/// Rules live in the manager config.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AlertThresholds {
-    /// CPU usage percentage (0-100) above which an alert is triggered.
-    pub cpu_threshold_percent: f32,
-    /// Memory usage percentage (0-100) above which an alert is triggered.
-    pub memory_threshold_percent: f32,
-    /// Player count at or above which a "server nearly full" alert is triggered.
-    pub player_threshold_count: u32,
-    /// Minimum duration (in seconds) between identical alerts to prevent spam.
-    pub alert_cooldown_secs: u64,
+struct AlertsFile {
+    version: u32,
+    /// Alerts currently firing.
+    #[serde(default)]
+    active: Vec<Alert>,
+    /// Most recent resolved alerts, newest last.
+    #[serde(default)]
+    history: Vec<Alert>,
+    /// Rules of a version 1 file, imported into the manager config on first load.
+    #[serde(default, skip_serializing)]
+    rules: Option<Vec<AlertRule>>,
 }
 
-impl Default for AlertThresholds {
-    fn default() -> Self {
-        Self {
-            cpu_threshold_percent: 85.0,   // Default 85% CPU
-            memory_threshold_percent: 85.0, // Default 85% Memory
-            player_threshold_count: 18,     // Default 18 players (if max is 20)
-            alert_cooldown_secs: 300,       // Default 5 minutes cooldown
-        }
-    }
+/// Mutable state guarded by a single lock so rule edits and evaluation never interleave.
+#[derive(Debug)]
+struct AlertEngineState {
+    rules: Vec<AlertRule>,
+    /// Firing alerts keyed by rule id (at most one per rule).
+    active: HashMap<String, Alert>,
+    history: Vec<Alert>,
+    /// Rules whose condition currently holds but whose `for_secs` has not elapsed yet,
+    /// keyed by rule id with the timestamp the condition started holding. Not persisted.
+    pending: HashMap<String, u64>,
 }
 
-/// Manages checking metrics against thresholds and emitting alert events.
+/// Default rules, matching the thresholds the manager shipped with before rules were editable.
+pub fn default_rules() -> Vec<AlertRule> {
+    vec![
+        AlertRule {
+            id: "high-cpu".to_string(),
+            name: "High CPU Usage".to_string(),
+            metric: MetricField::CpuUsage,
+            comparison: Comparison::GreaterThan,
+            threshold: 85.0,
+            for_secs: 60,
+            recovery_threshold: Some(75.0),
+            severity: AlertSeverity::Warning,
+            enabled: true,
+        },
+        AlertRule {
+            id: "high-memory".to_string(),
+            name: "High Memory Usage".to_string(),
+            metric: MetricField::MemoryUsagePercent,
+            comparison: Comparison::GreaterThan,
+            threshold: 85.0,
+            for_secs: 60,
+            recovery_threshold: Some(80.0),
+            severity: AlertSeverity::Warning,
+            enabled: true,
+        },
+        AlertRule {
+            id: "server-almost-full".to_string(),
+            name: "Server Almost Full".to_string(),
+            metric: MetricField::PlayerPercent,
+            comparison: Comparison::GreaterOrEqual,
+            threshold: 90.0,
+            for_secs: 0,
+            recovery_threshold: Some(80.0),
+            severity: AlertSeverity::Info,
+            enabled: true,
+        },
+        AlertRule {
+            id: "heap-after-full-gc".to_string(),
+            name: "Heap Nearly Exhausted After Full GC".to_string(),
+            metric: MetricField::HeapAfterFullGcPercent,
+            comparison: Comparison::GreaterThan,
+            threshold: 90.0,
+            for_secs: 0,
+            recovery_threshold: Some(80.0),
+            severity: AlertSeverity::Critical,
+            enabled: true,
+        },
+        AlertRule {
+            id: "low-tps".to_string(),
+            name: "Low TPS".to_string(),
+            metric: MetricField::Tps,
+            comparison: Comparison::LessThan,
+            threshold: 15.0,
+            for_secs: 30,
+            recovery_threshold: Some(18.0),
+            severity: AlertSeverity::Warning,
+            enabled: true,
+        },
+    ]
+}
+
+/// Evaluates alert rules against metrics samples and emits firing/resolved alert events.
+///
+/// Each rule fires once its condition has held for `for_secs`, stays firing while the
+/// value keeps violating the recovery threshold, and emits a resolved alert when it recovers.
+/// Rules are stored in the manager config; active alerts and recent history in `alerts.json`.
 pub struct AlertManager {
-    // No AppState needed if metrics are passed directly
-    // state: Arc<AppState>,
-    /// Configurable thresholds for triggering alerts. RwLock allows concurrent reads.
-    thresholds: RwLock<AlertThresholds>,
-    /// Tracks the last time (timestamp) each type of alert was triggered.
-    last_cpu_alert_ts: Mutex<Option<u64>>,
-    last_memory_alert_ts: Mutex<Option<u64>>,
-    last_player_alert_ts: Mutex<Option<u64>>,
+    /// Rules and alert state. RwLock allows concurrent reads from Tauri commands.
+    state: RwLock<AlertEngineState>,
+    /// Path of the persisted alert state file.
+    storage_path: PathBuf,
+    /// Manager config holding the rules.
+    config_store: Arc<ManagerConfigStore>,
 }
 
 impl AlertManager {
-    /// Creates a new AlertManager with default thresholds.
-    pub fn new() -> Self { // Removed AppState dependency
-        info!("Initializing AlertManager with default thresholds.");
-        Self {
-            // state,
-            thresholds: RwLock::new(AlertThresholds::default()),
-            last_cpu_alert_ts: Mutex::new(None),
-            last_memory_alert_ts: Mutex::new(None),
-            last_player_alert_ts: Mutex::new(None),
+    /// Creates a new AlertManager with the rules from the manager config and the
+    /// active alerts/history from `storage_path`. A missing or unreadable state file starts empty.
+    pub fn new(config_store: Arc<ManagerConfigStore>, storage_path: PathBuf) -> Self {
+        let mut rules = match config_store.get() {
+            Ok(config) => config.alert_rules,
+            Err(e) => {
+                error!("Failed to read alert rules from manager config, using defaults: {}", e);
+                default_rules()
+            }
+        };
+        let file = match fs_utils::read_file_to_string(&storage_path) {
+            Ok(content) => match serde_json::from_str::<AlertsFile>(&content) {
+                Ok(file) => {
+                    info!("Loaded {} active alerts from {}", file.active.len(), storage_path.display());
+                    Some(file)
+                }
+                Err(e) => {
+                    error!("Alerts file {} is corrupt, starting without active alerts: {}", storage_path.display(), e);
+                    None
+                }
+            },
+            Err(_) => None,
+        };
+        let mut file = file.unwrap_or_else(|| AlertsFile {
+            version: ALERTS_FILE_VERSION,
+            active: Vec::new(),
+            history: Vec::new(),
+            rules: None,
+        });
+        // Version 1 kept the rules in this file; move them into the manager config once
+        let mut imported = false;
+        if file.version < 2 {
+            if let Some(v1_rules) = file.rules.take() {
+                match config_store.update(|config| {
+                    config.alert_rules = v1_rules.clone();
+                    Ok(())
+                }) {
+                    Ok(_) => {
+                        info!("Imported {} alert rules from {}", v1_rules.len(), storage_path.display());
+                        rules = v1_rules;
+                        imported = true;
+                    }
+                    Err(e) => error!("Failed to import alert rules from {}: {}", storage_path.display(), e),
+                }
+            }
         }
+        info!("Loaded {} alert rules.", rules.len());
+
+        let manager = Self {
+            state: RwLock::new(AlertEngineState {
+                rules,
+                active: file.active.into_iter().map(|a| (a.rule_id.clone(), a)).collect(),
+                history: file.history,
+                pending: HashMap::new(),
+            }),
+            storage_path,
+            config_store,
+        };
+        // Alerts whose rule was removed from the config while the manager was closed
+        if let Ok(mut guard) = manager.state.write() {
+            manager.drop_orphaned_alerts(&mut guard);
+            // Rewrite the file as version 2 only once the rules are safe in the manager config
+            if imported {
+                if let Err(e) = manager.persist(&guard) {
+                    error!("Failed to rewrite {} after importing its rules: {}", manager.storage_path.display(), e);
+                }
+            }
+        }
+        manager
     }
 
-    /// Updates the alert thresholds.
-    /// TODO: Persist these thresholds to a config file.
-    pub fn set_thresholds(&self, thresholds: AlertThresholds) -> Result<()> {
-        info!(
-            "Updating alert thresholds: CPU={:.1}%, Mem={:.1}%, Players={}, Cooldown={}s",
-            thresholds.cpu_threshold_percent,
-            thresholds.memory_threshold_percent,
-            thresholds.player_threshold_count,
-            thresholds.alert_cooldown_secs
-        );
-        let mut writer = self.thresholds.write().map_err(|e| {
-            AppError::LockError(format!("Failed to lock thresholds for writing: {}", e))
-        })?;
-        *writer = thresholds;
-        Ok(())
+    // --- Rule Management ---
+
+    /// Returns a clone of all rules.
+    pub fn get_rules(&self) -> Result<Vec<AlertRule>> {
+        self.state
+            .read()
+            .map(|guard| guard.rules.clone())
+            .map_err(|e| AppError::LockError(format!("Failed to lock alert rules for reading: {}", e)))
+    }
+
+    /// Replaces all rules. Alerts of rules that no longer exist are resolved.
+    pub fn set_rules(&self, rules: Vec<AlertRule>) -> Result<()> {
+        let rules = rules.into_iter().map(normalize_rule).collect::<Result<Vec<_>>>()?;
+        let mut ids: Vec<&str> = rules.iter().map(|r| r.id.as_str()).collect();
+        ids.sort_unstable();
+        if ids.windows(2).any(|w| w[0] == w[1]) {
+            return Err(AppError::ConfigError("Alert rule ids must be unique".to_string()));
+        }
+
+        info!("Replacing alert rules ({} rules)", rules.len());
+        let mut guard = self.write_state()?;
+        self.save_rules(&rules)?;
+        guard.rules = rules;
+        guard.pending.clear();
+        self.drop_orphaned_alerts(&mut guard);
+        self.persist(&guard)
+    }
+
+    /// Adds a rule or replaces the rule with the same id. Returns the saved rule (with generated id).
+    pub fn upsert_rule(&self, rule: AlertRule) -> Result<AlertRule> {
+        let rule = normalize_rule(rule)?;
+        info!("Saving alert rule '{}' ({})", rule.name, rule.id);
+        let mut guard = self.write_state()?;
+        let mut rules = guard.rules.clone();
+        match rules.iter_mut().find(|r| r.id == rule.id) {
+            Some(existing) => *existing = rule.clone(),
+            None => rules.push(rule.clone()),
+        }
+        self.save_rules(&rules)?;
+        guard.rules = rules;
+        // Re-evaluate from scratch with the new threshold/duration
+        guard.pending.remove(&rule.id);
+        self.drop_orphaned_alerts(&mut guard);
+        self.persist(&guard)?;
+        Ok(rule)
+    }
+
+    /// Deletes a rule by id, resolving its active alert if any.
+    pub fn delete_rule(&self, rule_id: &str) -> Result<()> {
+        let mut guard = self.write_state()?;
+        let rules: Vec<AlertRule> = guard.rules.iter().filter(|r| r.id != rule_id).cloned().collect();
+        if rules.len() == guard.rules.len() {
+            return Err(AppError::ConfigError(format!("Alert rule '{}' not found", rule_id)));
+        }
+        self.save_rules(&rules)?;
+        guard.rules = rules;
+        info!("Deleted alert rule '{}'", rule_id);
+        self.drop_orphaned_alerts(&mut guard);
+        self.persist(&guard)
     }
 
-    /// Returns a clone of the current alert thresholds.
-    pub fn get_thresholds(&self) -> Result<AlertThresholds> {
-        self.thresholds
+    // --- Alert Queries / Edits ---
+
+    /// Returns all currently firing alerts, oldest first.
+    pub fn get_active_alerts(&self) -> Result<Vec<Alert>> {
+        let guard = self
+            .state
             .read()
-            .map(|guard| guard.clone())
-            .map_err(|e| AppError::LockError(format!("Failed to lock thresholds for reading: {}", e)))
+            .map_err(|e| AppError::LockError(format!("Failed to lock alerts for reading: {}", e)))?;
+        let mut alerts: Vec<Alert> = guard.active.values().cloned().collect();
+        alerts.sort_by_key(|a| a.started_at);
+        Ok(alerts)
     }
 
+    /// Returns recently resolved alerts, newest last.
+    pub fn get_alert_history(&self) -> Result<Vec<Alert>> {
+        self.state
+            .read()
+            .map(|guard| guard.history.clone())
+            .map_err(|e| AppError::LockError(format!("Failed to lock alert history for reading: {}", e)))
+    }
 
-    /// Checks the given metrics against the configured thresholds and triggers alerts if needed.
+    /// Manually resolves a firing alert (e.g., the user acknowledged it).
+    /// The rule will fire again if its condition still holds for `for_secs`.
+    pub fn dismiss_alert(&self, alert_id: &str) -> Result<()> {
+        let mut guard = self.write_state()?;
+        let rule_id = guard
+            .active
+            .iter()
+            .find(|(_, a)| a.id == alert_id)
+            .map(|(rule_id, _)| rule_id.clone())
+            .ok_or_else(|| AppError::ConfigError(format!("Active alert '{}' not found", alert_id)))?;
+        if let Some(alert) = guard.active.remove(&rule_id) {
+            let last_value = alert.last_value;
+            self.resolve(&mut guard, alert, last_value, now_secs(), "dismissed");
+        }
+        self.persist(&guard)
+    }
+
+    // --- Evaluation ---
+
+    /// Evaluates all enabled rules against a metrics sample.
     pub fn check_alerts(&self, metrics: &MetricsData) {
-        // Use read lock for thresholds - allows concurrent checks if thresholds aren't being modified
-        let thresholds = match self.thresholds.read() {
+        let mut guard = match self.state.write() {
             Ok(guard) => guard,
             Err(e) => {
-                error!("Failed to read alert thresholds: {}", e);
-                return; // Cannot check alerts without thresholds
+                error!("Failed to lock alert state for evaluation: {}", e);
+                return; // Cannot check alerts without rules
             }
         };
 
         let now = metrics.timestamp; // Use timestamp from metrics data
-        let cooldown_duration = thresholds.alert_cooldown_secs;
-
-        // --- Check CPU Alert ---
-        if metrics.cpu_usage > thresholds.cpu_threshold_percent {
-            self.check_and_send_alert(
-                &self.last_cpu_alert_ts,
-                now,
-                cooldown_duration,
-                || { // Closure to generate message only if needed
-                    format!(
-                        "High CPU Usage: {:.1}% (Threshold: {:.1}%)",
-                        metrics.cpu_usage, thresholds.cpu_threshold_percent
-                    )
-                },
-            );
-        }
+        let mut changed = false;
+        let rules: Vec<AlertRule> = guard.rules.iter().filter(|r| r.enabled).cloned().collect();
 
-        // --- Check Memory Alert ---
-        // Avoid division by zero if system_memory_total is 0
-        if metrics.system_memory_total > 0 {
-            let memory_percent =
-                (metrics.memory_usage as f64 / metrics.system_memory_total as f64 * 100.0) as f32; // Use f64 for intermediate calc
-            if memory_percent > thresholds.memory_threshold_percent {
-                self.check_and_send_alert(
-                    &self.last_memory_alert_ts,
-                    now,
-                    cooldown_duration,
-                    || {
-                        format!(
-                            "High Memory Usage: {:.1}% ({:.1} MiB / {:.1} MiB) (Threshold: {:.1}%)",
-                            memory_percent,
-                            metrics.memory_usage as f64 / 1024.0 / 1024.0,
-                            metrics.system_memory_total as f64 / 1024.0 / 1024.0,
-                            thresholds.memory_threshold_percent
-                        )
-                    }
-                );
+        for rule in rules {
+            // Missing values (no TPS, no JVM metrics...) neither fire nor resolve, but a
+            // condition only counts as lasting while it is observed
+            let Some(value) = rule.metric.extract(metrics) else {
+                guard.pending.remove(&rule.id);
+                continue;
+            };
+
+            if let Some(mut alert) = guard.active.remove(&rule.id) {
+                let recovery = rule.recovery_threshold.unwrap_or(rule.threshold);
+                if rule.comparison.matches(value, recovery) {
+                    alert.last_value = value; // Still firing
+                    guard.active.insert(rule.id.clone(), alert);
+                } else {
+                    self.resolve(&mut guard, alert, value, now, "recovered");
+                    changed = true;
+                }
+                continue;
             }
-        } else {
-            // Log warning if total memory is unknown
-            // This check should only happen once ideally
-            warn!("Cannot calculate memory percentage: system_memory_total is zero.");
-        }
 
+            if !rule.comparison.matches(value, rule.threshold) {
+                guard.pending.remove(&rule.id);
+                continue;
+            }
 
-        // --- Check Player Count Alert ---
-        // Ensure max_players is valid to avoid nonsensical alerts
-        if metrics.max_players > 0 && metrics.player_count >= thresholds.player_threshold_count {
-            self.check_and_send_alert(
-                &self.last_player_alert_ts,
-                now,
-                cooldown_duration,
-                || {
-                    format!(
-                        "Server Almost Full: {} / {} players (Threshold: {})",
-                        metrics.player_count, metrics.max_players, thresholds.player_threshold_count
-                    )
-                }
-            );
+            let since = *guard.pending.entry(rule.id.clone()).or_insert(now);
+            if now.saturating_sub(since) >= rule.for_secs {
+                guard.pending.remove(&rule.id);
+                let alert = build_alert(&rule, value, now);
+                info!("Alert firing: {}", alert.message);
+                self.send_alert_event(&alert);
+                guard.active.insert(rule.id.clone(), alert);
+                changed = true;
+            } else {
+                debug!("Alert rule '{}' pending for {}s (needs {}s)", rule.id, now - since, rule.for_secs);
+            }
+        }
+
+        if changed {
+            if let Err(e) = self.persist(&guard) {
+                error!("Failed to persist alerts: {}", e);
+            }
         }
     }
 
-    /// Helper function to check cooldown and send an alert message.
-    fn check_and_send_alert<F>(
-        &self,
-        last_alert_mutex: &Mutex<Option<u64>>,
-        current_timestamp: u64,
-        cooldown_secs: u64,
-        message_fn: F, // Use a closure to generate message lazily
-    ) where
-        F: FnOnce() -> String,
-    {
-        let mut last_alert_ts_guard = match last_alert_mutex.lock() {
+    /// Resolves all firing alerts and forgets pending conditions once the server is stopped;
+    /// nothing is measured until it starts again. Does nothing if no alert is firing.
+    pub fn server_stopped(&self) {
+        let mut guard = match self.write_state() {
             Ok(guard) => guard,
             Err(e) => {
-                error!("Failed to lock alert timestamp: {}", e);
+                error!("{}", e);
                 return;
             }
         };
+        guard.pending.clear();
+        if guard.active.is_empty() {
+            return;
+        }
+        let now = now_secs();
+        let alerts: Vec<Alert> = guard.active.drain().map(|(_, alert)| alert).collect();
+        for alert in alerts {
+            let last_value = alert.last_value;
+            self.resolve(&mut guard, alert, last_value, now, "server stopped");
+        }
+        if let Err(e) = self.persist(&guard) {
+            error!("Failed to persist alerts: {}", e);
+        }
+    }
 
-        let should_alert = match *last_alert_ts_guard {
-            Some(last_ts) => (current_timestamp > last_ts) && (current_timestamp - last_ts >= cooldown_secs),
-            None => true, // Alert if never alerted before
-        };
+    /// Marks an alert as resolved, emits the event and moves it to the history.
+    fn resolve(&self, state: &mut AlertEngineState, mut alert: Alert, value: f64, now: u64, reason: &str) {
+        alert.state = AlertState::Resolved;
+        alert.last_value = value;
+        alert.resolved_at = Some(now);
+        alert.message = format!(
+            "Resolved ({}): {} (value {:.1}, fired at {:.1})",
+            reason, alert.rule_name, value, alert.value
+        );
+        info!("Alert resolved: {}", alert.message);
+        self.send_alert_event(&alert);
 
-        if should_alert {
-            let message = message_fn(); // Generate the message only now
-            info!("Triggering Alert: {}", message); // Log the alert
-            self.send_alert_event(&message); // Send the event
-            *last_alert_ts_guard = Some(current_timestamp); // Update last alert time
+        state.history.push(alert);
+        if state.history.len() > MAX_ALERT_HISTORY {
+            let excess = state.history.len() - MAX_ALERT_HISTORY;
+            state.history.drain(..excess);
         }
     }
 
-    /// Sends an alert event and a corresponding warning log event.
-    fn send_alert_event(&self, message: &str) {
+    /// Resolves active alerts and clears pending state of rules that were deleted or disabled.
+    fn drop_orphaned_alerts(&self, state: &mut AlertEngineState) {
+        let enabled: Vec<String> = state.rules.iter().filter(|r| r.enabled).map(|r| r.id.clone()).collect();
+        state.pending.retain(|rule_id, _| enabled.contains(rule_id));
+        let orphaned: Vec<String> = state
+            .active
+            .keys()
+            .filter(|rule_id| !enabled.contains(rule_id))
+            .cloned()
+            .collect();
+        for rule_id in orphaned {
+            if let Some(alert) = state.active.remove(&rule_id) {
+                let last_value = alert.last_value;
+                self.resolve(state, alert, last_value, now_secs(), "rule removed");
+            }
+        }
+    }
+
+    fn write_state(&self) -> Result<std::sync::RwLockWriteGuard<'_, AlertEngineState>> {
+        self.state
+            .write()
+            .map_err(|e| AppError::LockError(format!("Failed to lock alert state for writing: {}", e)))
+    }
+
+    /// Stores the rules in the manager config. Called before the in-memory rules change.
+    fn save_rules(&self, rules: &[AlertRule]) -> Result<()> {
+        self.config_store.update(|config| {
+            config.alert_rules = rules.to_vec();
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    /// Writes active alerts and history to disk atomically.
+    fn persist(&self, state: &AlertEngineState) -> Result<()> {
+        let file = AlertsFile {
+            version: ALERTS_FILE_VERSION,
+            active: state.active.values().cloned().collect(),
+            history: state.history.clone(),
+            rules: None,
+        };
+        let json = serde_json::to_string_pretty(&file)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize alerts: {}", e)))?;
+        fs_utils::write_string_to_file_atomic(&self.storage_path, &json)?;
+        debug!("Persisted alerts to {}", self.storage_path.display());
+        Ok(())
+    }
+
+    /// Sends an alert event and a corresponding log event.
+    fn send_alert_event(&self, alert: &Alert) {
         // Use helpers from api::events
-        // Alert event (specific type for UI filtering?)
-        emit_event(events::Event::Alert(message.to_string()));
-        // Also send as a standard log message
-        emit_log(LogLevel::Warn, message.to_string(), "AlertManager".to_string());
+        emit_event(events::Event::Alert(alert.clone()));
+        let level = match (alert.state, alert.severity) {
+            (AlertState::Resolved, _) => LogLevel::Info,
+            (AlertState::Firing, AlertSeverity::Critical) => LogLevel::Error,
+            (AlertState::Firing, _) => LogLevel::Warn,
+        };
+        emit_log(level, alert.message.clone(), "AlertManager".to_string());
+    }
+}
+
+/// Validates a rule and fills in a generated id if missing.
+fn normalize_rule(mut rule: AlertRule) -> Result<AlertRule> {
+    rule.name = rule.name.trim().to_string();
+    if rule.name.is_empty() {
+        return Err(AppError::ConfigError("Alert rule name cannot be empty".to_string()));
+    }
+    if !rule.threshold.is_finite() || rule.recovery_threshold.map_or(false, |r| !r.is_finite()) {
+        return Err(AppError::ConfigError(format!("Alert rule '{}' has an invalid threshold", rule.name)));
     }
-}
\ No newline at end of file
+    if let Some(recovery) = rule.recovery_threshold {
+        // The recovery threshold must be on the "healthy" side of the firing threshold
+        let valid = match rule.comparison {
+            Comparison::GreaterThan | Comparison::GreaterOrEqual => recovery <= rule.threshold,
+            Comparison::LessThan | Comparison::LessOrEqual => recovery >= rule.threshold,
+        };
+        if !valid {
+            return Err(AppError::ConfigError(format!(
+                "Alert rule '{}': recovery threshold {} must not be past the firing threshold {}",
+                rule.name, recovery, rule.threshold
+            )));
+        }
+    }
+    if rule.id.trim().is_empty() {
+        let slug: String = rule
+            .name
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+            .collect();
+        rule.id = format!("{}-{:04x}", slug.trim_matches('-'), rand::random::<u16>());
+    }
+    Ok(rule)
+}
+
+/// Creates a firing alert instance for a rule.
+fn build_alert(rule: &AlertRule, value: f64, now: u64) -> Alert {
+    Alert {
+        id: format!("{}-{}-{:04x}", rule.id, now, rand::random::<u16>()),
+        rule_id: rule.id.clone(),
+        rule_name: rule.name.clone(),
+        metric: rule.metric,
+        severity: rule.severity,
+        state: AlertState::Firing,
+        comparison: rule.comparison,
+        threshold: rule.threshold,
+        value,
+        last_value: value,
+        started_at: now,
+        resolved_at: None,
+        message: format!(
+            "{}: {:.1} {} {:.1}{}",
+            rule.name,
+            value,
+            rule.comparison.symbol(),
+            rule.threshold,
+            if rule.for_secs > 0 { format!(" for {}s", rule.for_secs) } else { String::new() }
+        ),
+    }
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,457 @@
+// src/monitoring/jvm_metrics.rs
+
+use crate::models::metrics::{JvmMetrics, JvmMetricsSource};
+use lazy_static::lazy_static;
+use log::{debug, info, trace, warn};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Location of the GC log relative to the server directory. Used by the launcher and the tailer.
+pub const GC_LOG_RELATIVE_PATH: &str = "logs/gc.log";
+/// How often `jstat` is polled when no GC log is available (it spawns a process each time).
+const JSTAT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// How long the GC log may stay silent before we consider `jstat` instead.
+const GC_LOG_STALE_AFTER: Duration = Duration::from_secs(30);
+
+lazy_static! {
+    // Unified logging uptime decorator, e.g. "[12.345s]"
+    static ref UPTIME_REGEX: Regex = Regex::new(r"^\[(\d+(?:\.\d+)?)s\]").unwrap();
+    // Pause summary line, e.g.:
+    // "[12.345s][info][gc] GC(3) Pause Young (Normal) (G1 Evacuation Pause) 120M->40M(512M) 3.456ms"
+    // "[98.001s][info][gc] GC(9) Pause Full (System.gc()) 480M->300M(512M) 250.123ms"
+    // The parenthesised causes may nest one level, as in "(System.gc())".
+    // Captures: 1: Pause kind, 2/3: before, 4/5: after, 6/7: committed, 8: duration ms
+    static ref GC_PAUSE_REGEX: Regex = Regex::new(
+        r"\[gc\s*\] GC\(\d+\) Pause (Young|Full|Mixed|Remark|Cleanup)(?:\s*\((?:[^()]|\([^()]*\))*\))*\s*(\d+)([KMG])->(\d+)([KMG])\((\d+)([KMG])\) (\d+(?:\.\d+)?)ms"
+    ).unwrap();
+    // "[0.010s][info][gc,init] Heap Max Capacity: 2G" (JDK 17+) or "Maximum heap size: 2G"
+    static ref HEAP_MAX_REGEX: Regex = Regex::new(r"(?:Heap Max Capacity|Maximum heap size): (\d+)([KMG])").unwrap();
+    // "[0.010s][info][gc,init] Heap Region Size: 1M"
+    static ref REGION_SIZE_REGEX: Regex = Regex::new(r"Heap Region Size: (\d+)([KMG])").unwrap();
+    // "[12.345s][info][gc,heap] GC(3) Old regions: 10->12"
+    static ref OLD_REGIONS_REGEX: Regex = Regex::new(r"GC\(\d+\) Old regions: \d+->(\d+)").unwrap();
+    // "[12.345s][info][gc,heap] GC(3) ParOldGen: 81920K->90112K(175104K)" (Parallel GC)
+    static ref OLD_GEN_REGEX: Regex = Regex::new(r"GC\(\d+\) (?:ParOldGen|Tenured|PSOldGen): \d+[KMG]->(\d+)([KMG])").unwrap();
+}
+
+/// Returns the JVM arguments that make the server write a GC log the tailer understands.
+/// Requires Java 9+ (unified logging); callers should check the Java version first.
+pub fn gc_log_jvm_args() -> Vec<String> {
+    vec![format!(
+        "-Xlog:gc*:file={}:uptime,level,tags:filecount=5,filesize=10M",
+        GC_LOG_RELATIVE_PATH
+    )]
+}
+
+/// Converts a value with a HotSpot size suffix (K, M, G) into bytes.
+fn size_to_bytes(value: &str, unit: &str) -> u64 {
+    let value = value.parse::<u64>().unwrap_or(0);
+    match unit {
+        "K" => value * 1024,
+        "M" => value * 1024 * 1024,
+        "G" => value * 1024 * 1024 * 1024,
+        _ => value,
+    }
+}
+
+/// Collects JVM heap and GC metrics for the running server.
+///
+/// Prefers tailing the GC log written by `-Xlog:gc*` (cheap, no extra processes).
+/// Falls back to polling `jstat -gc` from the JDK the server runs on when the log
+/// is missing or has stopped growing (e.g., the user removed the flag). `poll` must be given
+/// the PID of the JVM itself, not of a wrapper script that launched it.
+pub struct JvmMetricsCollector {
+    /// Path to the GC log file (server_dir/logs/gc.log).
+    gc_log_path: PathBuf,
+    /// Path to the `jstat` executable next to the `java` the server runs on, if it exists.
+    jstat_path: Option<PathBuf>,
+    /// Byte offset up to which the GC log has been consumed.
+    gc_log_offset: u64,
+    /// Last time a GC log line was parsed.
+    last_gc_log_activity: Option<Instant>,
+    /// Last time jstat was polled.
+    last_jstat_poll: Option<Instant>,
+    /// G1 region size, needed to convert "Old regions" counts into bytes.
+    region_size_bytes: Option<u64>,
+    /// Heap occupancy after the previous pause, and the JVM uptime it was logged at.
+    previous_pause: Option<(f64, u64)>,
+    /// Eden usage/capacity and young GC count from the previous jstat poll.
+    previous_jstat: Option<(Instant, f64, f64, u64)>,
+    /// Latest known metrics, updated incrementally.
+    current: JvmMetrics,
+}
+
+impl JvmMetricsCollector {
+    /// Creates a collector for the server in `server_directory`. No `jstat` is used until
+    /// `reset` names the Java the server runs on.
+    pub fn new(server_directory: &Path) -> Self {
+        Self {
+            gc_log_path: server_directory.join(GC_LOG_RELATIVE_PATH),
+            jstat_path: None,
+            gc_log_offset: 0,
+            last_gc_log_activity: None,
+            last_jstat_poll: None,
+            region_size_bytes: None,
+            previous_pause: None,
+            previous_jstat: None,
+            current: JvmMetrics::default(),
+        }
+    }
+
+    /// Forgets all state. Called when a new server JVM is detected, with the `java` executable
+    /// it runs from; `jstat` is taken from the same JDK.
+    /// The GC log is read from the start since the JVM truncates it on startup.
+    pub fn reset(&mut self, java_path: &Path) {
+        debug!("Resetting JVM metrics collector state.");
+        self.jstat_path = find_jstat(java_path);
+        if self.jstat_path.is_none() {
+            info!("jstat not found next to {}; JVM metrics rely on the GC log only.", java_path.display());
+        }
+        self.gc_log_offset = 0;
+        self.last_gc_log_activity = None;
+        self.last_jstat_poll = None;
+        self.region_size_bytes = None;
+        self.previous_pause = None;
+        self.previous_jstat = None;
+        self.current = JvmMetrics::default();
+    }
+
+    /// Returns the latest JVM metrics for the process `pid`, or None if no source is available yet.
+    pub fn poll(&mut self, pid: u32) -> Option<JvmMetrics> {
+        let log_updated = match self.tail_gc_log() {
+            Ok(updated) => updated,
+            Err(e) => {
+                trace!("GC log not readable ({}): {}", self.gc_log_path.display(), e);
+                false
+            }
+        };
+
+        let gc_log_fresh = self
+            .last_gc_log_activity
+            .map_or(false, |at| at.elapsed() < GC_LOG_STALE_AFTER);
+
+        if !log_updated && !gc_log_fresh && self.jstat_path.is_some() {
+            let due = self
+                .last_jstat_poll
+                .map_or(true, |at| at.elapsed() >= JSTAT_POLL_INTERVAL);
+            if due {
+                self.last_jstat_poll = Some(Instant::now());
+                if let Err(e) = self.poll_jstat(pid) {
+                    debug!("jstat poll for PID {} failed: {}", pid, e);
+                }
+            }
+        }
+
+        self.current.source.map(|_| self.current.clone())
+    }
+
+    /// Reads any new lines from the GC log. Returns true if at least one line was parsed.
+    fn tail_gc_log(&mut self) -> std::io::Result<bool> {
+        let mut file = File::open(&self.gc_log_path)?;
+        let length = file.metadata()?.len();
+        if length < self.gc_log_offset {
+            // The log was rotated or truncated; start over from the beginning
+            debug!("GC log shrank ({} < {}), restarting from offset 0.", length, self.gc_log_offset);
+            self.gc_log_offset = 0;
+        }
+        if length == self.gc_log_offset {
+            return Ok(false);
+        }
+
+        file.seek(SeekFrom::Start(self.gc_log_offset))?;
+        let mut reader = BufReader::new(file);
+        let mut line = String::new();
+        let mut parsed_any = false;
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            // Only consume complete lines; a partial line will be re-read next cycle
+            if read == 0 || !line.ends_with('\n') {
+                break;
+            }
+            self.gc_log_offset += read as u64;
+            self.parse_gc_log_line(line.trim_end());
+            parsed_any = true;
+        }
+
+        if parsed_any {
+            self.last_gc_log_activity = Some(Instant::now());
+        }
+        Ok(parsed_any)
+    }
+
+    /// Updates the current metrics from a single unified-logging GC line.
+    fn parse_gc_log_line(&mut self, line: &str) {
+        if let Some(caps) = HEAP_MAX_REGEX.captures(line) {
+            self.current.heap_max_bytes = Some(size_to_bytes(&caps[1], &caps[2]));
+            return;
+        }
+        if let Some(caps) = REGION_SIZE_REGEX.captures(line) {
+            self.region_size_bytes = Some(size_to_bytes(&caps[1], &caps[2]));
+            return;
+        }
+        if let Some(caps) = OLD_REGIONS_REGEX.captures(line) {
+            if let (Some(region_size), Ok(regions)) = (self.region_size_bytes, caps[1].parse::<u64>()) {
+                self.set_old_gen_used(regions * region_size);
+            }
+            return;
+        }
+        if let Some(caps) = OLD_GEN_REGEX.captures(line) {
+            self.set_old_gen_used(size_to_bytes(&caps[1], &caps[2]));
+            return;
+        }
+
+        let Some(caps) = GC_PAUSE_REGEX.captures(line) else { return };
+        let before = size_to_bytes(&caps[2], &caps[3]);
+        let after = size_to_bytes(&caps[4], &caps[5]);
+        let committed = size_to_bytes(&caps[6], &caps[7]);
+        let pause_ms = caps[8].parse::<f64>().unwrap_or(0.0);
+        let uptime_secs = UPTIME_REGEX
+            .captures(line)
+            .and_then(|u| u[1].parse::<f64>().ok());
+
+        let metrics = &mut self.current;
+        metrics.source = Some(JvmMetricsSource::GcLog);
+        metrics.heap_used_bytes = after;
+        metrics.heap_committed_bytes = committed;
+        metrics.gc_pause_total_ms += pause_ms;
+        metrics.last_gc_pause_ms = Some(pause_ms);
+
+        if &caps[1] == "Full" {
+            metrics.full_gc_count += 1;
+            if let Some(max) = metrics.heap_max_bytes.filter(|max| *max > 0) {
+                metrics.heap_after_full_gc_percent = Some((after as f64 / max as f64 * 100.0) as f32);
+            }
+        } else if &caps[1] != "Remark" && &caps[1] != "Cleanup" {
+            metrics.young_gc_count += 1;
+        }
+
+        // Allocation rate: what was allocated between the previous pause and this one
+        if let Some(uptime) = uptime_secs {
+            if let Some((previous_uptime, previous_after)) = self.previous_pause {
+                let elapsed = uptime - previous_uptime;
+                if elapsed > 0.0 {
+                    let allocated = before.saturating_sub(previous_after);
+                    metrics.allocation_rate_bytes_per_sec = (allocated as f64 / elapsed) as u64;
+                }
+            }
+            self.previous_pause = Some((uptime, after));
+        }
+        trace!("GC pause parsed: {:?}", metrics);
+    }
+
+    fn set_old_gen_used(&mut self, bytes: u64) {
+        self.current.old_gen_used_bytes = Some(bytes);
+        self.current.old_gen_occupancy_percent = self
+            .current
+            .heap_max_bytes
+            .filter(|max| *max > 0)
+            .map(|max| (bytes as f64 / max as f64 * 100.0) as f32);
+    }
+
+    /// Polls `jstat -gc` and `jstat -gccapacity` for the process and updates the current metrics.
+    fn poll_jstat(&mut self, pid: u32) -> std::result::Result<(), String> {
+        let jstat = self.jstat_path.clone().ok_or_else(|| "jstat not available".to_string())?;
+        let gc = run_jstat(&jstat, "-gc", pid)?;
+        let capacity = run_jstat(&jstat, "-gccapacity", pid).ok();
+
+        // All jstat sizes are in KB
+        let kb = |columns: &HashMap<String, f64>, name: &str| -> f64 {
+            columns.get(name).copied().unwrap_or(0.0) * 1024.0
+        };
+        let used = kb(&gc, "S0U") + kb(&gc, "S1U") + kb(&gc, "EU") + kb(&gc, "OU");
+        let committed = kb(&gc, "S0C") + kb(&gc, "S1C") + kb(&gc, "EC") + kb(&gc, "OC");
+        let young_gc_count = gc.get("YGC").copied().unwrap_or(0.0) as u64;
+        let full_gc_count = gc.get("FGC").copied().unwrap_or(0.0) as u64;
+
+        let metrics = &mut self.current;
+        metrics.source = Some(JvmMetricsSource::Jstat);
+        metrics.heap_used_bytes = used as u64;
+        metrics.heap_committed_bytes = committed as u64;
+        metrics.old_gen_used_bytes = Some(kb(&gc, "OU") as u64);
+        if let Some(capacity) = capacity {
+            let max = kb(&capacity, "NGCMX") + kb(&capacity, "OGCMX");
+            if max > 0.0 {
+                metrics.heap_max_bytes = Some(max as u64);
+            }
+        }
+        if let Some(max) = metrics.heap_max_bytes.filter(|max| *max > 0) {
+            metrics.old_gen_occupancy_percent = Some((kb(&gc, "OU") / max as f64 * 100.0) as f32);
+            if full_gc_count > metrics.full_gc_count {
+                // jstat cannot see the heap right after the full GC; old gen right after is the closest
+                metrics.heap_after_full_gc_percent = metrics.old_gen_occupancy_percent;
+            }
+        }
+        let total_gc_ms = gc.get("GCT").copied().unwrap_or(0.0) * 1000.0;
+        let previous_total_ms = metrics.gc_pause_total_ms;
+        let new_collections = (young_gc_count + full_gc_count)
+            .saturating_sub(metrics.young_gc_count + metrics.full_gc_count);
+        if new_collections > 0 {
+            metrics.last_gc_pause_ms = Some((total_gc_ms - previous_total_ms).max(0.0) / new_collections as f64);
+        }
+        metrics.gc_pause_total_ms = total_gc_ms;
+        metrics.young_gc_count = young_gc_count;
+        metrics.full_gc_count = full_gc_count;
+
+        // Allocation rate from eden growth; a young GC empties eden, so count the part that was collected
+        let eden_used = kb(&gc, "EU");
+        let eden_capacity = kb(&gc, "EC");
+        let now = Instant::now();
+        if let Some((previous_at, previous_eden_used, previous_eden_capacity, previous_young)) = self.previous_jstat {
+            let elapsed = now.duration_since(previous_at).as_secs_f64();
+            if elapsed > 0.0 {
+                let allocated = if young_gc_count > previous_young {
+                    (previous_eden_capacity - previous_eden_used).max(0.0) + eden_used
+                } else {
+                    (eden_used - previous_eden_used).max(0.0)
+                };
+                metrics.allocation_rate_bytes_per_sec = (allocated / elapsed) as u64;
+            }
+        }
+        self.previous_jstat = Some((now, eden_used, eden_capacity, young_gc_count));
+        Ok(())
+    }
+}
+
+/// Returns the `jstat` in the same directory as `java_path`. Symlinks such as `/usr/bin/java`
+/// are followed, since the JDK tools only sit next to the real executable.
+fn find_jstat(java_path: &Path) -> Option<PathBuf> {
+    let jstat_name = if cfg!(target_os = "windows") { "jstat.exe" } else { "jstat" };
+    let resolved = std::fs::canonicalize(java_path).ok();
+    std::iter::once(java_path)
+        .chain(resolved.as_deref())
+        .filter_map(|java| java.parent().map(|bin| bin.join(jstat_name)))
+        .find(|path| path.exists())
+}
+
+/// Runs `jstat <option> <pid>` and returns the header/value pairs of its single output row.
+fn run_jstat(jstat: &Path, option: &str, pid: u32) -> std::result::Result<HashMap<String, f64>, String> {
+    let output = Command::new(jstat)
+        .arg(option)
+        .arg(pid.to_string())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .output()
+        .map_err(|e| format!("Failed to execute {}: {}", jstat.display(), e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        warn!("jstat {} {} failed: {}", option, pid, stderr.trim());
+        return Err(format!("jstat exited with {:?}", output.status.code()));
+    }
+
+    parse_jstat_output(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parses `jstat` output: a header row and a value row, separated by whitespace.
+fn parse_jstat_output(stdout: &str) -> std::result::Result<HashMap<String, f64>, String> {
+    let mut lines = stdout.lines();
+    let headers: Vec<&str> = lines.next().unwrap_or("").split_whitespace().collect();
+    let values: Vec<&str> = lines.next().unwrap_or("").split_whitespace().collect();
+    if headers.is_empty() || headers.len() != values.len() {
+        return Err(format!("Unexpected jstat output: {}", stdout.trim()));
+    }
+    Ok(headers
+        .into_iter()
+        .zip(values)
+        // Columns not supported by the current collector are printed as "-"
+        .filter_map(|(header, value)| value.parse::<f64>().ok().map(|v| (header.to_string(), v)))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collector() -> JvmMetricsCollector {
+        JvmMetricsCollector::new(Path::new("/nonexistent"))
+    }
+
+    const MB: u64 = 1024 * 1024;
+
+    #[test]
+    fn parses_young_pause_and_allocation_rate() {
+        let mut collector = collector();
+        collector.parse_gc_log_line("[0.010s][info][gc,init] Heap Max Capacity: 2G");
+        collector.parse_gc_log_line(
+            "[10.000s][info][gc] GC(0) Pause Young (Normal) (G1 Evacuation Pause) 100M->20M(512M) 4.000ms",
+        );
+        collector.parse_gc_log_line(
+            "[12.000s][info][gc] GC(1) Pause Young (Normal) (G1 Evacuation Pause) 120M->40M(512M) 6.000ms",
+        );
+
+        let metrics = &collector.current;
+        assert_eq!(metrics.source, Some(JvmMetricsSource::GcLog));
+        assert_eq!(metrics.heap_max_bytes, Some(2048 * MB));
+        assert_eq!(metrics.heap_used_bytes, 40 * MB);
+        assert_eq!(metrics.heap_committed_bytes, 512 * MB);
+        assert_eq!(metrics.young_gc_count, 2);
+        assert_eq!(metrics.full_gc_count, 0);
+        assert_eq!(metrics.gc_pause_total_ms, 10.0);
+        assert_eq!(metrics.last_gc_pause_ms, Some(6.0));
+        // 100M allocated (20M -> 120M) over 2 seconds
+        assert_eq!(metrics.allocation_rate_bytes_per_sec, 50 * MB);
+    }
+
+    #[test]
+    fn parses_full_pause_with_nested_cause() {
+        let mut collector = collector();
+        collector.parse_gc_log_line("[0.010s][info][gc,init] Heap Max Capacity: 1G");
+        collector.parse_gc_log_line(
+            "[98.001s][info][gc] GC(9) Pause Full (System.gc()) 480M->256M(512M) 250.500ms",
+        );
+
+        let metrics = &collector.current;
+        assert_eq!(metrics.full_gc_count, 1);
+        assert_eq!(metrics.young_gc_count, 0);
+        assert_eq!(metrics.last_gc_pause_ms, Some(250.5));
+        assert_eq!(metrics.heap_after_full_gc_percent, Some(25.0));
+    }
+
+    #[test]
+    fn parses_old_generation_from_regions_and_parallel_gc() {
+        let mut collector = collector();
+        collector.parse_gc_log_line("[0.010s][info][gc,init] Heap Max Capacity: 100M");
+        collector.parse_gc_log_line("[0.010s][info][gc,init] Heap Region Size: 1M");
+        collector.parse_gc_log_line("[5.000s][info][gc,heap] GC(3) Old regions: 10->12");
+        assert_eq!(collector.current.old_gen_used_bytes, Some(12 * MB));
+        assert_eq!(collector.current.old_gen_occupancy_percent, Some(12.0));
+
+        collector.parse_gc_log_line("[6.000s][info][gc,heap] GC(4) ParOldGen: 81920K->51200K(175104K)");
+        assert_eq!(collector.current.old_gen_used_bytes, Some(50 * MB));
+    }
+
+    #[test]
+    fn ignores_unrelated_gc_log_lines() {
+        let mut collector = collector();
+        collector.parse_gc_log_line("[0.005s][info][gc] Using G1");
+        collector.parse_gc_log_line("[12.345s][info][gc,phases] GC(3)   Pre Evacuate Collection Set: 0.1ms");
+        assert_eq!(collector.current.source, None);
+    }
+
+    #[test]
+    fn parses_jstat_output() {
+        let output = "\
+ S0C    S1C    S0U    S1U      EC       EU        OC         OU       MC     MU    CCSC   CCSU   YGC     YGCT    FGC    FGCT     CGC    CGCT     GCT
+ 0.0   4096.0  0.0   4096.0 61440.0  20480.0   65536.0    32768.0  50000.0 49000.0 6000.0 5500.0     12    0.120   1      0.300     -        -    0.420
+";
+        let columns = parse_jstat_output(output).unwrap();
+        assert_eq!(columns["EU"], 20480.0);
+        assert_eq!(columns["OU"], 32768.0);
+        assert_eq!(columns["YGC"], 12.0);
+        assert_eq!(columns["GCT"], 0.42);
+        // Unsupported columns ("-") are left out
+        assert!(!columns.contains_key("CGC"));
+    }
+
+    #[test]
+    fn rejects_malformed_jstat_output() {
+        assert!(parse_jstat_output("").is_err());
+        assert!(parse_jstat_output("S0C S1C\n1.0\n").is_err());
+        assert!(parse_jstat_output("12345 not found\n").is_err());
+    }
+}
@@ -1,39 +1,35 @@
 ﻿use crate::app_state::AppState;
 use crate::error::Result; // For potential future use
 use crate::models::metrics::MetricsData;
+use crate::monitoring::metrics_store::{MetricsRange, MetricsResolution, MetricsStore};
 use log::{debug, error, info, warn};
-use serde_json; // For serialization
 use std::collections::VecDeque;
-use std::fs::{self, File}; // Need fs for create_dir_all
-use std::io::{self, Write};
+use std::fs; // Need fs for create_dir_all
 use std::path::PathBuf;
-use std::sync::Arc;
-use std::time::{Duration, Instant}; // Use Instant for elapsed time
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 /// Maximum number of metrics entries to keep in memory (e.g., 1 hour worth).
+/// Only used for quick averages; long-term history lives in the `MetricsStore`.
 const MAX_HISTORY_SIZE: usize = 3600;
-/// How often to persist the metrics history to a file.
-const PERSIST_INTERVAL: Duration = Duration::from_secs(300); // 5 minutes
 
 /// Collects and stores a history of server metrics.
-/// Also handles persisting metrics to disk periodically.
+/// Every sample is appended to an on-disk `MetricsStore` with downsampled retention tiers.
 pub struct MetricsCollector {
     // No AppState needed if log path is generated differently or passed in
     // state: Arc<AppState>,
     /// In-memory buffer holding recent metrics data.
     history: Mutex<VecDeque<MetricsData>>, // Wrap history in Mutex for thread safety
-    /// Timestamp of the last time metrics were persisted to disk.
-    last_persisted: Mutex<Instant>,
-    /// Path to the directory where metrics logs should be stored.
-    log_directory: PathBuf,
+    /// Append-only on-disk store (raw 1s / 1m / 1h tiers).
+    store: MetricsStore,
 }
 
 impl MetricsCollector {
     /// Creates a new MetricsCollector.
     pub fn new(log_directory: PathBuf) -> Self { // Removed AppState dependency
         info!(
-            "Initializing MetricsCollector. History size: {}, Persist interval: {:?}",
-            MAX_HISTORY_SIZE, PERSIST_INTERVAL
+            "Initializing MetricsCollector. In-memory history size: {}",
+            MAX_HISTORY_SIZE
         );
         // Ensure log directory exists
         if !log_directory.exists() {
@@ -47,15 +43,19 @@ impl MetricsCollector {
         Self {
             // state,
             history: Mutex::new(VecDeque::with_capacity(MAX_HISTORY_SIZE)),
-            last_persisted: Mutex::new(Instant::now()),
-            log_directory,
+            store: MetricsStore::new(log_directory.join("metrics")),
         }
     }
 
     /// Adds a new metrics data point to the history.
     /// Trims old data if history exceeds `MAX_HISTORY_SIZE`.
-    /// Triggers persistence check.
+    /// Appends the sample to the on-disk store (incremental, no rewrites).
     pub fn add_metrics(&self, metrics: MetricsData) -> Result<()> {
+        if let Err(e) = self.store.append(&metrics) {
+            // Keep the in-memory history working even if the disk is full or read-only
+            error!("Failed to append metrics to store: {}", e);
+        }
+
         let mut history_guard = self
             .history
             .lock()
@@ -68,38 +68,19 @@ impl MetricsCollector {
         while history_guard.len() > MAX_HISTORY_SIZE {
             history_guard.pop_front();
         }
-
-        // Release history lock before checking persistence lock
-        drop(history_guard);
-
-        // Check if it's time to persist metrics
-        let should_persist = {
-            let last_persisted_guard = self.last_persisted.lock().map_err(|e| {
-                AppError::LockError(format!("Failed to lock last_persisted time: {}", e))
-            })?;
-            last_persisted_guard.elapsed() >= PERSIST_INTERVAL
-        }; // Lock released
-
-        if should_persist {
-            info!("Persist interval reached. Persisting metrics...");
-            match self.persist_metrics() {
-                Ok(_) => {
-                    // Update last persisted time only on success
-                    let mut last_persisted_guard = self.last_persisted.lock().map_err(|e| {
-                        AppError::LockError(format!("Failed to lock last_persisted time after persist: {}", e))
-                    })?;
-                    *last_persisted_guard = Instant::now(); // Reset timer
-                    info!("Metrics persisted successfully.");
-                }
-                Err(e) => {
-                    error!("Failed to persist metrics: {}", e);
-                    // Don't update last_persisted time, try again next time
-                }
-            }
-        }
         Ok(())
     }
 
+    /// Writes partially filled rollup buckets to disk. Called when the server stops.
+    pub fn flush(&self) -> Result<()> {
+        self.store.flush()
+    }
+
+    /// Returns persisted metrics between `from` and `to` (UNIX seconds) at the given resolution.
+    pub fn get_metrics_range(&self, from: u64, to: u64, resolution: MetricsResolution) -> Result<MetricsRange> {
+        self.store.query_range(from, to, resolution)
+    }
+
     /// Returns a clone of the entire metrics history.
     /// Potentially memory-intensive if history is large.
     pub fn get_history(&self) -> Result<Vec<MetricsData>> {
@@ -153,64 +134,35 @@ impl MetricsCollector {
             }
         });
 
+        // Average JVM heap usage only over samples that carry JVM metrics
+        let (sum_heap, heap_count) = relevant_metrics.iter().fold((0u64, 0u64), |(sum, count), m| {
+            match &m.jvm {
+                Some(jvm) => (sum + jvm.heap_used_bytes, count + 1),
+                None => (sum, count),
+            }
+        });
+
         let avg_cpu = sum_cpu / count_f32;
         let avg_memory = sum_memory / count_u64;
         let avg_tps = if tps_count > 0 { Some(sum_tps / tps_count as f32) } else { None };
 
+        // GC counters and pause totals are cumulative, so the latest values are kept as-is
+        let avg_jvm = latest_metric.jvm.clone().map(|mut jvm| {
+            if heap_count > 0 {
+                jvm.heap_used_bytes = sum_heap / heap_count;
+            }
+            jvm
+        });
+
         // Return a new MetricsData with averaged values, using latest for others
         Ok(Some(MetricsData {
             cpu_usage: avg_cpu,
+            jvm: avg_jvm,
             memory_usage: avg_memory,
             tps: avg_tps,
             timestamp: now_ts, // Timestamp of the latest considered metric
-            // Copy other fields from the latest metric
-            system_memory_total: latest_metric.system_memory_total,
-            player_count: latest_metric.player_count, // Average player count might also be useful
-            max_players: latest_metric.max_players,
-            uptime: latest_metric.uptime,
+            // Copy other fields (players, uptime, threads, I/O rates...) from the latest metric
+            ..latest_metric.clone()
         }))
     }
-
-    /// Persists the current metrics history to a JSON file (e.g., logs/metrics_YYYYMMDD.json).
-    fn persist_metrics(&self) -> Result<()> {
-        let history_guard = self
-            .history
-            .lock()
-            .map_err(|e| AppError::LockError(format!("Failed to lock metrics history for persist: {}", e)))?;
-
-        if history_guard.is_empty() {
-            debug!("Skipping metrics persistence: History is empty.");
-            return Ok(());
-        }
-
-        // Generate filename based on current date
-        let filename = format!("metrics_{}.json", chrono::Local::now().format("%Y%m%d"));
-        let log_path = self.log_directory.join(filename);
-        debug!("Persisting metrics to: {}", log_path.display());
-
-        // Serialize the VecDeque directly
-        let json_data = serde_json::to_string_pretty(&*history_guard).map_err(|e| {
-            AppError::InternalEventError(format!("Failed to serialize metrics history: {}", e))
-            // Using InternalEventError might not be perfect, maybe a SerializationError?
-        })?;
-
-        // Write to file
-        let mut file = File::create(&log_path).map_err(|e| {
-            AppError::IoError(io::Error::new(
-                e.kind(),
-                format!("Failed to create metrics file {}: {}", log_path.display(), e),
-            ))
-        })?;
-
-        file.write_all(json_data.as_bytes()).map_err(|e| {
-            AppError::IoError(io::Error::new(
-                e.kind(),
-                format!("Failed to write metrics file {}: {}", log_path.display(), e),
-            ))
-        })?;
-
-        file.flush()?; // Ensure write completes
-
-        Ok(())
-    }
 }
\ No newline at end of file
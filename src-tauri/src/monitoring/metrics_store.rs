@@ -0,0 +1,502 @@
+// src/monitoring/metrics_store.rs
+
+use crate::error::{AppError, Result};
+use crate::models::metrics::MetricsData;
+use crate::utils::fs_utils;
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, NaiveDate, Utc};
+use log::{debug, info, trace, warn};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Raw 1-second samples are kept for one day.
+const RAW_RETENTION_DAYS: i64 = 1;
+/// 1-minute rollups are kept for a month.
+const MINUTE_RETENTION_DAYS: i64 = 31;
+/// 1-hour rollups are kept for a year.
+const HOUR_RETENTION_DAYS: i64 = 366;
+/// How often old files are pruned (checked on append, based on sample timestamps).
+const PRUNE_INTERVAL_SECS: u64 = 3600;
+
+/// Resolution of a range query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricsResolution {
+    /// Raw samples (one per monitor interval, usually 1s).
+    Raw,
+    /// 1-minute rollups.
+    Minute,
+    /// 1-hour rollups.
+    Hour,
+    /// Pick the finest resolution that keeps the result reasonably small and is still retained.
+    Auto,
+}
+
+impl MetricsResolution {
+    /// Bucket width in seconds for rollup tiers.
+    fn bucket_secs(self) -> u64 {
+        match self {
+            MetricsResolution::Minute => 60,
+            MetricsResolution::Hour => 3600,
+            MetricsResolution::Raw | MetricsResolution::Auto => 1,
+        }
+    }
+
+    /// Tier directory name below the store root.
+    fn dir_name(self) -> &'static str {
+        match self {
+            MetricsResolution::Minute => "1m",
+            MetricsResolution::Hour => "1h",
+            MetricsResolution::Raw | MetricsResolution::Auto => "raw",
+        }
+    }
+}
+
+/// Minimum, average and maximum of a metric over a rollup bucket.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct RollupStat {
+    pub min: f64,
+    pub avg: f64,
+    pub max: f64,
+}
+
+impl RollupStat {
+    fn single(value: f64) -> Self {
+        Self { min: value, avg: value, max: value }
+    }
+}
+
+/// A point in a range query result. Raw samples are returned as points with min = avg = max.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsRollup {
+    /// Start of the bucket (UNIX seconds). For raw samples, the sample timestamp.
+    pub timestamp: u64,
+    /// Bucket width in seconds (1 for raw samples).
+    pub resolution_secs: u64,
+    /// Number of raw samples aggregated into this point.
+    pub samples: u64,
+    pub cpu_usage: RollupStat,
+    pub memory_usage: RollupStat,
+    pub player_count: RollupStat,
+    /// Only present if at least one sample in the bucket had a TPS value.
+    pub tps: Option<RollupStat>,
+    /// Only present if at least one sample in the bucket had JVM metrics.
+    pub heap_used_bytes: Option<RollupStat>,
+    pub disk_read_bytes_per_sec: RollupStat,
+    pub disk_write_bytes_per_sec: RollupStat,
+    pub uptime: u64,
+}
+
+/// Result of `get_metrics_range`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsRange {
+    /// The resolution actually used (never `Auto`).
+    pub resolution: MetricsResolution,
+    pub from: u64,
+    pub to: u64,
+    pub points: Vec<MetricsRollup>,
+}
+
+/// Running min/sum/max accumulator for one optional metric.
+#[derive(Debug, Clone, Copy, Default)]
+struct StatAccumulator {
+    min: f64,
+    max: f64,
+    weighted_sum: f64,
+    weight: u64,
+}
+
+impl StatAccumulator {
+    fn add(&mut self, stat: RollupStat, weight: u64) {
+        if self.weight == 0 {
+            self.min = stat.min;
+            self.max = stat.max;
+        } else {
+            self.min = self.min.min(stat.min);
+            self.max = self.max.max(stat.max);
+        }
+        self.weighted_sum += stat.avg * weight as f64;
+        self.weight += weight;
+    }
+
+    fn finish(&self) -> Option<RollupStat> {
+        if self.weight == 0 {
+            return None;
+        }
+        Some(RollupStat {
+            min: self.min,
+            avg: self.weighted_sum / self.weight as f64,
+            max: self.max,
+        })
+    }
+}
+
+/// Accumulates points for the bucket currently being filled in one rollup tier.
+#[derive(Debug, Default)]
+struct BucketAccumulator {
+    bucket_start: u64,
+    samples: u64,
+    cpu_usage: StatAccumulator,
+    memory_usage: StatAccumulator,
+    player_count: StatAccumulator,
+    tps: StatAccumulator,
+    heap_used_bytes: StatAccumulator,
+    disk_read_bytes_per_sec: StatAccumulator,
+    disk_write_bytes_per_sec: StatAccumulator,
+    uptime: u64,
+}
+
+impl BucketAccumulator {
+    fn add(&mut self, point: &MetricsRollup) {
+        let weight = point.samples.max(1);
+        self.samples += weight;
+        self.cpu_usage.add(point.cpu_usage, weight);
+        self.memory_usage.add(point.memory_usage, weight);
+        self.player_count.add(point.player_count, weight);
+        if let Some(tps) = point.tps {
+            self.tps.add(tps, weight);
+        }
+        if let Some(heap) = point.heap_used_bytes {
+            self.heap_used_bytes.add(heap, weight);
+        }
+        self.disk_read_bytes_per_sec.add(point.disk_read_bytes_per_sec, weight);
+        self.disk_write_bytes_per_sec.add(point.disk_write_bytes_per_sec, weight);
+        self.uptime = self.uptime.max(point.uptime);
+    }
+
+    fn finish(&self, resolution_secs: u64) -> Option<MetricsRollup> {
+        if self.samples == 0 {
+            return None;
+        }
+        let zero = RollupStat::single(0.0);
+        Some(MetricsRollup {
+            timestamp: self.bucket_start,
+            resolution_secs,
+            samples: self.samples,
+            cpu_usage: self.cpu_usage.finish().unwrap_or(zero),
+            memory_usage: self.memory_usage.finish().unwrap_or(zero),
+            player_count: self.player_count.finish().unwrap_or(zero),
+            tps: self.tps.finish(),
+            heap_used_bytes: self.heap_used_bytes.finish(),
+            disk_read_bytes_per_sec: self.disk_read_bytes_per_sec.finish().unwrap_or(zero),
+            disk_write_bytes_per_sec: self.disk_write_bytes_per_sec.finish().unwrap_or(zero),
+            uptime: self.uptime,
+        })
+    }
+}
+
+/// Merges consecutive points with the same timestamp, weighted by their sample counts.
+/// Expects `points` sorted by timestamp.
+fn merge_duplicate_buckets(points: Vec<MetricsRollup>) -> Vec<MetricsRollup> {
+    let mut merged: Vec<MetricsRollup> = Vec::with_capacity(points.len());
+    for point in points {
+        match merged.last_mut() {
+            Some(last) if last.timestamp == point.timestamp => {
+                let mut bucket = BucketAccumulator { bucket_start: last.timestamp, ..Default::default() };
+                bucket.add(last);
+                bucket.add(&point);
+                if let Some(combined) = bucket.finish(last.resolution_secs) {
+                    *last = combined;
+                }
+            }
+            _ => merged.push(point),
+        }
+    }
+    merged
+}
+
+/// Mutable state of the store, guarded by a single mutex.
+#[derive(Debug, Default)]
+struct StoreState {
+    minute: Option<BucketAccumulator>,
+    hour: Option<BucketAccumulator>,
+    last_prune_ts: u64,
+}
+
+/// Append-only, file-based time-series store for server metrics.
+///
+/// Layout below the store root (one JSON object per line):
+/// - `raw/YYYYMMDD.jsonl`: every `MetricsData` sample, kept for a day.
+/// - `1m/YYYYMMDD.jsonl`: 1-minute min/avg/max rollups, kept for a month.
+/// - `1h/YYYYMM.jsonl`: 1-hour rollups, kept for a year.
+///
+/// Each sample is appended as a single line; rollups are appended when their bucket
+/// closes. Retention is enforced by deleting whole files that fall out of range.
+pub struct MetricsStore {
+    root: PathBuf,
+    state: Mutex<StoreState>,
+}
+
+impl MetricsStore {
+    /// Creates a store rooted at `root` (e.g., `<log_dir>/metrics`).
+    pub fn new(root: PathBuf) -> Self {
+        info!("Initializing metrics store at {}", root.display());
+        for tier in [MetricsResolution::Raw, MetricsResolution::Minute, MetricsResolution::Hour] {
+            if let Err(e) = fs_utils::ensure_directory(&root.join(tier.dir_name())) {
+                warn!("Failed to create metrics store directory: {}", e);
+            }
+        }
+        Self {
+            root,
+            state: Mutex::new(StoreState::default()),
+        }
+    }
+
+    /// Appends a raw sample and updates the rollup tiers, writing closed buckets to disk.
+    pub fn append(&self, metrics: &MetricsData) -> Result<()> {
+        let line = serde_json::to_string(metrics)
+            .map_err(|e| AppError::InternalEventError(format!("Failed to serialize metrics sample: {}", e)))?;
+        let raw_path = self.tier_file(MetricsResolution::Raw, metrics.timestamp);
+        fs_utils::append_string_to_file(&raw_path, &format!("{}\n", line))?;
+
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|e| AppError::LockError(format!("Failed to lock metrics store state: {}", e)))?;
+
+        // Minute tier is fed by raw samples, hour tier by closed minute rollups
+        let point = rollup_from_sample(metrics);
+        if let Some(closed_minute) = add_to_bucket(&mut state.minute, &point, MetricsResolution::Minute) {
+            self.write_rollup(MetricsResolution::Minute, &closed_minute)?;
+            if let Some(closed_hour) = add_to_bucket(&mut state.hour, &closed_minute, MetricsResolution::Hour) {
+                self.write_rollup(MetricsResolution::Hour, &closed_hour)?;
+            }
+        }
+
+        if metrics.timestamp >= state.last_prune_ts + PRUNE_INTERVAL_SECS {
+            state.last_prune_ts = metrics.timestamp;
+            drop(state); // Pruning only touches files
+            self.prune(metrics.timestamp);
+        }
+        Ok(())
+    }
+
+    /// Flushes partially filled buckets, e.g. when the server stops. Later samples
+    /// in the same bucket will produce a second point for it, which `query_range` merges.
+    pub fn flush(&self) -> Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|e| AppError::LockError(format!("Failed to lock metrics store state: {}", e)))?;
+        if let Some(minute) = state.minute.take().and_then(|b| b.finish(MetricsResolution::Minute.bucket_secs())) {
+            self.write_rollup(MetricsResolution::Minute, &minute)?;
+            if let Some(hour) = add_to_bucket(&mut state.hour, &minute, MetricsResolution::Hour) {
+                self.write_rollup(MetricsResolution::Hour, &hour)?;
+            }
+        }
+        if let Some(hour) = state.hour.take().and_then(|b| b.finish(MetricsResolution::Hour.bucket_secs())) {
+            self.write_rollup(MetricsResolution::Hour, &hour)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the points between `from` and `to` (inclusive, UNIX seconds) at the requested resolution.
+    ///
+    /// Rollup tiers include the bucket currently being filled. Points sharing a bucket (written
+    /// before and after a `flush`, or still in memory) are merged into one.
+    pub fn query_range(&self, from: u64, to: u64, resolution: MetricsResolution) -> Result<MetricsRange> {
+        if from > to {
+            return Err(AppError::ConfigError(format!(
+                "Invalid metrics range: from ({}) is after to ({})",
+                from, to
+            )));
+        }
+        let resolution = match resolution {
+            MetricsResolution::Auto => pick_resolution(from, to),
+            other => other,
+        };
+        debug!("Querying metrics {}..{} at {:?} resolution", from, to, resolution);
+
+        let mut points = Vec::new();
+        for path in self.tier_files_for_range(resolution, from, to) {
+            if !path.exists() {
+                continue;
+            }
+            let file = File::open(&path)?;
+            for (line_num, line_result) in BufReader::new(file).lines().enumerate() {
+                let line = line_result?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let point = if resolution == MetricsResolution::Raw {
+                    serde_json::from_str::<MetricsData>(&line).map(|m| rollup_from_sample(&m))
+                } else {
+                    serde_json::from_str::<MetricsRollup>(&line)
+                };
+                match point {
+                    Ok(point) if point.timestamp >= from && point.timestamp <= to => points.push(point),
+                    Ok(_) => {}
+                    // A crash can leave a truncated last line; skip it rather than failing the query
+                    Err(e) => warn!("Skipping malformed line {} in {}: {}", line_num + 1, path.display(), e),
+                }
+            }
+        }
+        if resolution != MetricsResolution::Raw {
+            let state = self
+                .state
+                .lock()
+                .map_err(|e| AppError::LockError(format!("Failed to lock metrics store state: {}", e)))?;
+            let open = match resolution {
+                MetricsResolution::Minute => state.minute.as_ref(),
+                _ => state.hour.as_ref(),
+            };
+            if let Some(point) = open.and_then(|b| b.finish(resolution.bucket_secs())) {
+                if point.timestamp >= from && point.timestamp <= to {
+                    points.push(point);
+                }
+            }
+        }
+        points.sort_by_key(|p| p.timestamp);
+        let points = merge_duplicate_buckets(points);
+        trace!("Metrics range query returned {} points", points.len());
+
+        Ok(MetricsRange { resolution, from, to, points })
+    }
+
+    /// Appends a closed rollup to its tier file.
+    fn write_rollup(&self, tier: MetricsResolution, rollup: &MetricsRollup) -> Result<()> {
+        let line = serde_json::to_string(rollup)
+            .map_err(|e| AppError::InternalEventError(format!("Failed to serialize metrics rollup: {}", e)))?;
+        let path = self.tier_file(tier, rollup.timestamp);
+        trace!("Writing {:?} rollup for {} to {}", tier, rollup.timestamp, path.display());
+        fs_utils::append_string_to_file(&path, &format!("{}\n", line))
+    }
+
+    /// Path of the tier file holding the given timestamp.
+    fn tier_file(&self, tier: MetricsResolution, timestamp: u64) -> PathBuf {
+        let date = timestamp_to_datetime(timestamp);
+        let name = match tier {
+            MetricsResolution::Hour => format!("{}.jsonl", date.format("%Y%m")),
+            _ => format!("{}.jsonl", date.format("%Y%m%d")),
+        };
+        self.root.join(tier.dir_name()).join(name)
+    }
+
+    /// All tier files that can contain points in the given range, in chronological order.
+    fn tier_files_for_range(&self, tier: MetricsResolution, from: u64, to: u64) -> Vec<PathBuf> {
+        let mut files = Vec::new();
+        let mut day = timestamp_to_datetime(from).date_naive();
+        let last_day = timestamp_to_datetime(to).date_naive();
+        while day <= last_day {
+            let name = match tier {
+                MetricsResolution::Hour => format!("{}.jsonl", day.format("%Y%m")),
+                _ => format!("{}.jsonl", day.format("%Y%m%d")),
+            };
+            let path = self.root.join(tier.dir_name()).join(name);
+            if !files.contains(&path) {
+                files.push(path);
+            }
+            day = match day.succ_opt() {
+                Some(next) => next,
+                None => break,
+            };
+        }
+        files
+    }
+
+    /// Deletes tier files that are entirely older than their retention period.
+    fn prune(&self, now: u64) {
+        let today = timestamp_to_datetime(now).date_naive();
+        let tiers = [
+            (MetricsResolution::Raw, RAW_RETENTION_DAYS),
+            (MetricsResolution::Minute, MINUTE_RETENTION_DAYS),
+            (MetricsResolution::Hour, HOUR_RETENTION_DAYS),
+        ];
+        for (tier, retention_days) in tiers {
+            let cutoff = today - ChronoDuration::days(retention_days);
+            let dir = self.root.join(tier.dir_name());
+            let entries = match fs::read_dir(&dir) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    warn!("Failed to list metrics store directory {}: {}", dir.display(), e);
+                    continue;
+                }
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                if let Some(last_day) = file_last_day(&path, tier) {
+                    if last_day < cutoff {
+                        debug!("Pruning expired metrics file: {}", path.display());
+                        if let Err(e) = fs_utils::remove_file(&path) {
+                            warn!("Failed to prune {}: {}", path.display(), e);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Adds a point to the bucket accumulator of a tier.
+/// Returns the previous bucket's rollup if the point starts a new bucket.
+fn add_to_bucket(
+    bucket: &mut Option<BucketAccumulator>,
+    point: &MetricsRollup,
+    tier: MetricsResolution,
+) -> Option<MetricsRollup> {
+    let width = tier.bucket_secs();
+    let bucket_start = point.timestamp - point.timestamp % width;
+
+    let mut closed = None;
+    if let Some(current) = bucket.as_ref() {
+        if current.bucket_start != bucket_start {
+            closed = current.finish(width);
+            *bucket = None;
+        }
+    }
+    bucket
+        .get_or_insert_with(|| BucketAccumulator { bucket_start, ..Default::default() })
+        .add(point);
+    closed
+}
+
+/// Converts a raw sample into a single-sample rollup point.
+fn rollup_from_sample(metrics: &MetricsData) -> MetricsRollup {
+    MetricsRollup {
+        timestamp: metrics.timestamp,
+        resolution_secs: 1,
+        samples: 1,
+        cpu_usage: RollupStat::single(metrics.cpu_usage as f64),
+        memory_usage: RollupStat::single(metrics.memory_usage as f64),
+        player_count: RollupStat::single(metrics.player_count as f64),
+        tps: metrics.tps.map(|tps| RollupStat::single(tps as f64)),
+        heap_used_bytes: metrics.jvm.as_ref().map(|jvm| RollupStat::single(jvm.heap_used_bytes as f64)),
+        disk_read_bytes_per_sec: RollupStat::single(metrics.disk_read_bytes_per_sec as f64),
+        disk_write_bytes_per_sec: RollupStat::single(metrics.disk_write_bytes_per_sec as f64),
+        uptime: metrics.uptime,
+    }
+}
+
+/// Chooses a resolution for `Auto` queries: raw up to 2 hours (and only within raw retention),
+/// minutes up to a week (within minute retention), hours otherwise.
+fn pick_resolution(from: u64, to: u64) -> MetricsResolution {
+    let span = to - from;
+    let now = Utc::now().timestamp().max(0) as u64;
+    let age = now.saturating_sub(from);
+    if span <= 2 * 3600 && age <= RAW_RETENTION_DAYS as u64 * 86_400 {
+        MetricsResolution::Raw
+    } else if span <= 7 * 86_400 && age <= MINUTE_RETENTION_DAYS as u64 * 86_400 {
+        MetricsResolution::Minute
+    } else {
+        MetricsResolution::Hour
+    }
+}
+
+fn timestamp_to_datetime(timestamp: u64) -> DateTime<Utc> {
+    DateTime::<Utc>::from_timestamp(timestamp as i64, 0).unwrap_or_default()
+}
+
+/// Last day covered by a tier file, derived from its name (YYYYMMDD or YYYYMM).
+fn file_last_day(path: &Path, tier: MetricsResolution) -> Option<NaiveDate> {
+    let stem = path.file_stem()?.to_str()?;
+    match tier {
+        MetricsResolution::Hour => {
+            let first = NaiveDate::parse_from_str(&format!("{}01", stem), "%Y%m%d").ok()?;
+            let (year, month) = if first.month() == 12 { (first.year() + 1, 1) } else { (first.year(), first.month() + 1) };
+            NaiveDate::from_ymd_opt(year, month, 1)?.pred_opt()
+        }
+        _ => NaiveDate::parse_from_str(stem, "%Y%m%d").ok(),
+    }
+}
@@ -1,3 +1,5 @@
 ﻿pub mod resource_monitor;
 pub mod metrics_collector;
-pub mod alert_manager;
\ No newline at end of file
+pub mod alert_manager;
+pub mod jvm_metrics;
+pub mod metrics_store;
\ No newline at end of file
@@ -1,30 +1,63 @@
 ﻿use crate::api::events::{self, emit_event, emit_log, emit_warn}; // Use helpers
 use crate::app_state::AppState;
+use crate::commands::process_manager;
+use crate::config::software_detector;
 use crate::error::{AppError, Result}; // Use Result
 use crate::models::log_entry::LogLevel; // Import LogLevel
 use crate::models::metrics::MetricsData;
 use crate::models::server_status::ServerStatus;
 // Import collector and alerter
 use crate::monitoring::alert_manager::AlertManager;
+use crate::monitoring::jvm_metrics::JvmMetricsCollector;
 use crate::monitoring::metrics_collector::MetricsCollector;
+use crate::utils::process_utils;
 use log::{debug, error, info, trace, warn};
 use std::path::PathBuf; // Import PathBuf
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH}; // Import SystemTime, UNIX_EPOCH
 use sysinfo::{Pid, ProcessExt, System, SystemExt}; // Import Pid
 
 const MONITOR_INTERVAL: Duration = Duration::from_secs(1); // Check every second
 
+/// Aggregated resource usage of the server process and all of its descendants.
+#[derive(Debug, Default)]
+struct ProcessTreeSample {
+    /// Sum of per-process CPU usage, normalized to host cores (0-100).
+    cpu_percent: f32,
+    /// Sum of resident memory in bytes.
+    rss_bytes: u64,
+    /// Number of processes found in the tree.
+    process_count: u32,
+    /// Total threads, None if not available on this platform.
+    thread_count: Option<u32>,
+    /// Total open file descriptors, None if not available on this platform.
+    open_files: Option<u32>,
+    /// Bytes read from disk since the previous refresh.
+    disk_read_bytes: u64,
+    /// Bytes written to disk since the previous refresh.
+    disk_written_bytes: u64,
+}
+
+/// Cumulative network counters of the previous sample, used to derive per-second rates.
+struct NetworkSample {
+    taken_at: Instant,
+    rx_bytes: u64,
+    tx_bytes: u64,
+}
+
 /// Starts the main monitoring loop in a separate thread.
 ///
 /// - Periodically checks the server status.
-/// - If running, uses `sysinfo` to get CPU/Memory for the Java process.
-/// - Gathers other metrics (uptime, player count from AppState).
+/// - If running, takes the PID from the `Child` handle in `AppState` and uses `sysinfo`
+///   to aggregate CPU, memory, threads, file descriptors and disk I/O over the whole
+///   process tree (wrapper scripts such as `run.sh` plus the JVM and its children).
+/// - Gathers other metrics (uptime, player count from AppState, host load average,
+///   host (network namespace) network throughput on Linux, JVM heap/GC metrics via `JvmMetricsCollector`).
 /// - Updates `AppState.metrics`.
 /// - Sends `MetricsUpdated` events via MPSC channel.
 /// - Calls `MetricsCollector::add_metrics`.
-/// - Calls `AlertManager::check_alerts`.
+/// - Calls `AlertManager::check_alerts`, and `AlertManager::server_stopped` while stopped.
 pub async fn start_monitoring(
     state: Arc<AppState>,
     metrics_collector: Arc<MetricsCollector>,
@@ -34,8 +67,14 @@ pub async fn start_monitoring(
 
     thread::spawn(move || {
         let mut sys = System::new_all();
+        let cpu_count = sys.cpus().len().max(1) as f32; // Used to normalize CPU to 0-100
         let mut server_pid: Option<Pid> = None; // Store the PID when found
+        let mut jvm_pid: Option<Pid> = None; // The JVM in the tree (the root may be run.sh)
         let mut last_metrics_update = Instant::now();
+        let mut last_refresh = Instant::now(); // Disk counters are deltas since the previous refresh
+        let mut last_network: Option<NetworkSample> = None;
+        // Heap/GC metrics from the GC log or jstat
+        let mut jvm_collector = JvmMetricsCollector::new(&state.server_directory);
         // Track server start time *relative to when monitor detects Running/Starting*
         let server_start_time: Arc<Mutex<Option<Instant>>> = Arc::new(Mutex::new(None));
 
@@ -56,11 +95,20 @@ pub async fn start_monitoring(
             // If running or starting, try to find/confirm the PID
             if status == ServerStatus::Running || status == ServerStatus::Starting {
                 if server_pid.is_none() {
-                    // Try to find the PID if we don't have it
-                    debug!("Monitor: Searching for server process PID...");
-                    sys.refresh_processes(); // Refresh process list before searching
-                    server_pid = find_server_pid(&sys, &state);
+                    // Take the PID from the Child handle spawned by process_manager
+                    debug!("Monitor: Reading server process PID from process handle...");
+                    server_pid = match state.get_process_id() {
+                        Ok(pid) => pid.map(Pid::from_u32),
+                        Err(e) => {
+                            error!("Monitor: Failed to read server process PID: {}", e);
+                            None
+                        }
+                    };
                     if let Some(pid) = server_pid {
+                        sys.refresh_processes(); // Prime CPU/disk counters for the new tree
+                        last_refresh = Instant::now();
+                        last_network = None;
+                        jvm_pid = None;
                         info!("Monitor: Found server process PID: {:?}", pid);
                         // Record start time when PID is first found while Running/Starting
                         let mut start_time_guard = server_start_time.lock().unwrap();
@@ -69,12 +117,14 @@ pub async fn start_monitoring(
                             info!("Monitor: Server start time recorded.");
                         }
                     } else {
-                        // This can happen briefly during startup before process is fully listed
-                        trace!("Monitor: Server status is {:?}, but process PID not found yet.", status);
+                        // This can happen briefly during startup before the handle is stored
+                        trace!("Monitor: Server status is {:?}, but process handle not stored yet.", status);
                     }
+                    continue; // Counters need one interval before they are meaningful
                 } else {
-                    // We have a PID, make sure it still exists (refresh_process does this)
-                    if !sys.refresh_process(server_pid.unwrap()) {
+                    // Refresh the whole process list so children spawned since the last cycle are seen
+                    sys.refresh_processes();
+                    if sys.process(server_pid.unwrap()).is_none() {
                         error!("Monitor: Server process with PID {:?} disappeared unexpectedly!", server_pid.unwrap());
                         server_pid = None; // Clear PID
                         let mut start_time_guard = server_start_time.lock().unwrap();
@@ -86,7 +136,7 @@ pub async fn start_monitoring(
                             state.reset_player_count(); // Reset count on crash
                             if state.set_status(ServerStatus::Stopped).is_ok() {
                                 events::emit_status_change(ServerStatus::Stopped);
-                                events::emit_warn("Server process stopped unexpectedly (disappeared).".to_string(), "Monitor".to_string());
+                                events::emit_server_crashed("Server process stopped unexpectedly (disappeared).".to_string(), "Monitor".to_string());
                             } else {
                                 error!("Monitor: Failed to lock state to set status to Stopped after process disappearance.");
                             }
@@ -97,10 +147,19 @@ pub async fn start_monitoring(
                     }
                 }
             } else {
+                if status == ServerStatus::Stopped {
+                    alert_manager.server_stopped();
+                }
                 // If stopped, stopping or error, clear the PID and start time
                 if server_pid.is_some() {
                     info!("Monitor: Server not running/starting. Clearing PID and start time.");
                     server_pid = None;
+                    jvm_pid = None;
+                    last_network = None;
+                    // Write out the partially filled minute/hour rollups of this run
+                    if let Err(e) = metrics_collector.flush() {
+                        error!("Monitor: Failed to flush metrics store: {}", e);
+                    }
                     let mut start_time_guard = server_start_time.lock().unwrap();
                     *start_time_guard = None;
                     // Ensure metrics are reset or show zero when stopped
@@ -119,10 +178,13 @@ pub async fn start_monitoring(
 
             // --- Collect Metrics if PID is known ---
             if let Some(pid) = server_pid {
-                // We already refreshed the process above, just need system memory occasionally
+                // We already refreshed the processes above, just need system memory occasionally
                 sys.refresh_memory(); // Refresh system memory info
+                let elapsed_secs = last_refresh.elapsed().as_secs_f64().max(0.001);
+                last_refresh = Instant::now();
 
-                if let Some(process) = sys.process(pid) {
+                if sys.process(pid).is_some() {
+                    let tree = sample_process_tree(&sys, pid, cpu_count);
                     // --- Create MetricsData ---
                     let current_time_secs = SystemTime::now()
                         .duration_since(UNIX_EPOCH)
@@ -167,19 +229,53 @@ pub async fn start_monitoring(
                     // TODO: Get TPS accurately
                     let tps = None; // Placeholder
 
+                    // Network counters are cumulative; convert to rates against the previous sample
+                    let (host_network_rx_bytes_per_sec, host_network_tx_bytes_per_sec) =
+                        match process_utils::read_namespace_network_bytes(pid.as_u32()) {
+                            Some((rx_bytes, tx_bytes)) => {
+                                let rates = last_network.as_ref().map(|prev| {
+                                    let secs = prev.taken_at.elapsed().as_secs_f64().max(0.001);
+                                    (
+                                        (rx_bytes.saturating_sub(prev.rx_bytes) as f64 / secs) as u64,
+                                        (tx_bytes.saturating_sub(prev.tx_bytes) as f64 / secs) as u64,
+                                    )
+                                });
+                                last_network = Some(NetworkSample { taken_at: Instant::now(), rx_bytes, tx_bytes });
+                                (rates.map(|r| r.0), rates.map(|r| r.1))
+                            }
+                            None => (None, None),
+                        };
+
+                    let load = System::load_average();
+                    // jstat must target the JVM, which a wrapper script starts some time after the root
+                    if jvm_pid.map_or(true, |jvm| sys.process(jvm).is_none()) {
+                        jvm_pid = process_utils::find_jvm_process(&sys, pid);
+                        if let Some(jvm) = jvm_pid {
+                            let java_path = jvm_java_path(&sys, jvm, &state);
+                            debug!("Monitor: Server JVM is PID {} ({})", jvm, java_path.display());
+                            jvm_collector.reset(&java_path);
+                        }
+                    }
+                    let jvm = jvm_pid.and_then(|jvm| jvm_collector.poll(jvm.as_u32()));
+
                     let metrics = MetricsData {
                         timestamp: current_time_secs,
-                        // sysinfo cpu_usage() needs careful interpretation.
-                        // It's often % since process start or last refresh cycle.
-                        // For more accurate *current* load, consider system-wide load
-                        // or calculating diffs between successive process CPU times.
-                        cpu_usage: process.cpu_usage(), // Use with caution, might not be interval load %
-                        memory_usage: process.memory(), // Bytes
+                        cpu_usage: tree.cpu_percent, // Normalized to host cores, 0-100
+                        memory_usage: tree.rss_bytes, // Bytes (RSS of the whole tree)
                         system_memory_total: sys.total_memory(), // Bytes
                         player_count, // Read from metrics lock
                         max_players: max_players_prop, // Use value read from properties
                         tps,
                         uptime: uptime_secs,
+                        process_count: tree.process_count,
+                        thread_count: tree.thread_count,
+                        open_files: tree.open_files,
+                        disk_read_bytes_per_sec: (tree.disk_read_bytes as f64 / elapsed_secs) as u64,
+                        disk_write_bytes_per_sec: (tree.disk_written_bytes as f64 / elapsed_secs) as u64,
+                        load_average: [load.one, load.five, load.fifteen],
+                        host_network_rx_bytes_per_sec,
+                        host_network_tx_bytes_per_sec,
+                        jvm,
                     };
                     trace!("Collected Metrics: {:?}", metrics);
 
@@ -208,42 +304,51 @@ pub async fn start_monitoring(
                         last_metrics_update = Instant::now();
                     }
                 }
-                // else case (process disappeared) handled by refresh_process check earlier
+                // else case (process disappeared) handled by the process list check earlier
             } // end if let Some(pid)
         } // end loop
     }); // end thread::spawn
 }
 
-/// Helper to find the PID of the Java server process.
-/// Looks for a "java" process whose command line arguments include the server JAR name.
-fn find_server_pid(sys: &System, state: &Arc<AppState>) -> Option<Pid> {
-    let server_jar_name = &state.server_jar; // Get the JAR name from state
-    trace!("Searching for java process matching JAR: {}", server_jar_name);
-
-    let process_name = if cfg!(target_os = "windows") { "java.exe" } else { "java" };
-
-    // Iterate through processes with the exact name
-    for (pid, process) in sys.processes_by_exact_name(process_name) {
-        // Check command line arguments
-        let cmd_line = process.cmd().join(" ");
-        // Simple check: does command line contain the JAR name?
-        // And does it run in the expected directory? (More robust)
-        if cmd_line.contains(server_jar_name) {
-            if let Some(cwd) = process.cwd() {
-                if cwd == state.server_directory {
-                    debug!("Found matching PID by name, JAR, and CWD: {}, Cmd: '{}'", pid, cmd_line);
-                    return Some(*pid);
-                } else {
-                    trace!("PID {} matches name/JAR but not CWD ({} != {})", pid, cwd.display(), state.server_directory.display());
-                }
-            } else {
-                // Fallback if CWD isn't available, just match name/JAR
-                debug!("Found potential match PID by name/JAR (CWD unknown): {}, Cmd: '{}'", pid, cmd_line);
-                return Some(*pid);
-            }
+/// Returns the `java` executable the server JVM runs from. Falls back to the Java the launcher
+/// selects for the installed software if the process doesn't expose its executable.
+fn jvm_java_path(sys: &System, jvm: Pid, state: &AppState) -> PathBuf {
+    if let Some(exe) = sys.process(jvm).map(|process| process.exe()).filter(|exe| !exe.as_os_str().is_empty()) {
+        return exe.to_path_buf();
+    }
+    let required_java = state
+        .get_server_jar()
+        .ok()
+        .and_then(|jar| software_detector::detect(&state.server_directory, &jar).ok())
+        .and_then(|detected| detected.required_java);
+    process_manager::select_java(state, required_java)
+}
+
+/// Aggregates resource usage over the process rooted at `root` and all of its descendants.
+/// `sys` must have been refreshed with `refresh_processes` beforehand.
+fn sample_process_tree(sys: &System, root: Pid, cpu_count: f32) -> ProcessTreeSample {
+    let mut sample = ProcessTreeSample::default();
+
+    for pid in process_utils::collect_process_tree(sys, root) {
+        let Some(process) = sys.process(pid) else { continue };
+        sample.process_count += 1;
+        // sysinfo reports per-process CPU as a percentage of a single core
+        sample.cpu_percent += process.cpu_usage() / cpu_count;
+        sample.rss_bytes = sample.rss_bytes.saturating_add(process.memory());
+
+        let disk = process.disk_usage();
+        sample.disk_read_bytes = sample.disk_read_bytes.saturating_add(disk.read_bytes);
+        sample.disk_written_bytes = sample.disk_written_bytes.saturating_add(disk.written_bytes);
+
+        if let Some(threads) = process_utils::count_threads(pid.as_u32()) {
+            sample.thread_count = Some(sample.thread_count.unwrap_or(0) + threads);
+        }
+        if let Some(files) = process_utils::count_open_files(pid.as_u32()) {
+            sample.open_files = Some(sample.open_files.unwrap_or(0) + files);
         }
     }
 
-    trace!("No matching Java process found by exact name.");
-    None // No matching process found
-}
\ No newline at end of file
+    sample.cpu_percent = sample.cpu_percent.min(100.0);
+    trace!("Monitor: Process tree sample for PID {}: {:?}", root, sample);
+    sample
+}
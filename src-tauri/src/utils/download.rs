@@ -0,0 +1,305 @@
+// src/utils/download.rs
+
+use crate::api::events::emit_progress;
+use crate::error::{AppError, Result};
+use crate::models::server_software::Checksum;
+use log::{debug, info, warn};
+use md5::Md5;
+use serde::de::DeserializeOwned;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+/// Creates the blocking HTTP client used for metadata requests and downloads.
+/// Some APIs (PaperMC, Modrinth) reject requests without a descriptive user agent.
+pub fn http_client() -> reqwest::blocking::Client {
+    reqwest::blocking::Client::builder()
+        .user_agent(concat!("minecraft-server-manager/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(Duration::from_secs(15))
+        .timeout(Duration::from_secs(600))
+        .build()
+        .unwrap_or_else(|_| reqwest::blocking::Client::new())
+}
+
+/// Fetches and deserializes a JSON document.
+pub fn get_json<T: DeserializeOwned>(client: &reqwest::blocking::Client, url: &str) -> Result<T> {
+    debug!("GET {}", url);
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| AppError::DownloadError(format!("Request to {} failed: {}", url, e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::DownloadError(format!("{} returned HTTP {}", url, response.status())));
+    }
+    response
+        .json()
+        .map_err(|e| AppError::DownloadError(format!("Invalid response from {}: {}", url, e)))
+}
+
+/// Fetches a plain-text document, such as maven metadata or a `.sha1` file.
+pub fn get_text(client: &reqwest::blocking::Client, url: &str) -> Result<String> {
+    debug!("GET {}", url);
+    let response = client
+        .get(url)
+        .send()
+        .map_err(|e| AppError::DownloadError(format!("Request to {} failed: {}", url, e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::DownloadError(format!("{} returned HTTP {}", url, response.status())));
+    }
+    response
+        .text()
+        .map_err(|e| AppError::DownloadError(format!("Invalid response from {}: {}", url, e)))
+}
+
+/// Incremental hasher for the algorithms providers publish.
+enum StreamHasher {
+    Sha1(Sha1),
+    Sha256(Sha256),
+    Sha512(Sha512),
+    Md5(Md5),
+}
+
+impl StreamHasher {
+    fn new(checksum: &Checksum) -> Self {
+        match checksum {
+            Checksum::Sha1(_) => StreamHasher::Sha1(Sha1::new()),
+            Checksum::Sha256(_) => StreamHasher::Sha256(Sha256::new()),
+            Checksum::Sha512(_) => StreamHasher::Sha512(Sha512::new()),
+            Checksum::Md5(_) => StreamHasher::Md5(Md5::new()),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            StreamHasher::Sha1(hasher) => hasher.update(data),
+            StreamHasher::Sha256(hasher) => hasher.update(data),
+            StreamHasher::Sha512(hasher) => hasher.update(data),
+            StreamHasher::Md5(hasher) => hasher.update(data),
+        }
+    }
+
+    fn finish(self) -> String {
+        match self {
+            StreamHasher::Sha1(hasher) => hex::encode(hasher.finalize()),
+            StreamHasher::Sha256(hasher) => hex::encode(hasher.finalize()),
+            StreamHasher::Sha512(hasher) => hex::encode(hasher.finalize()),
+            StreamHasher::Md5(hasher) => hex::encode(hasher.finalize()),
+        }
+    }
+}
+
+fn expected_hex(checksum: &Checksum) -> &str {
+    match checksum {
+        Checksum::Sha1(hex) | Checksum::Sha256(hex) | Checksum::Sha512(hex) | Checksum::Md5(hex) => hex,
+    }
+}
+
+/// Removes a download's `.part` file when dropped, unless it was moved into place.
+struct PartFile<'a> {
+    path: &'a Path,
+    keep: bool,
+}
+
+impl Drop for PartFile<'_> {
+    fn drop(&mut self) {
+        if !self.keep {
+            if let Err(e) = fs::remove_file(self.path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    warn!("Failed to remove partial download {}: {}", self.path.display(), e);
+                }
+            }
+        }
+    }
+}
+
+/// Downloads `url` to `destination`, emitting `ProgressUpdate` events under `task`.
+///
+/// The body is written to `<destination>.part` and only renamed into place once the
+/// checksum (if any) matches, so a failed or tampered download never replaces a good file.
+/// Returns the number of bytes written.
+pub fn download_verified(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    checksum: Option<&Checksum>,
+    destination: &Path,
+    task: &str,
+) -> Result<u64> {
+    download(client, url, checksum, destination, Some(task))
+}
+
+/// Same as `download_verified` without per-file progress events, for callers that download
+/// many files and report overall progress themselves.
+pub fn download_verified_quietly(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    checksum: Option<&Checksum>,
+    destination: &Path,
+) -> Result<u64> {
+    download(client, url, checksum, destination, None)
+}
+
+fn download(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    checksum: Option<&Checksum>,
+    destination: &Path,
+    task: Option<&str>,
+) -> Result<u64> {
+    info!("Downloading {} to {}", url, destination.display());
+    if let Some(task) = task {
+        emit_progress(task, 0.0, "Starting download...");
+    }
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut response = client
+        .get(url)
+        .send()
+        .map_err(|e| AppError::DownloadError(format!("Request to {} failed: {}", url, e)))?;
+    if !response.status().is_success() {
+        return Err(AppError::DownloadError(format!("{} returned HTTP {}", url, response.status())));
+    }
+    let total_size = response.content_length().unwrap_or(0);
+
+    let mut part_name = destination.as_os_str().to_owned();
+    part_name.push(".part");
+    let part_path = PathBuf::from(part_name);
+    let mut part = PartFile { path: &part_path, keep: false };
+    let mut file = File::create(&part_path)?;
+    let mut hasher = checksum.map(StreamHasher::new);
+    let mut downloaded: u64 = 0;
+    let mut last_percent = -1;
+    let mut buffer = [0; 64 * 1024];
+    loop {
+        let read = match response.read(&mut buffer) {
+            Ok(read) => read,
+            Err(e) => {
+                return Err(AppError::DownloadError(format!("Error while downloading {}: {}", url, e)));
+            }
+        };
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read])?;
+        if let Some(hasher) = hasher.as_mut() {
+            hasher.update(&buffer[..read]);
+        }
+        downloaded += read as u64;
+
+        let Some(task) = task else { continue };
+        if total_size > 0 {
+            let percent = (downloaded * 100 / total_size) as i64;
+            if percent != last_percent {
+                last_percent = percent;
+                emit_progress(task, percent as f32, &format!("Downloading... {}%", percent));
+            }
+        } else if downloaded % (1024 * 1024) < read as u64 {
+            emit_progress(task, -1.0, &format!("Downloading... {} bytes", downloaded));
+        }
+    }
+    file.flush()?;
+    drop(file);
+
+    if let (Some(hasher), Some(checksum)) = (hasher, checksum) {
+        let actual = hasher.finish();
+        if !actual.eq_ignore_ascii_case(expected_hex(checksum)) {
+            return Err(AppError::DownloadError(format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                url,
+                expected_hex(checksum),
+                actual
+            )));
+        }
+        debug!("Checksum verified for {}", destination.display());
+    }
+
+    fs::rename(&part_path, destination)?;
+    part.keep = true;
+    if let Some(task) = task {
+        emit_progress(task, 100.0, "Download complete.");
+    }
+    info!("Downloaded {} bytes to {}", downloaded, destination.display());
+    Ok(downloaded)
+}
+
+/// One file of a batch download.
+#[derive(Debug, Clone)]
+pub struct DownloadJob {
+    /// Mirrors, tried in order.
+    pub urls: Vec<String>,
+    pub checksum: Option<Checksum>,
+    pub destination: PathBuf,
+    /// Name shown in progress messages and errors.
+    pub label: String,
+}
+
+/// Downloads all `jobs` on up to `threads` threads with `download_verified_quietly`, emitting
+/// overall progress under `task`. The first failure stops the remaining downloads and is
+/// returned; files already downloaded are left in place.
+pub fn download_all(
+    client: &reqwest::blocking::Client,
+    jobs: &[DownloadJob],
+    threads: usize,
+    task: &str,
+) -> Result<()> {
+    let total = jobs.len();
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let failure: Mutex<Option<AppError>> = Mutex::new(None);
+
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, total.max(1)) {
+            scope.spawn(|| loop {
+                match failure.lock() {
+                    Ok(guard) if guard.is_none() => {}
+                    _ => break,
+                }
+                let Some(job) = jobs.get(next.fetch_add(1, Ordering::SeqCst)) else { break };
+                if let Err(e) = download_job(client, job) {
+                    if let Ok(mut guard) = failure.lock() {
+                        guard.get_or_insert(e);
+                    }
+                    break;
+                }
+                let count = done.fetch_add(1, Ordering::SeqCst) + 1;
+                emit_progress(
+                    task,
+                    count as f32 / total as f32 * 100.0,
+                    &format!("Downloaded {} ({}/{})", job.label, count, total),
+                );
+            });
+        }
+    });
+
+    match failure.into_inner() {
+        Ok(None) => Ok(()),
+        Ok(Some(e)) => Err(e),
+        Err(e) => Err(AppError::LockError(format!("Failed to lock download state: {}", e))),
+    }
+}
+
+/// Downloads one job, trying each mirror until one succeeds.
+fn download_job(client: &reqwest::blocking::Client, job: &DownloadJob) -> Result<()> {
+    let mut last_error = None;
+    for url in &job.urls {
+        match download_verified_quietly(client, url, job.checksum.as_ref(), &job.destination) {
+            Ok(_) => return Ok(()),
+            Err(e) => {
+                warn!("Download of {} from {} failed: {}", job.label, url, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    Err(AppError::DownloadError(format!(
+        "Could not download {}: {}",
+        job.label,
+        last_error.map_or_else(|| "no download URL".to_string(), |e| e.to_string())
+    )))
+}
@@ -74,6 +74,49 @@ pub fn write_string_to_file(path: &Path, content: &str) -> AppResult<()> {
     Ok(())
 }
 
+/// Writes a string slice to a file atomically.
+/// The content is written to a temporary file next to `path`, synced, then renamed over it,
+/// so readers never observe a partially written file.
+pub fn write_string_to_file_atomic(path: &Path, content: &str) -> AppResult<()> {
+    write_bytes_to_file_atomic(path, content.as_bytes())
+}
+
+/// Writes raw bytes to a file atomically (see `write_string_to_file_atomic`).
+pub fn write_bytes_to_file_atomic(path: &Path, content: &[u8]) -> AppResult<()> {
+    trace!("Atomically writing bytes to file: {}", path.display());
+    if let Some(parent) = path.parent() {
+        ensure_directory(parent)?;
+    }
+
+    let mut temp_name = path.file_name().unwrap_or_else(|| OsStr::new("file")).to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let mut file = File::create(&temp_path).map_err(|e| {
+        AppError::IoError(io::Error::new(
+            e.kind(),
+            format!("Failed to create temporary file {}: {}", temp_path.display(), e),
+        ))
+    })?;
+    file.write_all(content)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| {
+            AppError::IoError(io::Error::new(
+                e.kind(),
+                format!("Failed to write temporary file {}: {}", temp_path.display(), e),
+            ))
+        })?;
+    drop(file);
+
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path); // Don't leave the temp file behind
+        AppError::IoError(io::Error::new(
+            e.kind(),
+            format!("Failed to replace {} with {}: {}", path.display(), temp_path.display(), e),
+        ))
+    })
+}
+
 /// Appends a string slice to a file, creating the file if it doesn't exist.
 pub fn append_string_to_file(path: &Path, content: &str) -> AppResult<()> {
     trace!("Appending string to file: {}", path.display());
@@ -128,6 +171,37 @@ pub fn copy_file(source: &Path, destination: &Path) -> AppResult<u64> {
     })
 }
 
+/// Recursively copies a directory, creating `destination` if needed. Existing files are
+/// overwritten. Returns the number of bytes copied.
+pub fn copy_directory(source: &Path, destination: &Path) -> AppResult<u64> {
+    trace!("Copying directory from {} to {}", source.display(), destination.display());
+    let mut copied = 0;
+    for entry in WalkDir::new(source) {
+        let entry = entry.map_err(|e| {
+            AppError::IoError(io::Error::new(
+                io::ErrorKind::Other,
+                format!("Failed to walk {}: {}", source.display(), e),
+            ))
+        })?;
+        let relative = entry.path().strip_prefix(source).unwrap_or(entry.path());
+        let target = destination.join(relative);
+        if entry.file_type().is_dir() {
+            ensure_directory(&target)?;
+        } else if entry.file_type().is_file() {
+            if let Some(parent) = target.parent() {
+                ensure_directory(parent)?;
+            }
+            copied += fs::copy(entry.path(), &target).map_err(|e| {
+                AppError::IoError(io::Error::new(
+                    e.kind(),
+                    format!("Failed to copy {} to {}: {}", entry.path().display(), target.display(), e),
+                ))
+            })?;
+        }
+    }
+    Ok(copied)
+}
+
 // Optional: Add an overwriting version if needed
 // pub fn copy_file_overwrite(source: &Path, destination: &Path) -> AppResult<u64> { ... }
 
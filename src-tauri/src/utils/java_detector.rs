@@ -266,18 +266,101 @@ pub fn is_java_compatible(java_path: &Path) -> AppResult<bool> {
     }
 }
 
-/// Placeholder: Finds the most suitable Java version for a specific Minecraft version.
-/// This requires knowing Minecraft version requirements and potentially checking multiple Java installs.
-/// Returns the path found by `find_java_path` for now.
+/// Minimum Java major version a Minecraft release needs. Returns None for versions that
+/// can't be parsed (snapshots use the `java_version` from their `version.json` instead).
+pub fn required_java_major(minecraft_version: &str) -> Option<u32> {
+    let mut parts = minecraft_version.split(|c: char| c == '.' || c == '-');
+    if parts.next()? != "1" {
+        return None;
+    }
+    let minor: u32 = parts.next()?.parse().ok()?;
+    let patch: u32 = parts.next().and_then(|p| p.parse().ok()).unwrap_or(0);
+    Some(match (minor, patch) {
+        (0..=16, _) => 8,
+        (17, _) => 16,
+        (18..=19, _) | (20, 0..=4) => 17,
+        _ => 21,
+    })
+}
+
+/// Lists every valid Java installation found in JAVA_HOME, PATH, the common locations and
+/// the usual JVM directories, with its effective major version. Duplicates are removed.
+pub fn find_java_installations() -> Vec<(PathBuf, u32)> {
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    if let Some(java_home) = var_os("JAVA_HOME") {
+        candidates.push(get_java_executable_from_home(Path::new(&java_home)));
+    }
+    if let Ok(java_path) = which("java") {
+        candidates.push(java_path);
+    }
+    for location in get_common_java_locations() {
+        candidates.push(get_java_executable_from_home(Path::new(&location)));
+    }
+    for root in get_jvm_root_directories() {
+        let Ok(entries) = std::fs::read_dir(&root) else { continue };
+        for entry in entries.flatten() {
+            let home = entry.path();
+            // macOS bundles keep the actual home under Contents/Home
+            let mac_home = home.join("Contents").join("Home");
+            candidates.push(get_java_executable_from_home(if mac_home.is_dir() { &mac_home } else { &home }));
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    let mut installations = Vec::new();
+    for candidate in candidates {
+        if !candidate.is_file() {
+            continue;
+        }
+        let canonical = candidate.canonicalize().unwrap_or_else(|_| candidate.clone());
+        if !seen.insert(canonical) {
+            continue;
+        }
+        match get_java_version(&candidate) {
+            Ok((major, _, _, _)) => installations.push((candidate, major)),
+            Err(e) => trace!("Skipping {}: {}", candidate.display(), e),
+        }
+    }
+    debug!("Found {} Java installations.", installations.len());
+    installations
+}
+
+/// Finds the installation with the lowest major version that is at least `min_major`.
+/// The lowest match is preferred because older modded servers often break on newer Java.
+pub fn find_java_with_min_major(min_major: u32) -> AppResult<PathBuf> {
+    find_java_installations()
+        .into_iter()
+        .filter(|(_, major)| *major >= min_major)
+        .min_by_key(|(_, major)| *major)
+        .map(|(path, major)| {
+            info!("Selected Java {} at {} (needs {}+).", major, path.display(), min_major);
+            path
+        })
+        .ok_or(AppError::JavaNotFound)
+}
+
+/// Finds the most suitable Java installation for a specific Minecraft version.
+/// Falls back to `find_java_path` if the version's requirement is unknown.
 pub fn find_suitable_java(minecraft_version: &str) -> AppResult<PathBuf> {
-    warn!("find_suitable_java is a placeholder. Selecting first valid Java found.");
-    // TODO: Implement logic:
-    // 1. Get list of *all* valid Java installations found (modify find_java_path logic).
-    // 2. Get version for each installation using get_java_version.
-    // 3. Determine required Java version range based on minecraft_version string (e.g., "1.18.2", "1.16.5").
-    // 4. Select the best match from the available installations (e.g., highest compatible version).
-    // 5. Return the PathBuf for the selected installation.
+    match required_java_major(minecraft_version) {
+        Some(min_major) => find_java_with_min_major(min_major),
+        None => {
+            warn!("Unknown Java requirement for Minecraft {}, using default Java.", minecraft_version);
+            find_java_path()
+        }
+    }
+}
 
-    // For now, just return the first valid one found by find_java_path:
-    find_java_path()
-}
\ No newline at end of file
+/// Directories whose subdirectories are Java homes (one per installed JDK/JRE).
+fn get_jvm_root_directories() -> Vec<PathBuf> {
+    match consts::OS {
+        "windows" => vec![
+            PathBuf::from(r"C:\Program Files\Java"),
+            PathBuf::from(r"C:\Program Files\Eclipse Adoptium"),
+            PathBuf::from(r"C:\Program Files\Microsoft"),
+            PathBuf::from(r"C:\Program Files\Zulu"),
+        ],
+        "macos" => vec![PathBuf::from("/Library/Java/JavaVirtualMachines")],
+        _ => vec![PathBuf::from("/usr/lib/jvm"), PathBuf::from("/opt/java")],
+    }
+}
@@ -1,3 +1,5 @@
-﻿mod java_detector;
-mod fs_utils;
-mod process_utils;
\ No newline at end of file
+﻿pub mod java_detector;
+pub mod fs_utils;
+pub mod process_utils;
+pub mod uuid_resolver;
+pub mod download;
\ No newline at end of file
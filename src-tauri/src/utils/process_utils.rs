@@ -1,6 +1,6 @@
 use crate::error::{AppError, Result as AppResult};
 use log::{debug, trace};
-use sysinfo::{Pid, System, SystemExt};
+use sysinfo::{Pid, ProcessExt, System, SystemExt};
 
 /// Checks if a process with the given PID is currently running.
 /// Note: PID recycling means a new process could have the same PID later.
@@ -15,6 +15,102 @@ pub fn is_process_running(pid: u32) -> bool {
     is_running
 }
 
+/// Collects the given PID and all of its descendants from a refreshed `System`.
+/// The root PID is always the first entry, even if it is not (yet) listed.
+pub fn collect_process_tree(sys: &System, root: Pid) -> Vec<Pid> {
+    let mut tree = vec![root];
+    // Walk breadth-first; the process list is small enough that repeated scans are cheap.
+    let mut index = 0;
+    while index < tree.len() {
+        let parent = tree[index];
+        for (pid, process) in sys.processes() {
+            if process.parent() == Some(parent) && !tree.contains(pid) {
+                tree.push(*pid);
+            }
+        }
+        index += 1;
+    }
+    trace!("Process tree for PID {}: {:?}", root, tree);
+    tree
+}
+
+/// Returns the first JVM (`java`/`javaw`) in the process tree rooted at `root`. The server may
+/// be started through a wrapper script (Forge's `run.sh`), so the root isn't always the JVM.
+pub fn find_jvm_process(sys: &System, root: Pid) -> Option<Pid> {
+    collect_process_tree(sys, root).into_iter().find(|pid| {
+        sys.process(*pid).map_or(false, |process| {
+            let name = process.name().to_lowercase();
+            matches!(name.strip_suffix(".exe").unwrap_or(&name), "java" | "javaw")
+        })
+    })
+}
+
+/// Returns the number of threads of a process, read from `/proc/<pid>/status` on Linux.
+/// Returns None on other platforms or if the process is gone.
+pub fn count_threads(pid: u32) -> Option<u32> {
+    #[cfg(target_os = "linux")]
+    {
+        let status = std::fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix("Threads:"))
+            .and_then(|value| value.trim().parse::<u32>().ok())
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Returns the number of open file descriptors of a process (entries in `/proc/<pid>/fd`).
+/// Returns None on other platforms or if the directory cannot be read (e.g., permissions).
+pub fn count_open_files(pid: u32) -> Option<u32> {
+    #[cfg(target_os = "linux")]
+    {
+        std::fs::read_dir(format!("/proc/{}/fd", pid))
+            .ok()
+            .map(|entries| entries.count() as u32)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Reads cumulative network byte counters `(rx, tx)` as seen by a process on Linux.
+///
+/// Parses `/proc/<pid>/net/dev` and sums all interfaces except loopback. These are the
+/// counters of the process' network namespace, not per-process ones: unless the server runs
+/// in its own namespace (e.g. a container), they include all traffic of the host.
+pub fn read_namespace_network_bytes(pid: u32) -> Option<(u64, u64)> {
+    #[cfg(target_os = "linux")]
+    {
+        let content = std::fs::read_to_string(format!("/proc/{}/net/dev", pid)).ok()?;
+        let mut rx_total: u64 = 0;
+        let mut tx_total: u64 = 0;
+        // The first two lines are headers: "Inter-| Receive ..." and " face |bytes ..."
+        for line in content.lines().skip(2) {
+            let Some((iface, counters)) = line.split_once(':') else { continue };
+            if iface.trim() == "lo" {
+                continue;
+            }
+            let fields: Vec<&str> = counters.split_whitespace().collect();
+            // Field 0 is received bytes, field 8 is transmitted bytes
+            if fields.len() >= 9 {
+                rx_total = rx_total.saturating_add(fields[0].parse::<u64>().unwrap_or(0));
+                tx_total = tx_total.saturating_add(fields[8].parse::<u64>().unwrap_or(0));
+            }
+        }
+        Some((rx_total, tx_total))
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
 // Potential future functions:
 // pub fn kill_process(pid: u32, force: bool) -> AppResult<()> { ... }
-// pub fn get_process_resource_usage(pid: u32) -> AppResult<ProcessMetrics> { ... }
\ No newline at end of file
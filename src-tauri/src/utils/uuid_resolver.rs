@@ -0,0 +1,184 @@
+// src/utils/uuid_resolver.rs
+
+use crate::error::{AppError, Result};
+use crate::models::player_access::PlayerProfile;
+use crate::utils::fs_utils;
+use chrono::{DateTime, Duration, Utc};
+use log::{debug, error, warn};
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Default base URL of the Mojang profile API.
+pub const DEFAULT_MOJANG_API_URL: &str = "https://api.mojang.com";
+
+/// Cached online lookups are refreshed after this many days (names can change).
+const CACHE_TTL_DAYS: i64 = 30;
+
+/// Looks up the online (premium) profile for a player name.
+///
+/// Implementations return `Ok(None)` if no account has that name and an error only for
+/// lookup failures (network, rate limit), so callers can tell the two apart.
+pub trait UuidResolver: Send + Sync {
+    fn resolve(&self, name: &str) -> Result<Option<PlayerProfile>>;
+}
+
+/// Resolves names through the Mojang profile API (or a compatible mirror).
+pub struct MojangResolver {
+    base_url: String,
+    client: reqwest::blocking::Client,
+}
+
+#[derive(Deserialize)]
+struct MojangProfile {
+    id: String,
+    name: String,
+}
+
+impl MojangResolver {
+    pub fn new(base_url: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client: reqwest::blocking::Client::builder()
+                .user_agent(concat!("minecraft-server-manager/", env!("CARGO_PKG_VERSION")))
+                .timeout(std::time::Duration::from_secs(10))
+                .build()
+                .unwrap_or_else(|_| reqwest::blocking::Client::new()),
+        }
+    }
+}
+
+impl UuidResolver for MojangResolver {
+    fn resolve(&self, name: &str) -> Result<Option<PlayerProfile>> {
+        let url = format!("{}/users/profiles/minecraft/{}", self.base_url, name);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .map_err(|e| AppError::ServerError(format!("UUID lookup for '{}' failed: {}", name, e)))?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NO_CONTENT || status == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !status.is_success() {
+            return Err(AppError::ServerError(format!("UUID lookup for '{}' failed: HTTP {}", name, status)));
+        }
+        let profile: MojangProfile = response
+            .json()
+            .map_err(|e| AppError::ServerError(format!("Invalid UUID lookup response for '{}': {}", name, e)))?;
+        Ok(Some(PlayerProfile {
+            uuid: dashed_uuid(&profile.id)?,
+            name: profile.name,
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedProfile {
+    profile: PlayerProfile,
+    resolved_at: DateTime<Utc>,
+}
+
+/// Wraps an online resolver with a persistent cache (`uuid-cache.json`).
+///
+/// A stale cache entry is still used if the backend fails, since a slightly outdated name
+/// is better than none.
+pub struct CachingResolver {
+    backend: Box<dyn UuidResolver>,
+    cache: Mutex<HashMap<String, CachedProfile>>,
+    cache_path: PathBuf,
+}
+
+impl CachingResolver {
+    pub fn new(backend: Box<dyn UuidResolver>, cache_path: PathBuf) -> Self {
+        let cache = match fs_utils::read_file_to_string(&cache_path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                error!("UUID cache {} is corrupt, starting empty: {}", cache_path.display(), e);
+                HashMap::new()
+            }),
+            Err(_) => HashMap::new(),
+        };
+        Self {
+            backend,
+            cache: Mutex::new(cache),
+            cache_path,
+        }
+    }
+
+    /// Resolves an online profile, using the cache when it is fresh.
+    pub fn resolve_online(&self, name: &str) -> Result<Option<PlayerProfile>> {
+        let key = name.to_lowercase();
+        let cached = self.lock_cache()?.get(&key).cloned();
+        if let Some(entry) = &cached {
+            if Utc::now() - entry.resolved_at < Duration::days(CACHE_TTL_DAYS) {
+                return Ok(Some(entry.profile.clone()));
+            }
+        }
+
+        let result = self.backend.resolve(name);
+        match result {
+            Ok(Some(profile)) => {
+                debug!("Resolved {} to {}", profile.name, profile.uuid);
+                let mut cache = self.lock_cache()?;
+                cache.insert(key, CachedProfile { profile: profile.clone(), resolved_at: Utc::now() });
+                self.save(&cache);
+                Ok(Some(profile))
+            }
+            Ok(None) => Ok(None),
+            Err(e) => match cached {
+                Some(entry) => {
+                    warn!("{}; using cached UUID for {}", e, entry.profile.name);
+                    Ok(Some(entry.profile))
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    fn lock_cache(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, CachedProfile>>> {
+        self.cache
+            .lock()
+            .map_err(|e| AppError::LockError(format!("Failed to lock UUID cache: {}", e)))
+    }
+
+    fn save(&self, cache: &HashMap<String, CachedProfile>) {
+        let result = serde_json::to_string_pretty(cache)
+            .map_err(|e| AppError::ConfigError(format!("Failed to serialize UUID cache: {}", e)))
+            .and_then(|json| fs_utils::write_string_to_file_atomic(&self.cache_path, &json));
+        if let Err(e) = result {
+            error!("Failed to persist UUID cache: {}", e);
+        }
+    }
+}
+
+/// Computes the UUID an offline-mode server assigns to a name. Same as Java's
+/// `UUID.nameUUIDFromBytes("OfflinePlayer:" + name)`: an MD5 hash with the version set to 3.
+pub fn offline_uuid(name: &str) -> String {
+    let mut bytes: [u8; 16] = Md5::digest(format!("OfflinePlayer:{}", name).as_bytes()).into();
+    bytes[6] = (bytes[6] & 0x0f) | 0x30;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    format_uuid(&hex::encode(bytes))
+}
+
+/// Converts an undashed 32-digit UUID (as returned by the Mojang API) to the dashed form.
+pub fn dashed_uuid(id: &str) -> Result<String> {
+    let hex_id = id.replace('-', "").to_lowercase();
+    if hex_id.len() != 32 || !hex_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(AppError::ConfigError(format!("Invalid UUID '{}'", id)));
+    }
+    Ok(format_uuid(&hex_id))
+}
+
+fn format_uuid(hex_id: &str) -> String {
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex_id[0..8],
+        &hex_id[8..12],
+        &hex_id[12..16],
+        &hex_id[16..20],
+        &hex_id[20..32]
+    )
+}